@@ -0,0 +1,32 @@
+extern crate cursive;
+
+use cursive::Cursive;
+use cursive::traits::*;
+use cursive::views::{SelectView, SplitView, TextView};
+
+fn main() {
+    let mut siv = Cursive::new();
+
+    // A list of files on the left, and a preview on the right.
+    let files = SelectView::new()
+        .with_all_str(vec!["Cargo.toml", "Readme.md", "LICENSE"])
+        .on_select(|s, name: &String| {
+            s.call_on_id("preview", |view: &mut TextView| {
+                view.set_content(format!("Preview of {}", name))
+            });
+        });
+
+    // Ctrl+Left/Right moves the divider,
+    // Ctrl+Shift+Left/Right collapses one side.
+    siv.add_fullscreen_layer(SplitView::horizontal(files,
+                                                   TextView::new("Preview")
+                                                       .with_id("preview"))
+        .ratio(0.3)
+        .min_first(10)
+        .min_second(20)
+        .full_screen());
+
+    siv.add_global_callback('q', |s| s.quit());
+
+    siv.run();
+}
@@ -42,7 +42,9 @@ impl backend::Backend for Concrete {
 
     fn with_effect<F: FnOnce()>(&self, effect: Effect, f: F) {
         match effect {
-            Effect::Simple => f(),
+            // TODO: bold, italic and underline need separate fonts.
+            Effect::Simple | Effect::Bold | Effect::Italic |
+            Effect::Underline => f(),
             // TODO: how to do this correctly?`
             //       BLT itself doesn't do this kind of thing,
             //       we'd need the colours in our position,
@@ -72,6 +74,10 @@ impl backend::Backend for Concrete {
         terminal::refresh();
     }
 
+    fn write_escape(&mut self, _: &str) {
+        // There is no terminal to talk to.
+    }
+
     fn print_at(&self, (x, y): (usize, usize), text: &str) {
         terminal::print_xy(x as i32, y as i32, text);
     }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use theme::{BaseColor, Color};
 
 #[cfg(feature = "ncurses")]
@@ -38,3 +39,105 @@ fn find_closest(color: &Color) -> u8 {
         Color::RgbLowRes(r, g, b) => (16 + 36 * r + 6 * g + b) as u8,
     }
 }
+
+// Color pairs created on the fly for `with_any_color`.
+struct ColorPairs {
+    pairs: HashMap<(u8, u8), i16>,
+    // Number of pairs supported by the terminal.
+    max: i32,
+}
+
+impl ColorPairs {
+    fn new(max: i32) -> Self {
+        ColorPairs {
+            pairs: HashMap::new(),
+            max: max,
+        }
+    }
+
+    // Returns the pair for the given colors.
+    //
+    // Missing pairs are created with `init_pair`. When the terminal has no
+    // pair left, falls back to the closest existing one.
+    fn get<F>(&mut self, foreground: &Color, background: &Color,
+              init_pair: F)
+              -> i16
+        where F: FnOnce(i16, i16, i16)
+    {
+        let key = (find_closest(foreground), find_closest(background));
+        if let Some(&pair) = self.pairs.get(&key) {
+            return pair;
+        }
+
+        // The first pairs are used by the color styles.
+        let next = 16 + self.pairs.len() as i32;
+        if next < self.max && next <= i16::max_value() as i32 {
+            init_pair(next as i16, key.0 as i16, key.1 as i16);
+            self.pairs.insert(key, next as i16);
+            return next as i16;
+        }
+
+        self.pairs
+            .iter()
+            .min_by_key(|&(&(fg, bg), _)| {
+                distance(fg, key.0) + distance(bg, key.1)
+            })
+            .map(|(_, &pair)| pair)
+            .unwrap_or(0)
+    }
+}
+
+// Squared distance between two colors of the 256-color palette.
+fn distance(a: u8, b: u8) -> i32 {
+    let (a, b) = (palette_rgb(a), palette_rgb(b));
+    (a.0 - b.0).pow(2) + (a.1 - b.1).pow(2) + (a.2 - b.2).pow(2)
+}
+
+// Approximate RGB value of a color of the 256-color palette.
+fn palette_rgb(color: u8) -> (i32, i32, i32) {
+    const BASE: [(i32, i32, i32); 16] =
+        [(0, 0, 0), (128, 0, 0), (0, 128, 0), (128, 128, 0),
+         (0, 0, 128), (128, 0, 128), (0, 128, 128), (192, 192, 192),
+         (128, 128, 128), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+         (0, 0, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255)];
+
+    let level = |x: u8| if x == 0 { 0 } else { 55 + 40 * x as i32 };
+    match color {
+        0...15 => BASE[color as usize],
+        16...231 => {
+            let c = color - 16;
+            (level(c / 36), level(c / 6 % 6), level(c % 6))
+        }
+        _ => {
+            let gray = 8 + 10 * (color as i32 - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_pairs() {
+        let red = Color::Dark(BaseColor::Red);
+        let light_red = Color::Light(BaseColor::Red);
+        let black = Color::Dark(BaseColor::Black);
+        let blue = Color::Dark(BaseColor::Blue);
+
+        let mut created = Vec::new();
+        let mut pairs = ColorPairs::new(18);
+        assert_eq!(16, pairs.get(&red, &black, |p, _, _| created.push(p)));
+        assert_eq!(17, pairs.get(&blue, &black, |p, _, _| created.push(p)));
+        assert_eq!(16, pairs.get(&red, &black, |p, _, _| created.push(p)));
+        assert_eq!(vec![16, 17], created);
+
+        // No pair left: use the closest one.
+        assert_eq!(16,
+                   pairs.get(&light_red, &black, |_, _, _| panic!()));
+
+        let mut pairs = ColorPairs::new(16);
+        assert_eq!(0, pairs.get(&red, &black, |_, _, _| panic!()));
+    }
+}
@@ -1,13 +1,29 @@
 extern crate ncurses;
 
 
-use self::super::find_closest;
+use self::super::{ColorPairs, find_closest};
 use backend;
 use event::{Event, Key};
+use std::cell::RefCell;
+use std::io::{self, Write};
 use theme::{Color, ColorStyle, Effect};
 use utf8;
 
-pub struct Concrete;
+pub struct Concrete {
+    pairs: RefCell<ColorPairs>,
+}
+
+impl Concrete {
+    // Returns the color pair for the given colors, creating it if needed.
+    fn get_or_create_pair(&self, foreground: &Color, background: &Color)
+                          -> i16 {
+        self.pairs
+            .borrow_mut()
+            .get(foreground, background, |pair, fg, bg| {
+                ncurses::init_pair(pair, fg, bg);
+            })
+    }
+}
 
 impl backend::Backend for Concrete {
     fn init() -> Self {
@@ -19,13 +35,16 @@ impl backend::Backend for Concrete {
         ncurses::initscr();
         ncurses::keypad(ncurses::stdscr(), true);
         ncurses::noecho();
-        ncurses::cbreak();
+        // Raw mode, so keys like Ctrl-C or Ctrl-Z reach us as events
+        // instead of sending signals.
+        ncurses::raw();
         ncurses::start_color();
         ncurses::curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
         ncurses::wbkgd(ncurses::stdscr(),
                        ncurses::COLOR_PAIR(ColorStyle::Background.id()));
 
-        Concrete
+        let pairs = ColorPairs::new(ncurses::COLOR_PAIRS());
+        Concrete { pairs: RefCell::new(pairs) }
     }
 
     fn screen_size(&self) -> (usize, usize) {
@@ -66,13 +85,23 @@ impl backend::Backend for Concrete {
     }
 
     fn with_any_color<F: FnOnce()>(&self, fg_color: Color, bg_color: Color, f: F) {
+        let mut current_style: ncurses::attr_t = 0;
+        let mut current_color: i16 = 0;
+        ncurses::attr_get(&mut current_style, &mut current_color);
+
+        let pair = self.get_or_create_pair(&fg_color, &bg_color);
+        ncurses::attron(ncurses::COLOR_PAIR(pair));
         f();
+        ncurses::attron(current_style);
     }
 
     fn with_effect<F: FnOnce()>(&self, effect: Effect, f: F) {
         let style = match effect {
             Effect::Reverse => ncurses::A_REVERSE(),
             Effect::Simple => ncurses::A_NORMAL(),
+            Effect::Bold => ncurses::A_BOLD(),
+            Effect::Italic => ncurses::A_ITALIC(),
+            Effect::Underline => ncurses::A_UNDERLINE(),
         };
         ncurses::attron(style);
         f();
@@ -87,6 +116,15 @@ impl backend::Backend for Concrete {
         ncurses::refresh();
     }
 
+    fn write_escape(&mut self, sequence: &str) {
+        // ncurses is done writing after a refresh.
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        // If the terminal doesn't get it, there is nothing we can do.
+        let _ = stdout.write_all(sequence.as_bytes());
+        let _ = stdout.flush();
+    }
+
     fn print_at(&self, (x, y): (usize, usize), text: &str) {
         ncurses::mvaddstr(y as i32, x as i32, text);
     }
@@ -229,7 +267,8 @@ fn parse_ncurses_char(ch: i32) -> Event {
         f @ 313...324 => Event::Alt(Key::from_f((f - 312) as u8)),
         // Values 8-10 (H,I,J) are used by other commands,
         // so we probably won't receive them. Meh~
-        c @ 1...25 => Event::CtrlChar((b'a' + (c - 1) as u8) as char),
+        // In raw mode, this includes Ctrl-C, Ctrl-Z, Ctrl-S...
+        c @ 1...26 => Event::CtrlChar((b'a' + (c - 1) as u8) as char),
         other => {
             // Split the i32 into 4 bytes
             Event::Unknown((0..4)
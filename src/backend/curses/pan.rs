@@ -2,14 +2,30 @@ extern crate pancurses;
 
 
 
-use self::super::find_closest;
+use self::super::{ColorPairs, find_closest};
 use backend;
 use event::{Event, Key};
+use std::cell::RefCell;
+use std::cmp::min;
+use std::io::{self, Write};
 use theme::{Color, ColorStyle, Effect};
 use utf8;
 
 pub struct Concrete {
     window: pancurses::Window,
+    pairs: RefCell<ColorPairs>,
+}
+
+impl Concrete {
+    // Returns the color pair for the given colors, creating it if needed.
+    fn get_or_create_pair(&self, foreground: &Color, background: &Color)
+                          -> i16 {
+        self.pairs
+            .borrow_mut()
+            .get(foreground, background, |pair, fg, bg| {
+                pancurses::init_pair(pair, fg, bg);
+            })
+    }
 }
 
 impl backend::Backend for Concrete {
@@ -18,12 +34,19 @@ impl backend::Backend for Concrete {
         ::std::env::set_var("ESCDELAY", "25");
         window.keypad(true);
         pancurses::noecho();
-        pancurses::cbreak();
+        // Raw mode, so keys like Ctrl-C or Ctrl-Z reach us as events
+        // instead of sending signals.
+        pancurses::raw();
         pancurses::start_color();
         pancurses::curs_set(0);
         window.bkgd(pancurses::ColorPair(ColorStyle::Background.id() as u8));
 
-        Concrete { window: window }
+        Concrete {
+            window: window,
+            // `ColorPair` only takes a `u8`.
+            pairs: RefCell::new(ColorPairs::new(min(pancurses::COLOR_PAIRS(),
+                                                    256))),
+        }
     }
 
     fn screen_size(&self) -> (usize, usize) {
@@ -55,10 +78,23 @@ impl backend::Backend for Concrete {
         self.window.attron(pancurses::ColorPair(current_color_pair as u8));
     }
 
+    fn with_any_color<F: FnOnce()>(&self, fg_color: Color, bg_color: Color,
+                                   f: F) {
+        let (_, current_color_pair) = self.window.attrget();
+        let pair = self.get_or_create_pair(&fg_color, &bg_color);
+
+        self.window.attron(pancurses::ColorPair(pair as u8));
+        f();
+        self.window.attron(pancurses::ColorPair(current_color_pair as u8));
+    }
+
     fn with_effect<F: FnOnce()>(&self, effect: Effect, f: F) {
         let style = match effect {
             Effect::Reverse => pancurses::Attribute::Reverse,
             Effect::Simple => pancurses::Attribute::Normal,
+            Effect::Bold => pancurses::Attribute::Bold,
+            Effect::Italic => pancurses::Attribute::Italic,
+            Effect::Underline => pancurses::Attribute::Underline,
         };
         self.window.attron(style);
         f();
@@ -73,6 +109,15 @@ impl backend::Backend for Concrete {
         self.window.refresh();
     }
 
+    fn write_escape(&mut self, sequence: &str) {
+        // pancurses is done writing after a refresh.
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        // If the terminal doesn't get it, there is nothing we can do.
+        let _ = stdout.write_all(sequence.as_bytes());
+        let _ = stdout.flush();
+    }
+
     fn print_at(&self, (x, y): (usize, usize), text: &str) {
         self.window.mvaddstr(y as i32, x as i32, text);
     }
@@ -103,6 +148,10 @@ impl backend::Backend for Concrete {
                     })
                                         .unwrap())
                 }
+                pancurses::Input::Character(c) if 1 <= (c as u32) &&
+                                                  (c as u32) <= 26 => {
+                    Event::CtrlChar((b'a' + (c as u8) - 1) as char)
+                }
                 pancurses::Input::Character(c) => {
                     let mut bytes = [0u8; 4];
                     Event::Unknown(c.encode_utf8(&mut bytes)
@@ -30,6 +30,9 @@ pub trait Backend {
 
     fn print_at(&self, (usize, usize), &str);
 
+    /// Sends an escape sequence to the terminal, without changing the screen.
+    fn write_escape(&mut self, sequence: &str);
+
     fn poll_event(&self) -> event::Event;
     fn set_refresh_rate(&mut self, fps: u32);
     fn screen_size(&self) -> (usize, usize);
@@ -54,6 +54,9 @@ impl Effectable for theme::Effect {
         match *self {
             theme::Effect::Simple => (),
             theme::Effect::Reverse => print!("{}", tstyle::Invert),
+            theme::Effect::Bold => print!("{}", tstyle::Bold),
+            theme::Effect::Italic => print!("{}", tstyle::Italic),
+            theme::Effect::Underline => print!("{}", tstyle::Underline),
         }
     }
 
@@ -61,6 +64,9 @@ impl Effectable for theme::Effect {
         match *self {
             theme::Effect::Simple => (),
             theme::Effect::Reverse => print!("{}", tstyle::NoInvert),
+            theme::Effect::Bold => print!("{}", tstyle::NoBold),
+            theme::Effect::Italic => print!("{}", tstyle::NoItalic),
+            theme::Effect::Underline => print!("{}", tstyle::NoUnderline),
         }
     }
 }
@@ -170,6 +176,12 @@ impl backend::Backend for Concrete {
         self.terminal.flush().unwrap();
     }
 
+    fn write_escape(&mut self, sequence: &str) {
+        // If the terminal doesn't get it, there is nothing we can do.
+        let _ = self.terminal.write_all(sequence.as_bytes());
+        let _ = self.terminal.flush();
+    }
+
     fn print_at(&self, (x, y): (usize, usize), text: &str) {
         print!("{}{}",
                termion::cursor::Goto(1 + x as u16, 1 + y as u16),
@@ -220,7 +232,6 @@ fn map_key(event: TEvent) -> Event {
         TEvent::Key(TKey::Char('\n')) => Event::Key(Key::Enter),
         TEvent::Key(TKey::Char('\t')) => Event::Key(Key::Tab),
         TEvent::Key(TKey::Char(c)) => Event::Char(c),
-        TEvent::Key(TKey::Ctrl('c')) => Event::Exit,
         TEvent::Key(TKey::Ctrl(c)) => Event::CtrlChar(c),
         TEvent::Key(TKey::Alt(c)) => Event::AltChar(c),
         _ => Event::Unknown(vec![]),
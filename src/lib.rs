@@ -117,13 +117,16 @@ pub mod backend;
 
 use backend::Backend;
 
-use event::{Callback, Event, EventResult};
+use event::{Callback, Event, EventResult, Key};
 
-pub use printer::Printer;
+pub use printer::{LayoutArea, LayoutReport, Printer};
 
 use std::any::Any;
+use std::cell::RefCell;
+use std::cmp::max;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 
 use std::sync::mpsc;
 
@@ -158,6 +161,9 @@ pub struct Cursive {
 
     running: bool,
 
+    // If `true`, draw layout debugging information.
+    layout_debug: bool,
+
     backend: backend::Concrete,
 
     cb_source: mpsc::Receiver<Box<Fn(&mut Cursive) + Send>>,
@@ -168,6 +174,10 @@ new_default!(Cursive);
 
 impl Cursive {
     /// Creates a new Cursive root, and initialize the back-end.
+    ///
+    /// The terminal is put in raw mode: keys like `Ctrl-C` and `Ctrl-Z` are
+    /// delivered as events, and don't send signals. `Ctrl-C` quits, unless
+    /// the focused view uses it.
     pub fn new() -> Self {
         let mut backend = backend::Concrete::init();
 
@@ -185,6 +195,7 @@ impl Cursive {
             menubar: views::Menubar::new(),
             active_screen: 0,
             running: true,
+            layout_debug: false,
             cb_source: rx,
             cb_sink: tx,
             backend: backend,
@@ -192,6 +203,10 @@ impl Cursive {
 
         res.screens.push(views::StackView::new());
 
+        // The terminal is in raw mode, so Ctrl-C doesn't kill us anymore.
+        res.add_global_callback(Event::CtrlChar('c'), |s| s.quit());
+        res.add_global_callback(Key::F12, |s| s.toggle_layout_debug());
+
         res
     }
 
@@ -224,6 +239,48 @@ impl Cursive {
         self.menubar.autohide = autohide;
     }
 
+    /// Enables or disables layout debugging.
+    ///
+    /// When enabled, every view is outlined with its type name, and an
+    /// overlay at the bottom of the screen shows the constraint given to the
+    /// focused view, the size it required and the size it was given. It also
+    /// lists the detected violations of the `View` contract.
+    ///
+    /// Views are described by their container, see [`child_draw`].
+    ///
+    /// `F12` toggles it by default.
+    ///
+    /// [`child_draw`]: view/fn.child_draw.html
+    pub fn set_layout_debug(&mut self, enabled: bool) {
+        self.layout_debug = enabled;
+        view::set_recording(enabled);
+        self.clear();
+    }
+
+    /// Toggles layout debugging.
+    ///
+    /// It is bound to `F12` by default, and can be moved to another key:
+    ///
+    /// ```no_run
+    /// # extern crate cursive;
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// # fn main() {
+    /// let mut siv = Cursive::new();
+    /// siv.clear_global_callback(Key::F12);
+    /// siv.add_global_callback(Key::F9, |s| s.toggle_layout_debug());
+    /// # }
+    /// ```
+    pub fn toggle_layout_debug(&mut self) {
+        let enabled = !self.layout_debug;
+        self.set_layout_debug(enabled);
+    }
+
+    /// Returns `true` if layout debugging is enabled.
+    pub fn is_layout_debug(&self) -> bool {
+        self.layout_debug
+    }
+
     /// Access the menu tree used by the menubar.
     ///
     /// This allows to add menu items to the menubar.
@@ -475,6 +532,14 @@ impl Cursive {
         self.global_callbacks.insert(event.into(), Callback::from_fn(cb));
     }
 
+    /// Removes the global callback for the given event, if any.
+    ///
+    /// By default, `Ctrl-C` quits the application, and `F12` toggles layout
+    /// debugging.
+    pub fn clear_global_callback<E: Into<Event>>(&mut self, event: E) {
+        self.global_callbacks.remove(&event.into());
+    }
+
     /// Add a layer to the current screen.
     ///
     /// # Examples
@@ -529,7 +594,7 @@ impl Cursive {
 
     fn layout(&mut self) {
         let size = self.screen_size();
-        self.screen_mut().layout(size);
+        view::child_layout(self.screen_mut(), size);
     }
 
     fn draw(&mut self) {
@@ -559,11 +624,61 @@ impl Cursive {
 
         let selected = self.menubar.receive_events();
 
-        let printer =
+        let mut screen =
             printer.sub_printer(Vec2::new(0, offset), printer.size, !selected);
+        // Only record the areas of views inside the screen.
+        let report = Rc::new(RefCell::new(LayoutReport::default()));
+        if self.layout_debug {
+            screen.set_layout_report(report.clone());
+        }
         let id = self.active_screen;
-        self.screens[id].draw(&printer);
+        view::child_draw(&self.screens[id], &screen);
 
+        if self.layout_debug {
+            self.draw_layout_report(&printer, &report.borrow());
+        }
+    }
+
+    // Outlines every recorded area, and draws the layout debugging overlay
+    // at the bottom of the screen.
+    fn draw_layout_report(&self, printer: &Printer, report: &LayoutReport) {
+        for area in &report.areas {
+            let color = if area.invalid {
+                theme::ColorStyle::Highlight
+            } else {
+                theme::ColorStyle::TitleSecondary
+            };
+            let printer = printer.sub_printer(area.offset, area.size, false);
+            printer.with_color(color, |printer| {
+                if printer.size.x >= 2 && printer.size.y >= 2 {
+                    printer.print_box((0, 0), printer.size, false);
+                }
+                if let Some(ref name) = area.name {
+                    let x = if printer.size.x > 2 { 1 } else { 0 };
+                    let mark = if area.invalid { "!" } else { "" };
+                    printer.print((x, 0), &format!("{}{}", mark, name));
+                }
+            });
+        }
+
+        let focused = report.focused
+            .clone()
+            .unwrap_or_else(|| "No focused view".to_string());
+        let mut lines = vec![focused];
+        // Keep most of the screen visible.
+        let max_lines = max(1, printer.size.y / 3);
+        lines.extend(report.violations
+            .iter()
+            .take(max_lines - 1)
+            .map(|violation| format!("! {}", violation)));
+
+        let start = printer.size.y.saturating_sub(lines.len());
+        printer.with_color(theme::ColorStyle::Highlight, |printer| {
+            for (i, line) in lines.iter().enumerate() {
+                printer.print_hline((0, start + i), printer.size.x, " ");
+                printer.print((0, start + i), line);
+            }
+        });
     }
 
     /// Returns `true` until [`quit(&mut self)`] is called.
@@ -612,6 +727,11 @@ impl Cursive {
         self.draw();
         self.backend.refresh();
 
+        // Things like the OSC 52 clipboard talk to the terminal directly.
+        for escape in utils::clipboard::take_escapes() {
+            self.backend.write_escape(&escape);
+        }
+
         // Wait for next event.
         // (If set_fps was called, this returns -1 now and then)
         let event = self.backend.poll_event();
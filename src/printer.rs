@@ -2,16 +2,75 @@
 
 
 use backend::{self, Backend};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::cmp::min;
 use std::rc::Rc;
 
-use theme::{BorderStyle, ColorStyle, Color, Effect, Theme};
+use theme::{BorderStyle, ColorStyle, ColorType, Color, Effect, Effects,
+            Palette, Style, Theme};
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use utils::prefix;
+use utils::{prefix, StyledString};
 use vec::Vec2;
 
+/// Information collected while drawing with layout debugging enabled.
+///
+/// See [`Cursive::set_layout_debug`].
+///
+/// [`Cursive::set_layout_debug`]: struct.Cursive.html#method.set_layout_debug
+#[derive(Default, Debug)]
+pub struct LayoutReport {
+    /// Description of the innermost focused view.
+    pub focused: Option<String>,
+    /// Contract violations detected since the last draw.
+    pub violations: Vec<String>,
+    /// Areas given to views, in drawing order.
+    pub areas: Vec<LayoutArea>,
+}
+
+/// Area of the screen given to a view, recorded for layout debugging.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutArea {
+    /// Position of the top-left corner, from the top-left of the screen.
+    pub offset: Vec2,
+    /// Size of the area.
+    pub size: Vec2,
+    /// Name of the view drawn there, if known.
+    pub name: Option<String>,
+    /// `true` if the view drawn there violates the `View` contract.
+    pub invalid: bool,
+}
+
+impl LayoutReport {
+    /// Names the given area, recording it if needed.
+    pub fn name_area(&mut self, offset: Vec2, size: Vec2, name: &str,
+                     invalid: bool) {
+        let area = self.area(offset, size);
+        area.name = Some(name.to_string());
+        area.invalid = invalid;
+    }
+
+    // Returns the recorded area with this position, adding it if needed.
+    fn area(&mut self, offset: Vec2, size: Vec2) -> &mut LayoutArea {
+        let i = match self.areas
+            .iter()
+            .position(|area| area.offset == offset && area.size == size) {
+            Some(i) => i,
+            None => {
+                self.areas.push(LayoutArea {
+                    offset: offset,
+                    size: size,
+                    name: None,
+                    invalid: false,
+                });
+                self.areas.len() - 1
+            }
+        };
+        &mut self.areas[i]
+    }
+}
+
 /// Convenient interface to draw on a subset of the screen.
 pub struct Printer<'a> {
     /// Offset into the window this printer should start drawing at.
@@ -25,6 +84,8 @@ pub struct Printer<'a> {
 
     /// `true` if nothing has been drawn yet.
     new: Rc<Cell<bool>>,
+    /// Collects layout information when layout debugging is enabled.
+    layout_report: Option<Rc<RefCell<LayoutReport>>>,
     /// Backend used to actually draw things
     backend: &'a backend::Concrete,
 }
@@ -43,10 +104,26 @@ impl<'a> Printer<'a> {
             focused: true,
             theme: theme,
             new: Rc::new(Cell::new(true)),
+            layout_report: None,
             backend: backend,
         }
     }
 
+    /// Enables layout debugging, collecting information in `report`.
+    #[doc(hidden)]
+    pub fn set_layout_report(&mut self, report: Rc<RefCell<LayoutReport>>) {
+        self.layout_report = Some(report);
+    }
+
+    /// Returns the layout report, if layout debugging is enabled.
+    ///
+    /// Views use it to describe their layout, see [`LayoutInfo::report`].
+    ///
+    /// [`LayoutInfo::report`]: view/struct.LayoutInfo.html#method.report
+    pub fn layout_report(&self) -> Option<&RefCell<LayoutReport>> {
+        self.layout_report.as_ref().map(|report| &**report)
+    }
+
     /// Clear the screen.
     ///
     /// It will discard anything drawn before.
@@ -82,6 +159,23 @@ impl<'a> Printer<'a> {
         self.backend.print_at((p.x, p.y), text);
     }
 
+    /// Prints a styled text at the given position.
+    ///
+    /// Each span is printed with its own style.
+    pub fn print_styled<S: Into<Vec2>>(&self, pos: S, text: &StyledString) {
+        self.print_spans(pos, &text.spans());
+    }
+
+    /// Prints some spans of text one after the other, each with its style.
+    pub fn print_spans<S: Into<Vec2>>(&self, pos: S,
+                                      spans: &[(&str, Style)]) {
+        let mut pos = pos.into();
+        for &(text, style) in spans {
+            self.with_style(style, |printer| printer.print(pos, text));
+            pos.x += text.width();
+        }
+    }
+
     /// Prints a vertical line using the given character.
     pub fn print_vline<T: Into<Vec2>>(&self, start: T, len: usize, c: &str) {
         self.new.set(false);
@@ -152,6 +246,57 @@ impl<'a> Printer<'a> {
         self.backend.with_effect(effect, || f(self));
     }
 
+    /// Call the given closure with a printer using all the given effects.
+    pub fn with_effects<F>(&self, effects: Effects, f: F)
+        where F: FnOnce(&Printer)
+    {
+        let mut f = Some(f);
+        self.with_each_effect(&effects.to_vec(),
+                              &mut |printer| (f.take().unwrap())(printer));
+    }
+
+    fn with_each_effect(&self, effects: &[Effect], f: &mut FnMut(&Printer)) {
+        match effects.split_first() {
+            None => f(self),
+            Some((&effect, rest)) => {
+                self.with_effect(effect,
+                                 |printer| printer.with_each_effect(rest, f))
+            }
+        }
+    }
+
+    /// Call the given closure with a printer using the given style.
+    ///
+    /// Raw colors are printed on the theme's view background, unless the
+    /// style has a background. A background without a foreground color
+    /// uses the primary color.
+    pub fn with_style<F>(&self, style: Style, f: F)
+        where F: FnOnce(&Printer)
+    {
+        let effects = style.effects;
+        match (style.color, style.background) {
+            (None, None) => self.with_effects(effects, f),
+            (Some(ColorType::Style(color)), None) => {
+                self.with_color(color,
+                                |printer| printer.with_effects(effects, f))
+            }
+            (color, background) => {
+                let colors = &self.theme.colors;
+                let front = match color {
+                    Some(ColorType::Color(color)) => color,
+                    Some(ColorType::Style(color)) => {
+                        front_color(colors, color)
+                    }
+                    None => colors.primary,
+                };
+                let back = background.unwrap_or(colors.view);
+                self.with_any_color(front, back, |printer| {
+                    printer.with_effects(effects, f)
+                })
+            }
+        }
+    }
+
     /// Prints a rectangular box.
     ///
     /// If `invert` is `true`, and the theme uses `Outset` borders, then the
@@ -263,7 +408,7 @@ impl<'a> Printer<'a> {
                                                      -> Printer<'a> {
         let size = size.into();
         let offset = offset.into().or_min(self.size);
-        Printer {
+        let printer = Printer {
             offset: self.offset + offset,
             // We can't be larger than what remains
             size: Vec2::min(self.size - offset, size),
@@ -271,7 +416,15 @@ impl<'a> Printer<'a> {
             theme: self.theme.clone(),
             backend: self.backend,
             new: self.new.clone(),
+            layout_report: self.layout_report.clone(),
+        };
+
+        // Containers give each child its own sub-printer.
+        if let Some(report) = printer.layout_report() {
+            report.borrow_mut().area(printer.offset, printer.size);
         }
+
+        printer
     }
 
     /// Returns a sub-printer with the given offset.
@@ -279,3 +432,18 @@ impl<'a> Printer<'a> {
         self.sub_printer(offset, self.size, focused)
     }
 }
+
+// Returns the foreground color of a color style.
+fn front_color(colors: &Palette, style: ColorStyle) -> Color {
+    match style {
+        ColorStyle::Background |
+        ColorStyle::Highlight |
+        ColorStyle::HighlightInactive => colors.view,
+        ColorStyle::Shadow => colors.shadow,
+        ColorStyle::Primary => colors.primary,
+        ColorStyle::Secondary => colors.secondary,
+        ColorStyle::Tertiary => colors.tertiary,
+        ColorStyle::TitlePrimary => colors.title_primary,
+        ColorStyle::TitleSecondary => colors.title_secondary,
+    }
+}
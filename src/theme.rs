@@ -73,6 +73,9 @@
 //!
 //! On top of a color style, some effects can be applied on cells: `Reverse`,
 //! for instance, swaps the foreground and background colors of a cell.
+//! Several effects can be combined with [`Effects`].
+//!
+//! [`Effects`]: struct.Effects.html
 //!
 //! # Themes
 //!
@@ -124,13 +127,165 @@ use std::path::Path;
 use toml;
 
 /// Text effect
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Effect {
     /// No effect
     Simple,
     /// Reverses foreground and background colors
-    Reverse, 
-    // TODO: bold, italic, underline
+    Reverse,
+    /// Prints text in bold
+    Bold,
+    /// Prints text in italic
+    Italic,
+    /// Underlines text
+    Underline,
+}
+
+/// Set of text effects.
+///
+/// `Effect::Simple` is the empty set: inserting or removing it does
+/// nothing.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::theme::{Effect, Effects};
+/// let mut effects = Effects::from(Effect::Bold);
+/// effects.insert(Effect::Underline);
+/// effects.remove(Effect::Bold);
+/// assert_eq!(vec![Effect::Underline], effects.to_vec());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Effects {
+    bits: u8,
+}
+
+impl Effects {
+    /// Returns an empty set.
+    pub fn empty() -> Self {
+        Effects { bits: 0 }
+    }
+
+    fn bit(effect: Effect) -> u8 {
+        match effect {
+            Effect::Simple => 0,
+            Effect::Reverse => 1,
+            Effect::Bold => 2,
+            Effect::Italic => 4,
+            Effect::Underline => 8,
+        }
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns `true` if the set contains `effect`.
+    pub fn contains(&self, effect: Effect) -> bool {
+        let bit = Effects::bit(effect);
+        self.bits & bit == bit
+    }
+
+    /// Adds `effect` to the set.
+    pub fn insert(&mut self, effect: Effect) {
+        self.bits |= Effects::bit(effect);
+    }
+
+    /// Removes `effect` from the set.
+    pub fn remove(&mut self, effect: Effect) {
+        self.bits &= !Effects::bit(effect);
+    }
+
+    /// Returns the effects in either `self` or `other`.
+    pub fn union(self, other: Effects) -> Self {
+        Effects { bits: self.bits | other.bits }
+    }
+
+    /// Returns the effects in the set.
+    pub fn to_vec(&self) -> Vec<Effect> {
+        [Effect::Reverse, Effect::Bold, Effect::Italic, Effect::Underline]
+            .iter()
+            .cloned()
+            .filter(|&effect| self.contains(effect))
+            .collect()
+    }
+}
+
+impl From<Effect> for Effects {
+    fn from(effect: Effect) -> Self {
+        Effects { bits: Effects::bit(effect) }
+    }
+}
+
+/// Color used to print some text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorType {
+    /// Uses a color style from the palette.
+    Style(ColorStyle),
+    /// Uses the given foreground color, on the `view` background.
+    Color(Color),
+}
+
+/// Colors and effects used to print some text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    /// Color to use. `None` keeps the current color.
+    pub color: Option<ColorType>,
+    /// Background color to use. `None` keeps the current background.
+    pub background: Option<Color>,
+    /// Effects to apply.
+    pub effects: Effects,
+}
+
+impl Style {
+    /// Returns a style which doesn't change anything.
+    pub fn none() -> Self {
+        Style {
+            color: None,
+            background: None,
+            effects: Effects::empty(),
+        }
+    }
+
+    /// Returns `true` if this style doesn't change anything.
+    pub fn is_none(&self) -> bool {
+        *self == Style::none()
+    }
+
+    /// Returns `self` with the given color.
+    pub fn with_color(self, color: ColorType) -> Self {
+        Style { color: Some(color), ..self }
+    }
+
+    /// Returns `self` with the given background color.
+    pub fn with_background(self, color: Color) -> Self {
+        Style { background: Some(color), ..self }
+    }
+
+    /// Returns `self` with the given effect added.
+    pub fn with_effect(mut self, effect: Effect) -> Self {
+        self.effects.insert(effect);
+        self
+    }
+}
+
+impl From<Effect> for Style {
+    fn from(effect: Effect) -> Self {
+        Style::none().with_effect(effect)
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        Style::none().with_color(ColorType::Color(color))
+    }
+}
+
+impl From<ColorStyle> for Style {
+    fn from(color: ColorStyle) -> Self {
+        Style::none().with_color(ColorType::Style(color))
+    }
 }
 
 /// Possible color style for a cell.
@@ -138,7 +293,7 @@ pub enum Effect {
 /// Represents a color pair role to use when printing something.
 ///
 /// The current theme will assign each role a foreground and background color.
-#[derive(Clone,Copy)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 pub enum ColorStyle {
     /// Application background, where no view is present.
     Background,
@@ -352,7 +507,7 @@ fn load_color(target: &mut Color, value: Option<&toml::Value>) -> bool {
 }
 
 /// One of the 8 base colors.
-#[derive(Clone,Copy,Debug)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 pub enum BaseColor {
     /// Black color
     ///
@@ -389,7 +544,7 @@ pub enum BaseColor {
 }
 
 /// Represents a color used by the theme.
-#[derive(Clone,Copy,Debug)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 pub enum Color {
     /// One of the 8 base colors.
     Dark(BaseColor),
@@ -429,7 +584,12 @@ impl From<toml::de::Error> for Error {
 }
 
 impl Color {
-    fn parse(value: &str) -> Option<Self> {
+    /// Parses a color name, like `"red"`, `"light blue"` or `"#ff8800"`.
+    ///
+    /// This uses the same syntax as theme files.
+    ///
+    /// Returns `None` if the value is not a valid color.
+    pub fn parse(value: &str) -> Option<Self> {
         Some(match value {
             "black" => Color::Dark(BaseColor::Black),
             "red" => Color::Dark(BaseColor::Red),
@@ -455,11 +615,15 @@ impl Color {
         if value.starts_with('#') {
 
             let value = &value[1..];
+            // The slices below need ASCII hex digits.
+            if !value.chars().all(|c| c.is_digit(16)) {
+                return None;
+            }
             // Compute per-color length, and amplitude
             let (l, multiplier) = match value.len() {
                 6 => (2, 1),
                 3 => (1, 17),
-                _ => panic!("Cannot parse color: {}", value),
+                _ => return None,
             };
             let r = load_hex(&value[0..l]) * multiplier;
             let g = load_hex(&value[l..2 * l]) * multiplier;
@@ -0,0 +1,257 @@
+//! Parse ANSI escape sequences into styled text.
+//!
+//! This is useful to show the output of programs which print colors, like
+//! `cargo` or `git`, in a [`TextView`].
+//!
+//! [`TextView`]: ../../views/struct.TextView.html
+
+use std::cmp::min;
+use std::str::Chars;
+use std::iter::Peekable;
+use theme::{BaseColor, Color, ColorType, Effect, Style};
+use utils::StyledString;
+
+/// Parses text containing ANSI escape sequences.
+///
+/// SGR sequences (`ESC [ ... m`) are turned into styles:
+///
+/// * 16 colors (`30`-`37`, `90`-`97`), 256 colors (`38;5;n`)
+///   and true colors (`38;2;r;g;b`) for the foreground.
+/// * The same for the background (`40`-`47`, `100`-`107`, `48;5;n` and
+///   `48;2;r;g;b`).
+/// * Bold (`1`), italic (`3`), underline (`4`) and reverse (`7`),
+///   and the codes turning each of them off (`22`, `23`, `24`, `27`).
+/// * Resets (`0`, `39`, `49`).
+///
+/// Every other escape sequence (cursor movement, screen clearing, window
+/// titles, ...) and control character except `\n` and `\t` is removed.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::ansi;
+/// let text = ansi::parse("\x1b[1;31merror\x1b[0m: oops");
+/// assert_eq!("error: oops", text.source());
+/// ```
+pub fn parse(text: &str) -> StyledString {
+    let mut result = StyledString::new();
+    let mut current = String::new();
+    let mut style = Style::none();
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                let params = match read_escape(&mut chars) {
+                    Some(params) => params,
+                    None => continue,
+                };
+                let next = apply_sgr(style, &params);
+                if next != style {
+                    result.append_styled(current, style);
+                    current = String::new();
+                    style = next;
+                }
+            }
+            '\n' | '\t' => current.push(c),
+            c if c.is_control() => (),
+            c => current.push(c),
+        }
+    }
+    result.append_styled(current, style);
+
+    result
+}
+
+// Reads an escape sequence, after the `ESC` character.
+//
+// Returns the parameters if this was a SGR sequence.
+fn read_escape(chars: &mut Peekable<Chars>) -> Option<String> {
+    match chars.next() {
+        Some('[') => {
+            // Control sequence: parameters, intermediates, then a final
+            // byte in `@`..`~`.
+            let mut params = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '@'...'~' if c == 'm' => return Some(params),
+                    '@'...'~' => return None,
+                    '\x1b' => return None,
+                    c => params.push(c),
+                }
+            }
+            None
+        }
+        Some(']') | Some('P') | Some('_') | Some('^') => {
+            // String sequence: ends with BEL or `ESC \`.
+            while let Some(c) = chars.next() {
+                match c {
+                    '\x07' => break,
+                    '\x1b' => {
+                        if chars.peek() == Some(&'\\') {
+                            chars.next();
+                        }
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+            None
+        }
+        Some('(') | Some(')') | Some('#') => {
+            // Character set selection: one more character.
+            chars.next();
+            None
+        }
+        _ => None,
+    }
+}
+
+// Returns the style after applying the given SGR parameters.
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let codes: Vec<u32> = params.split(|c| c == ';' || c == ':')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+
+    let mut codes = codes.into_iter();
+    while let Some(code) = codes.next() {
+        match code {
+            0 => style = Style::none(),
+            1 => style.effects.insert(Effect::Bold),
+            3 => style.effects.insert(Effect::Italic),
+            4 => style.effects.insert(Effect::Underline),
+            7 => style.effects.insert(Effect::Reverse),
+            22 => style.effects.remove(Effect::Bold),
+            23 => style.effects.remove(Effect::Italic),
+            24 => style.effects.remove(Effect::Underline),
+            27 => style.effects.remove(Effect::Reverse),
+            30...37 => {
+                let color = Color::Dark(base_color(code - 30));
+                style = style.with_color(ColorType::Color(color));
+            }
+            90...97 => {
+                let color = Color::Light(base_color(code - 90));
+                style = style.with_color(ColorType::Color(color));
+            }
+            39 => style.color = None,
+            40...47 => {
+                style.background = Some(Color::Dark(base_color(code - 40)))
+            }
+            100...107 => {
+                style.background = Some(Color::Light(base_color(code - 100)))
+            }
+            49 => style.background = None,
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(color_256),
+                    Some(2) => {
+                        // Out-of-range components are clamped.
+                        let mut next = || min(codes.next().unwrap_or(0), 255);
+                        let (r, g, b) = (next(), next(), next());
+                        Some(Color::Rgb(r as u8, g as u8, b as u8))
+                    }
+                    _ => None,
+                };
+                match (code, color) {
+                    (38, Some(color)) => {
+                        style = style.with_color(ColorType::Color(color))
+                    }
+                    (_, Some(color)) => style.background = Some(color),
+                    _ => (),
+                }
+            }
+            // Others are not supported.
+            _ => (),
+        }
+    }
+
+    style
+}
+
+// Returns the color with the given index in the 256-colors palette.
+fn color_256(n: u32) -> Color {
+    match n {
+        0...7 => Color::Dark(base_color(n)),
+        8...15 => Color::Light(base_color(n - 8)),
+        16...231 => {
+            let n = (n - 16) as u8;
+            Color::RgbLowRes(n / 36, (n / 6) % 6, n % 6)
+        }
+        232...255 => {
+            let gray = (8 + 10 * (n - 232)) as u8;
+            Color::Rgb(gray, gray, gray)
+        }
+        _ => Color::Dark(BaseColor::White),
+    }
+}
+
+fn base_color(n: u32) -> BaseColor {
+    match n {
+        0 => BaseColor::Black,
+        1 => BaseColor::Red,
+        2 => BaseColor::Green,
+        3 => BaseColor::Yellow,
+        4 => BaseColor::Blue,
+        5 => BaseColor::Magenta,
+        6 => BaseColor::Cyan,
+        _ => BaseColor::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{BaseColor, Color, Effect, Style};
+
+    #[test]
+    fn colors() {
+        let text = parse("\x1b[31ma\x1b[38;5;196mb\x1b[38;2;1;2;3mc\x1b[0md");
+        assert_eq!(vec![("a", Style::from(Color::Dark(BaseColor::Red))),
+                        ("b", Style::from(Color::RgbLowRes(5, 0, 0))),
+                        ("c", Style::from(Color::Rgb(1, 2, 3))),
+                        ("d", Style::none())],
+                   text.spans());
+
+        let text = parse("\x1b[38;2;300;0;4096ma\x1b[48;5;300mb");
+        let clamped = Style::from(Color::Rgb(255, 0, 255));
+        assert_eq!(vec![("a", clamped),
+                        ("b", clamped.with_background(
+                            Color::Dark(BaseColor::White)))],
+                   text.spans());
+    }
+
+    #[test]
+    fn effects() {
+        let text = parse("\x1b[1;92mok\x1b[22m!");
+        let green = Style::from(Color::Light(BaseColor::Green));
+        assert_eq!(vec![("ok", green.with_effect(Effect::Bold)),
+                        ("!", green)],
+                   text.spans());
+
+        // Effects add up, and each is turned off separately.
+        let text = parse("\x1b[1;4ma\x1b[24mb\x1b[3;22mc");
+        let bold = Style::from(Effect::Bold);
+        assert_eq!(vec![("a", bold.with_effect(Effect::Underline)),
+                        ("b", bold),
+                        ("c", Style::from(Effect::Italic))],
+                   text.spans());
+    }
+
+    #[test]
+    fn background() {
+        let text = parse("\x1b[41ma\x1b[33;48;5;9mb\x1b[49mc");
+        let red = Style::none().with_background(Color::Dark(BaseColor::Red));
+        let yellow = Style::from(Color::Dark(BaseColor::Yellow));
+        assert_eq!(vec![("a", red),
+                        ("b", yellow.with_background(
+                            Color::Light(BaseColor::Red))),
+                        ("c", yellow)],
+                   text.spans());
+    }
+
+    #[test]
+    fn strip_others() {
+        let text = parse("\x1b[2J\x1b[1;1Ha\x1b]0;title\x07b\rc\x1b[");
+        assert_eq!(StyledString::plain("abc"), text);
+    }
+}
@@ -0,0 +1,229 @@
+//! Clipboard providers for cut, copy and paste.
+//!
+//! Views like [`TextArea`] use a [`Clipboard`] to exchange text. By default,
+//! they share an in-process clipboard (see [`local`]). To exchange text with
+//! other applications, use either:
+//!
+//! * [`Osc52Clipboard`], which asks the terminal to copy the text (this
+//!   works over ssh, but paste only gets text copied from this process).
+//! * [`CommandClipboard`], which runs external commands like `xclip` or
+//!   `pbcopy`.
+//!
+//! [`TextArea`]: ../../views/struct.TextArea.html
+//! [`Clipboard`]: trait.Clipboard.html
+//! [`local`]: fn.local.html
+//! [`Osc52Clipboard`]: struct.Osc52Clipboard.html
+//! [`CommandClipboard`]: struct.CommandClipboard.html
+
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+/// Stores text for cut, copy and paste.
+pub trait Clipboard {
+    /// Returns the text currently in the clipboard, if any.
+    fn get(&mut self) -> Option<String>;
+
+    /// Replaces the content of the clipboard.
+    fn set(&mut self, text: &str);
+}
+
+thread_local! {
+    static LOCAL: Rc<RefCell<LocalClipboard>> =
+        Rc::new(RefCell::new(LocalClipboard::new()));
+
+    // Escape sequences waiting to be sent to the terminal.
+    static ESCAPES: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+/// Returns the escape sequences waiting to be sent to the terminal.
+///
+/// `Cursive` writes them through the backend after each refresh.
+#[doc(hidden)]
+pub fn take_escapes() -> Vec<String> {
+    ESCAPES.with(|escapes| escapes.borrow_mut().drain(..).collect())
+}
+
+/// Returns the in-process clipboard shared by default between views.
+pub fn local() -> Rc<RefCell<Clipboard>> {
+    LOCAL.with(|clipboard| clipboard.clone())
+}
+
+/// Clipboard only visible in this process.
+#[derive(Default)]
+pub struct LocalClipboard {
+    content: Option<String>,
+}
+
+impl LocalClipboard {
+    /// Creates a new, empty clipboard.
+    pub fn new() -> Self {
+        LocalClipboard { content: None }
+    }
+}
+
+impl Clipboard for LocalClipboard {
+    fn get(&mut self) -> Option<String> {
+        self.content.clone()
+    }
+
+    fn set(&mut self, text: &str) {
+        self.content = Some(text.to_string());
+    }
+}
+
+/// Clipboard using the OSC 52 terminal escape sequence.
+///
+/// Copied text is sent to the terminal, which stores it in the system
+/// clipboard, if supported. Most terminals don't let applications read the
+/// clipboard, so pasting uses the last text copied from this process.
+///
+/// The escape sequence is not written right away: the backend sends it
+/// after the next refresh, so it doesn't get mixed with the screen
+/// updates. It is only sent while a `Cursive` application is running.
+#[derive(Default)]
+pub struct Osc52Clipboard {
+    local: LocalClipboard,
+}
+
+impl Osc52Clipboard {
+    /// Creates a new OSC 52 clipboard.
+    pub fn new() -> Self {
+        Osc52Clipboard { local: LocalClipboard::new() }
+    }
+}
+
+impl Clipboard for Osc52Clipboard {
+    fn get(&mut self) -> Option<String> {
+        self.local.get()
+    }
+
+    fn set(&mut self, text: &str) {
+        self.local.set(text);
+
+        let escape = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+        ESCAPES.with(|escapes| escapes.borrow_mut().push(escape));
+    }
+}
+
+/// Clipboard using external commands.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::clipboard::CommandClipboard;
+/// let clipboard = CommandClipboard::new(&["xclip", "-selection", "c"],
+///                                       &["xclip", "-selection", "c", "-o"]);
+/// ```
+pub struct CommandClipboard {
+    copy: Vec<String>,
+    paste: Vec<String>,
+}
+
+impl CommandClipboard {
+    /// Creates a clipboard using the given commands.
+    ///
+    /// `copy` gets the text on its standard input, and `paste` should print
+    /// the clipboard content on its standard output.
+    pub fn new(copy: &[&str], paste: &[&str]) -> Self {
+        CommandClipboard {
+            copy: copy.iter().map(|s| s.to_string()).collect(),
+            paste: paste.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+// Builds a command from a list of arguments.
+fn command(args: &[String]) -> Option<Command> {
+    args.split_first().map(|(program, args)| {
+        let mut command = Command::new(program);
+        command.args(args).stderr(Stdio::null());
+        command
+    })
+}
+
+impl Clipboard for CommandClipboard {
+    fn get(&mut self) -> Option<String> {
+        command(&self.paste)
+            .and_then(|mut command| {
+                command.stdin(Stdio::null()).stdout(Stdio::piped());
+                command.spawn().ok()
+            })
+            .and_then(|mut child| {
+                let mut text = String::new();
+                let read = child.stdout
+                    .take()
+                    .map(|mut out| out.read_to_string(&mut text).is_ok())
+                    .unwrap_or(false);
+                match child.wait() {
+                    Ok(status) if status.success() && read => Some(text),
+                    _ => None,
+                }
+            })
+    }
+
+    fn set(&mut self, text: &str) {
+        let child = command(&self.copy).and_then(|mut command| {
+            command.stdin(Stdio::piped()).stdout(Stdio::null());
+            command.spawn().ok()
+        });
+        if let Some(mut child) = child {
+            if let Some(mut input) = child.stdin.take() {
+                let _ = input.write_all(text.as_bytes());
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+// Encodes bytes in base64, as required by OSC 52.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                                      abcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0],
+                 chunk.get(1).cloned().unwrap_or(0),
+                 chunk.get(2).cloned().unwrap_or(0)];
+        let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(n >> (18 - 6 * i)) & 63] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!("", base64(b""));
+        assert_eq!("Zg==", base64(b"f"));
+        assert_eq!("Zm8=", base64(b"fo"));
+        assert_eq!("Zm9vYmFy", base64(b"foobar"));
+    }
+
+    #[test]
+    fn osc52() {
+        let mut clipboard = Osc52Clipboard::new();
+        clipboard.set("foo");
+        assert_eq!(Some("foo".to_string()), clipboard.get());
+        // Written later, by the backend.
+        assert_eq!(vec!["\x1b]52;c;Zm9v\x07".to_string()], take_escapes());
+        assert!(take_escapes().is_empty());
+    }
+
+    #[test]
+    fn shared_local() {
+        local().borrow_mut().set("a");
+        assert_eq!(Some("a".to_string()), local().borrow_mut().get());
+    }
+}
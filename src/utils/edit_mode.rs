@@ -0,0 +1,868 @@
+//! Editing modes for text views.
+//!
+//! An [`EditMode`] turns key presses into editing [`Action`]s, which text
+//! views like [`TextArea`] and [`EditView`] then apply. Two modes are
+//! available:
+//!
+//! * [`EmacsMode`], with the readline shortcuts.
+//! * [`ViMode`], a minimal modal editor.
+//!
+//! [`EditMode`]: trait.EditMode.html
+//! [`Action`]: enum.Action.html
+//! [`TextArea`]: ../../views/struct.TextArea.html
+//! [`EditView`]: ../../views/struct.EditView.html
+//! [`EmacsMode`]: struct.EmacsMode.html
+//! [`ViMode`]: struct.ViMode.html
+
+use event::{Event, Key};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::{max, min};
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+use utils::clipboard::Clipboard;
+
+/// Where the cursor moves.
+///
+/// Words are runs of letters, digits and underscores, or runs of other
+/// symbols.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Motion {
+    /// One character to the left, in the same line.
+    Left,
+    /// One character to the right, in the same line.
+    Right,
+    /// One row up.
+    Up,
+    /// One row down.
+    Down,
+    /// Beginning of the line.
+    LineStart,
+    /// End of the line.
+    LineEnd,
+    /// Beginning of the next word.
+    WordForward,
+    /// Beginning of the current or previous word.
+    WordBackward,
+    /// Right after the end of the current or next word.
+    WordEnd,
+    /// Last character of the next word, or of the current one when not
+    /// already there.
+    WordLastChar,
+}
+
+/// Editing operation applied by a view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Moves the cursor a number of times, and clears the selection.
+    Move(Motion, usize),
+    /// Moves the cursor a number of times, extending the selection.
+    Select(Motion, usize),
+    /// Copies the text between the cursor and where it would move.
+    Copy(Motion, usize),
+    /// Moves the text between the cursor and where it would move to the
+    /// clipboard.
+    Kill(Motion, usize),
+    /// Copies a number of lines, starting with the current one.
+    CopyLines(usize),
+    /// Moves a number of lines to the clipboard, starting with the current
+    /// one.
+    KillLines(usize),
+    /// Inserts the clipboard at the cursor.
+    Paste,
+    /// Inserts the clipboard a number of times after the cursor.
+    ///
+    /// Text copied from whole lines goes after the current line.
+    PasteAfter(usize),
+    /// Starts a selection at the cursor.
+    StartSelection,
+    /// Removes the selection, leaving the text unchanged.
+    ClearSelection,
+    /// Copies the selected text.
+    CopySelection,
+    /// Moves the selected text to the clipboard.
+    KillSelection,
+    /// Reverts the last group of changes.
+    Undo,
+    /// Applies again the last undone group of changes.
+    Redo,
+}
+
+/// Turns events into editing actions.
+pub trait EditMode {
+    /// Handles an event, and returns the actions to apply.
+    ///
+    /// Returns `None` to let the view handle the event as usual.
+    fn on_event(&mut self, event: &Event) -> Option<Vec<Action>>;
+
+    /// Returns a short description of the current state, like `INSERT`.
+    fn indicator(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Text buffer an editing mode acts on.
+///
+/// Positions are byte offsets in the content.
+pub trait Editor {
+    /// Returns the length of the content.
+    fn content_len(&self) -> usize;
+
+    /// Returns the text between `start` and `end`.
+    fn text(&self, start: usize, end: usize) -> Cow<str>;
+
+    /// Returns the start of the line containing `pos`, and that line
+    /// without the ending `\n`.
+    fn line_at(&self, pos: usize) -> (usize, &str);
+
+    /// Returns the position of the cursor.
+    fn cursor(&self) -> usize;
+
+    /// Moves the cursor.
+    fn set_cursor(&mut self, cursor: usize);
+
+    /// Returns the other end of the selection, if any.
+    fn anchor(&self) -> Option<usize>;
+
+    /// Sets the other end of the selection.
+    fn set_anchor(&mut self, anchor: Option<usize>);
+
+    /// Moves the cursor one row up, if possible.
+    fn move_up(&mut self) {}
+
+    /// Moves the cursor one row down, if possible.
+    fn move_down(&mut self) {}
+
+    /// Replaces the text between `start` and `end`, in a single undoable
+    /// step, and moves the cursor after the new text.
+    fn replace_range(&mut self, start: usize, end: usize, text: &str);
+
+    /// Reverts the last group of changes.
+    fn undo(&mut self);
+
+    /// Applies again the last undone group of changes.
+    fn redo(&mut self);
+
+    /// Returns the clipboard used to cut, copy and paste.
+    fn get_clipboard(&self) -> Rc<RefCell<Clipboard>>;
+
+    /// Applies an editing action.
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::Move(motion, count) => {
+                let target = motion_target(self, motion, count);
+                self.set_anchor(None);
+                self.set_cursor(target);
+            }
+            Action::Select(motion, count) => {
+                let target = motion_target(self, motion, count);
+                if self.anchor().is_none() {
+                    let cursor = self.cursor();
+                    self.set_anchor(Some(cursor));
+                }
+                self.set_cursor(target);
+            }
+            Action::Copy(motion, count) => {
+                let target = motion_target(self, motion, count);
+                let cursor = self.cursor();
+                let (start, end) = (min(cursor, target), max(cursor, target));
+                self.get_clipboard().borrow_mut().set(&self.text(start, end));
+                self.set_cursor(start);
+            }
+            Action::Kill(motion, count) => {
+                let target = motion_target(self, motion, count);
+                let cursor = self.cursor();
+                kill(self, min(cursor, target), max(cursor, target));
+            }
+            Action::CopyLines(count) => {
+                let (start, end) = line_range(self, count);
+                let text = format!("{}\n", self.text(start, end));
+                self.get_clipboard().borrow_mut().set(&text);
+            }
+            Action::KillLines(count) => {
+                let (start, end) = line_range(self, count);
+                let text = format!("{}\n", self.text(start, end));
+                self.get_clipboard().borrow_mut().set(&text);
+                // Also remove a newline around the lines.
+                if end < self.content_len() {
+                    self.replace_range(start, end + 1, "");
+                } else if start > 0 {
+                    self.replace_range(start - 1, end, "");
+                    let line_start = self.line_at(start - 1).0;
+                    self.set_cursor(line_start);
+                } else {
+                    self.replace_range(start, end, "");
+                }
+            }
+            Action::Paste => {
+                let text = self.get_clipboard().borrow_mut().get();
+                if let Some(text) = text {
+                    let cursor = self.cursor();
+                    self.replace_range(cursor, cursor, &text);
+                }
+            }
+            Action::PasteAfter(count) => {
+                let text = match self.get_clipboard().borrow_mut().get() {
+                    Some(ref text) if !text.is_empty() => text.repeat(count),
+                    _ => return,
+                };
+                paste_after(self, &text);
+            }
+            Action::StartSelection => {
+                let cursor = self.cursor();
+                self.set_anchor(Some(cursor));
+            }
+            Action::ClearSelection => self.set_anchor(None),
+            Action::CopySelection => {
+                if let Some((start, end)) = selection(self) {
+                    self.get_clipboard()
+                        .borrow_mut()
+                        .set(&self.text(start, end));
+                    self.set_cursor(start);
+                }
+            }
+            Action::KillSelection => {
+                if let Some((start, end)) = selection(self) {
+                    kill(self, start, end);
+                }
+                self.set_anchor(None);
+            }
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+        }
+    }
+}
+
+// Returns the selected range, if any.
+fn selection<E: Editor + ?Sized>(editor: &E) -> Option<(usize, usize)> {
+    match editor.anchor() {
+        Some(anchor) if anchor != editor.cursor() => {
+            let cursor = editor.cursor();
+            Some((min(anchor, cursor), max(anchor, cursor)))
+        }
+        _ => None,
+    }
+}
+
+// Moves the text between `start` and `end` to the clipboard.
+fn kill<E: Editor + ?Sized>(editor: &mut E, start: usize, end: usize) {
+    if start == end {
+        return;
+    }
+    editor.get_clipboard().borrow_mut().set(&editor.text(start, end));
+    editor.replace_range(start, end, "");
+}
+
+// Returns the range of `count` lines from the cursor, without the last
+// newline.
+fn line_range<E: Editor + ?Sized>(editor: &E, count: usize)
+                                  -> (usize, usize) {
+    let start = editor.line_at(editor.cursor()).0;
+    let mut end = start;
+    for i in 0..max(count, 1) {
+        let (line_start, line) = editor.line_at(end);
+        end = line_start + line.len();
+        if i + 1 == count || end == editor.content_len() {
+            break;
+        }
+        end += 1;
+    }
+    (start, end)
+}
+
+// Inserts `text` after the cursor, or after the line for whole lines.
+fn paste_after<E: Editor + ?Sized>(editor: &mut E, text: &str) {
+    let (line_start, line) = editor.line_at(editor.cursor());
+    let line_end = line_start + line.len();
+    if text.ends_with('\n') {
+        if line_end < editor.content_len() {
+            editor.replace_range(line_end + 1, line_end + 1, text);
+        } else {
+            let text = format!("\n{}", &text[..text.len() - 1]);
+            editor.replace_range(line_end, line_end, &text);
+        }
+        let cursor = min(line_end + 1, editor.content_len());
+        editor.set_cursor(cursor);
+    } else {
+        let cursor = editor.cursor();
+        let pos = if cursor < line_end {
+            cursor + next_grapheme_len(&line[cursor - line_start..])
+        } else {
+            cursor
+        };
+        editor.replace_range(pos, pos, text);
+        // Stay on the last pasted character.
+        let last = text.graphemes(true)
+            .next_back()
+            .map(str::len)
+            .unwrap_or(0);
+        editor.set_cursor(pos + text.len() - last);
+    }
+}
+
+fn next_grapheme_len(text: &str) -> usize {
+    text.graphemes(true).next().map(str::len).unwrap_or(0)
+}
+
+// Kind of character, to find word boundaries.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Symbol,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Symbol
+    }
+}
+
+// Returns the character at `pos`, newlines included.
+fn char_at<E: Editor + ?Sized>(editor: &E, pos: usize) -> Option<char> {
+    let (start, line) = editor.line_at(pos);
+    match line[pos - start..].chars().next() {
+        None if pos < editor.content_len() => Some('\n'),
+        c => c,
+    }
+}
+
+// Returns the character before `pos`, newlines included.
+fn char_before<E: Editor + ?Sized>(editor: &E, pos: usize) -> Option<char> {
+    if pos == 0 {
+        return None;
+    }
+    let (start, line) = editor.line_at(pos);
+    match line[..pos - start].chars().next_back() {
+        None => Some('\n'),
+        c => c,
+    }
+}
+
+// Skips characters of the given class, forward.
+fn skip_forward<E, F>(editor: &E, mut pos: usize, f: F) -> usize
+    where E: Editor + ?Sized,
+          F: Fn(CharClass) -> bool
+{
+    while let Some(c) = char_at(editor, pos) {
+        if !f(char_class(c)) {
+            break;
+        }
+        pos += c.len_utf8();
+    }
+    pos
+}
+
+// Skips characters of the given class, backward.
+fn skip_backward<E, F>(editor: &E, mut pos: usize, f: F) -> usize
+    where E: Editor + ?Sized,
+          F: Fn(CharClass) -> bool
+{
+    while let Some(c) = char_before(editor, pos) {
+        if !f(char_class(c)) {
+            break;
+        }
+        pos -= c.len_utf8();
+    }
+    pos
+}
+
+// Returns where the cursor would be after one motion from `pos`.
+fn motion_step<E: Editor + ?Sized>(editor: &E, pos: usize, motion: Motion)
+                                   -> usize {
+    let (line_start, line) = editor.line_at(pos);
+    let line_end = line_start + line.len();
+    let space = |class| class == CharClass::Space;
+    match motion {
+        Motion::Left => {
+            let before = &line[..pos - line_start];
+            pos -
+            before.graphemes(true)
+                .next_back()
+                .map(str::len)
+                .unwrap_or(0)
+        }
+        Motion::Right => {
+            pos + next_grapheme_len(&line[pos - line_start..])
+        }
+        Motion::LineStart => line_start,
+        Motion::LineEnd => line_end,
+        Motion::WordForward => {
+            let pos = match char_at(editor, pos).map(char_class) {
+                Some(CharClass::Space) | None => pos,
+                Some(class) => skip_forward(editor, pos, |c| c == class),
+            };
+            skip_forward(editor, pos, space)
+        }
+        Motion::WordBackward => {
+            let pos = skip_backward(editor, pos, space);
+            match char_before(editor, pos).map(char_class) {
+                Some(class) => skip_backward(editor, pos, |c| c == class),
+                None => pos,
+            }
+        }
+        Motion::WordEnd => {
+            let pos = skip_forward(editor, pos, space);
+            match char_at(editor, pos).map(char_class) {
+                Some(class) => skip_forward(editor, pos, |c| c == class),
+                None => pos,
+            }
+        }
+        Motion::WordLastChar => {
+            let next = match char_at(editor, pos) {
+                Some(c) => pos + c.len_utf8(),
+                None => return pos,
+            };
+            let end = motion_step(editor, next, Motion::WordEnd);
+            match char_before(editor, end) {
+                Some(c) if end > next => end - c.len_utf8(),
+                _ => pos,
+            }
+        }
+        // Rows are only known by the view.
+        Motion::Up | Motion::Down => pos,
+    }
+}
+
+// Returns where the cursor would be after `count` motions.
+fn motion_target<E: Editor + ?Sized>(editor: &mut E, motion: Motion,
+                                     count: usize)
+                                     -> usize {
+    let cursor = editor.cursor();
+    match motion {
+        Motion::Up | Motion::Down => {
+            for _ in 0..count {
+                if motion == Motion::Up {
+                    editor.move_up();
+                } else {
+                    editor.move_down();
+                }
+            }
+            let target = editor.cursor();
+            editor.set_cursor(cursor);
+            target
+        }
+        _ => {
+            (0..count).fold(cursor, |pos, _| motion_step(editor, pos, motion))
+        }
+    }
+}
+
+/// Readline-like editing mode.
+///
+/// Other events are handled by the view as usual.
+///
+/// * `Ctrl-A` and `Ctrl-E` go to the beginning and end of the line.
+/// * `Alt-B` and `Alt-F` go to the previous and next word.
+/// * `Ctrl-K` cuts the end of the line, and `Ctrl-U` its beginning.
+/// * `Ctrl-W` cuts the previous word, and `Alt-D` the next one.
+/// * `Ctrl-Y` pastes the last cut text.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmacsMode;
+
+impl EmacsMode {
+    /// Creates a new Emacs mode.
+    pub fn new() -> Self {
+        EmacsMode
+    }
+}
+
+impl EditMode for EmacsMode {
+    fn on_event(&mut self, event: &Event) -> Option<Vec<Action>> {
+        let action = match *event {
+            Event::CtrlChar('a') => Action::Move(Motion::LineStart, 1),
+            Event::CtrlChar('e') => Action::Move(Motion::LineEnd, 1),
+            Event::AltChar('b') => Action::Move(Motion::WordBackward, 1),
+            Event::AltChar('f') => Action::Move(Motion::WordEnd, 1),
+            Event::CtrlChar('k') => Action::Kill(Motion::LineEnd, 1),
+            Event::CtrlChar('u') => Action::Kill(Motion::LineStart, 1),
+            Event::CtrlChar('w') => Action::Kill(Motion::WordBackward, 1),
+            Event::AltChar('d') => Action::Kill(Motion::WordEnd, 1),
+            Event::CtrlChar('y') => Action::Paste,
+            _ => return None,
+        };
+        Some(vec![action])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ViState {
+    Normal,
+    Insert,
+    Visual,
+}
+
+// Largest count, so a mistyped one doesn't freeze the editor.
+const MAX_COUNT: usize = 10_000;
+
+/// Minimal vi-like modal editing mode.
+///
+/// In insert mode, events are handled by the view as usual, and `Esc` goes
+/// back to normal mode. In normal mode:
+///
+/// * `h`, `j`, `k`, `l`, `w`, `b`, `e`, `0` and `$` move the cursor, and
+///   `Enter` goes to the start of the next line.
+/// * `i`, `a`, `I` and `A` switch to insert mode.
+/// * `x` cuts the next character, `dd` whole lines, and `d` followed by
+///   a motion the text until there.
+/// * `yy` copies whole lines, and `y` followed by a motion the text until
+///   there.
+/// * `p` pastes after the cursor.
+/// * `u` and `Ctrl-R` undo and redo.
+/// * `v` switches to visual mode, where motions extend the selection,
+///   `y` copies it, and `d` or `x` cuts it.
+///
+/// Other keys editing the content, like `Tab` or `Ctrl-V`, do nothing
+/// outside of insert mode.
+///
+/// Commands can be preceded by a count, like `3dd` or `2w`. Counts are
+/// capped at 10000.
+///
+/// The current mode is given by [`indicator`].
+///
+/// [`indicator`]: trait.EditMode.html#method.indicator
+#[derive(Clone, Debug)]
+pub struct ViMode {
+    state: ViState,
+    // Count typed so far, if any.
+    count: Option<usize>,
+    // Operator waiting for a motion, with its count.
+    operator: Option<(char, usize)>,
+}
+
+impl Default for ViMode {
+    fn default() -> Self {
+        ViMode::new()
+    }
+}
+
+impl ViMode {
+    /// Creates a new vi mode, starting in normal mode.
+    pub fn new() -> Self {
+        ViMode {
+            state: ViState::Normal,
+            count: None,
+            operator: None,
+        }
+    }
+
+    /// Creates a new vi mode, starting in insert mode.
+    pub fn insert() -> Self {
+        ViMode { state: ViState::Insert, ..ViMode::new() }
+    }
+
+    // Returns the motion for the given key, if any.
+    //
+    // `operator` is `true` when the motion follows an operator.
+    fn motion(event: &Event, operator: bool) -> Option<Motion> {
+        Some(match *event {
+            Event::Char('h') |
+            Event::Key(Key::Left) => Motion::Left,
+            Event::Char('l') |
+            Event::Key(Key::Right) => Motion::Right,
+            Event::Char('k') |
+            Event::Key(Key::Up) => Motion::Up,
+            Event::Char('j') |
+            Event::Key(Key::Down) => Motion::Down,
+            Event::Char('0') |
+            Event::Key(Key::Home) => Motion::LineStart,
+            Event::Char('$') |
+            Event::Key(Key::End) => Motion::LineEnd,
+            Event::Char('w') => Motion::WordForward,
+            Event::Char('b') => Motion::WordBackward,
+            // Operators apply until the end of the word.
+            Event::Char('e') if operator => Motion::WordEnd,
+            Event::Char('e') => Motion::WordLastChar,
+            _ => return None,
+        })
+    }
+
+    fn on_insert_event(&mut self, event: &Event) -> Option<Vec<Action>> {
+        match *event {
+            Event::Key(Key::Esc) => {
+                self.state = ViState::Normal;
+                Some(vec![Action::Move(Motion::Left, 1)])
+            }
+            _ => None,
+        }
+    }
+
+    fn on_visual_event(&mut self, event: &Event, count: usize)
+                       -> Vec<Action> {
+        if let Some(motion) = ViMode::motion(event, false) {
+            return vec![Action::Select(motion, count)];
+        }
+        if *event == Event::Key(Key::Enter) {
+            return vec![Action::Select(Motion::Down, count),
+                        Action::Select(Motion::LineStart, 1)];
+        }
+
+        let actions = match *event {
+            Event::Char('y') => {
+                vec![Action::CopySelection, Action::ClearSelection]
+            }
+            Event::Char('d') |
+            Event::Char('x') => vec![Action::KillSelection],
+            Event::Char('v') |
+            Event::Key(Key::Esc) => vec![Action::ClearSelection],
+            _ => return Vec::new(),
+        };
+        self.state = ViState::Normal;
+        actions
+    }
+
+    fn on_normal_event(&mut self, event: &Event, count: usize)
+                       -> Option<Vec<Action>> {
+        if let Some((operator, operator_count)) = self.operator.take() {
+            let count = min(count.saturating_mul(operator_count), MAX_COUNT);
+            let actions = match (operator, event.clone()) {
+                ('d', Event::Char('d')) => vec![Action::KillLines(count)],
+                ('y', Event::Char('y')) => vec![Action::CopyLines(count)],
+                (operator, event) => {
+                    match ViMode::motion(&event, true) {
+                        Some(motion) if operator == 'd' => {
+                            vec![Action::Kill(motion, count)]
+                        }
+                        Some(motion) => vec![Action::Copy(motion, count)],
+                        // Unknown motion: cancel the operator.
+                        None => Vec::new(),
+                    }
+                }
+            };
+            return Some(actions);
+        }
+
+        if let Some(motion) = ViMode::motion(event, false) {
+            return Some(vec![Action::Move(motion, count)]);
+        }
+        if *event == Event::Key(Key::Enter) {
+            return Some(vec![Action::Move(Motion::Down, count),
+                             Action::Move(Motion::LineStart, 1)]);
+        }
+
+        let actions = match *event {
+            Event::Char('i') => {
+                self.state = ViState::Insert;
+                Vec::new()
+            }
+            Event::Char('a') => {
+                self.state = ViState::Insert;
+                vec![Action::Move(Motion::Right, 1)]
+            }
+            Event::Char('I') => {
+                self.state = ViState::Insert;
+                vec![Action::Move(Motion::LineStart, 1)]
+            }
+            Event::Char('A') => {
+                self.state = ViState::Insert;
+                vec![Action::Move(Motion::LineEnd, 1)]
+            }
+            Event::Char('v') => {
+                self.state = ViState::Visual;
+                vec![Action::StartSelection]
+            }
+            Event::Char(c @ 'd') |
+            Event::Char(c @ 'y') => {
+                self.operator = Some((c, count));
+                Vec::new()
+            }
+            Event::Char('x') |
+            Event::Key(Key::Del) => vec![Action::Kill(Motion::Right, count)],
+            Event::Key(Key::Backspace) => {
+                vec![Action::Move(Motion::Left, count)]
+            }
+            Event::Char('p') => vec![Action::PasteAfter(count)],
+            Event::Char('u') => vec![Action::Undo; count],
+            Event::CtrlChar('r') => vec![Action::Redo; count],
+            // Other characters and editing keys don't change anything.
+            Event::Char(_) |
+            Event::Key(Key::Tab) |
+            Event::CtrlChar('x') |
+            Event::CtrlChar('v') |
+            Event::CtrlChar('y') |
+            Event::CtrlChar('z') => Vec::new(),
+            _ => return None,
+        };
+        Some(actions)
+    }
+}
+
+impl EditMode for ViMode {
+    fn on_event(&mut self, event: &Event) -> Option<Vec<Action>> {
+        if self.state == ViState::Insert {
+            return self.on_insert_event(event);
+        }
+
+        // Digits build the count, except for a leading `0`.
+        if let Event::Char(c) = *event {
+            if let Some(digit) = c.to_digit(10) {
+                if digit != 0 || self.count.is_some() {
+                    let count = self.count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit as usize))
+                        .map_or(MAX_COUNT, |count| min(count, MAX_COUNT));
+                    self.count = Some(count);
+                    return Some(Vec::new());
+                }
+            }
+        }
+
+        let count = self.count.take().unwrap_or(1);
+        if *event == Event::Key(Key::Esc) && self.state == ViState::Normal {
+            self.operator = None;
+            return Some(Vec::new());
+        }
+
+        match self.state {
+            ViState::Visual => Some(self.on_visual_event(event, count)),
+            _ => self.on_normal_event(event, count),
+        }
+    }
+
+    fn indicator(&self) -> Option<&str> {
+        Some(match self.state {
+            ViState::Normal => "NORMAL",
+            ViState::Insert => "INSERT",
+            ViState::Visual => "VISUAL",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, EventResult, Key};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use utils::clipboard::LocalClipboard;
+    use vec::Vec2;
+    use view::View;
+    use views::{EditView, TextArea};
+
+    fn content(area: &TextArea) -> String {
+        area.get_rope().to_string()
+    }
+
+    fn type_keys<V: View>(view: &mut V, keys: &str) {
+        for c in keys.chars() {
+            view.on_event(Event::Char(c));
+        }
+    }
+
+    #[test]
+    fn emacs() {
+        let mut edit = EditView::new()
+            .content("echo hello world")
+            .clipboard(Rc::new(RefCell::new(LocalClipboard::new())))
+            .edit_mode(EmacsMode::new());
+        edit.layout(Vec2::new(30, 1));
+
+        edit.on_event(Event::AltChar('b'));
+        assert_eq!(11, edit.cursor());
+        edit.on_event(Event::CtrlChar('w'));
+        assert_eq!("echo world", &*edit.get_content());
+        edit.on_event(Event::CtrlChar('e'));
+        edit.on_event(Event::CtrlChar('y'));
+        assert_eq!("echo worldhello ", &*edit.get_content());
+
+        edit.on_event(Event::CtrlChar('a'));
+        edit.on_event(Event::AltChar('f'));
+        assert_eq!(4, edit.cursor());
+        edit.on_event(Event::CtrlChar('k'));
+        assert_eq!("echo", &*edit.get_content());
+
+        // Other events are handled as usual.
+        edit.on_event(Event::Char('!'));
+        assert_eq!("echo!", &*edit.get_content());
+        edit.on_event(Event::CtrlChar('z'));
+        assert_eq!("echo", &*edit.get_content());
+    }
+
+    #[test]
+    fn vi() {
+        let mut area = TextArea::new()
+            .content("one two three\nfour\nfive")
+            .clipboard(Rc::new(RefCell::new(LocalClipboard::new())))
+            .edit_mode(ViMode::new());
+        area.layout(Vec2::new(20, 10));
+        assert_eq!(Some("NORMAL"), area.edit_mode_indicator());
+
+        // Characters don't insert anything in normal mode.
+        type_keys(&mut area, "2w");
+        assert_eq!(8, area.cursor());
+        type_keys(&mut area, "e");
+        assert_eq!(12, area.cursor());
+        type_keys(&mut area, "b");
+        type_keys(&mut area, "de");
+        assert_eq!("one two \nfour\nfive", content(&area));
+
+        type_keys(&mut area, "j2yyjp");
+        assert_eq!("one two \nfour\nfive\nfour\nfive", content(&area));
+        assert_eq!(19, area.cursor());
+
+        type_keys(&mut area, "k2dd");
+        assert_eq!("one two \nfour\nfive", content(&area));
+        type_keys(&mut area, "u");
+        assert_eq!("one two \nfour\nfive\nfour\nfive", content(&area));
+
+        type_keys(&mut area, "iX");
+        area.on_event(Event::Key(Key::Esc));
+        assert_eq!(Some("NORMAL"), area.edit_mode_indicator());
+        assert_eq!("one two \nfour\nXfive\nfour\nfive", content(&area));
+
+        type_keys(&mut area, "0vl");
+        assert_eq!(Some("VISUAL"), area.edit_mode_indicator());
+        type_keys(&mut area, "ld");
+        assert_eq!("one two \nfour\nive\nfour\nfive", content(&area));
+        assert_eq!(Some("NORMAL"), area.edit_mode_indicator());
+
+        // Enter goes to the next line, and editing keys do nothing.
+        type_keys(&mut area, "kk$");
+        area.on_event(Event::Key(Key::Enter));
+        area.on_event(Event::Key(Key::Tab));
+        area.on_event(Event::CtrlChar('v'));
+        assert_eq!("one two \nfour\nive\nfour\nfive", content(&area));
+        assert_eq!(9, area.cursor());
+        type_keys(&mut area, "v");
+        area.on_event(Event::Key(Key::Enter));
+        assert_eq!(Some("four\n".into()), area.selected_text());
+    }
+
+    #[test]
+    fn vi_submit() {
+        let mut edit = EditView::new()
+            .content("ab")
+            .on_submit(|_, _| ())
+            .edit_mode(ViMode::new());
+        edit.layout(Vec2::new(10, 1));
+
+        match edit.on_event(Event::Key(Key::Enter)) {
+            EventResult::Consumed(Some(_)) => (),
+            _ => panic!("Enter should submit"),
+        }
+        assert_eq!("ab", &*edit.get_content());
+    }
+
+    #[test]
+    fn vi_huge_count() {
+        let mut area = TextArea::new()
+            .content("ab")
+            .clipboard(Rc::new(RefCell::new(LocalClipboard::new())))
+            .edit_mode(ViMode::new());
+        area.layout(Vec2::new(20, 10));
+
+        type_keys(&mut area, "99999999999999999999999l");
+        assert_eq!(2, area.cursor());
+        type_keys(&mut area, "0yl99999999999999999999999p");
+        assert_eq!(2 + MAX_COUNT, area.get_rope().len());
+        type_keys(&mut area, "99999999999999999999999u");
+        assert_eq!("ab", content(&area));
+        type_keys(&mut area, "9999d9999l");
+        assert_eq!("", content(&area));
+    }
+}
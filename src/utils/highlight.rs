@@ -0,0 +1,280 @@
+//! Syntax highlighting for text editors.
+//!
+//! A [`Highlighter`] styles a text one line at a time. Constructs spanning
+//! several lines, like multi-line strings, are handled by passing a state
+//! from each line to the next one.
+//!
+//! [`Highlighter`]: trait.Highlighter.html
+
+use theme::{BaseColor, Color, Effect, Style};
+use utils::StyledString;
+
+/// Styles a text one line at a time.
+///
+/// The state is an opaque value describing what is still open at the end
+/// of a line: a string, a comment, a nested block... The first line starts
+/// with a state of `0`.
+///
+/// Any closure with the same signature as [`highlight`] is a `Highlighter`.
+///
+/// [`highlight`]: #tymethod.highlight
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::StyledString;
+/// # use cursive::utils::highlight::Highlighter;
+/// # use cursive::theme::Effect;
+/// // Shows lines starting with `#` in bold.
+/// let highlighter = |line: &str, state: usize| {
+///     if line.starts_with('#') {
+///         (StyledString::styled(line, Effect::Bold), state)
+///     } else {
+///         (StyledString::plain(line), state)
+///     }
+/// };
+///
+/// let (text, _) = highlighter.highlight("# Title", 0);
+/// assert_eq!(vec![("# Title", Effect::Bold.into())], text.spans());
+/// ```
+pub trait Highlighter {
+    /// Styles `line`, which follows a line ending with `state`.
+    ///
+    /// `line` doesn't include the newline. Returns the styled line, which
+    /// must have the same source as `line`, and the state at its end.
+    fn highlight(&self, line: &str, state: usize) -> (StyledString, usize);
+}
+
+impl<F> Highlighter for F
+    where F: Fn(&str, usize) -> (StyledString, usize)
+{
+    fn highlight(&self, line: &str, state: usize) -> (StyledString, usize) {
+        self(line, state)
+    }
+}
+
+// Kind of multi-line string open at the end of a line, in the low bits of
+// the state. The remaining bits count open arrays.
+const IN_BASIC: usize = 1;
+const IN_LITERAL: usize = 2;
+const STRING_MASK: usize = 3;
+const DEPTH_SHIFT: usize = 2;
+
+/// Highlights TOML documents.
+///
+/// Table headers, keys, strings, other values and comments each get their
+/// own style. Multi-line strings and arrays are followed across lines.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::TextArea;
+/// # use cursive::utils::highlight::TomlHighlighter;
+/// let area = TextArea::new()
+///     .content("[package]\nname = \"cursive\"")
+///     .highlighter(TomlHighlighter);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TomlHighlighter;
+
+impl TomlHighlighter {
+    fn header() -> Style {
+        Style::from(Color::Dark(BaseColor::Yellow)).with_effect(Effect::Bold)
+    }
+
+    fn key() -> Style {
+        Style::from(Color::Dark(BaseColor::Blue))
+    }
+
+    fn string() -> Style {
+        Style::from(Color::Dark(BaseColor::Green))
+    }
+
+    fn value() -> Style {
+        Style::from(Color::Dark(BaseColor::Magenta))
+    }
+
+    fn comment() -> Style {
+        Style::from(Color::Light(BaseColor::Black))
+    }
+}
+
+// Returns the position right after the `delimiter` closing a string,
+// looking from `start`.
+fn find_closing(text: &str, start: usize, delimiter: &str) -> Option<usize> {
+    let escapes = delimiter.starts_with('"');
+    let mut chars = text[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if escapes && c == '\\' {
+            chars.next();
+        } else if text[start + i..].starts_with(delimiter) {
+            return Some(start + i + delimiter.len());
+        }
+    }
+    None
+}
+
+impl Highlighter for TomlHighlighter {
+    fn highlight(&self, line: &str, state: usize) -> (StyledString, usize) {
+        let mut result = StyledString::new();
+        let mut depth = state >> DEPTH_SHIFT;
+        let mut pos = 0;
+
+        // Finish the string left open by the previous line.
+        let delimiter = match state & STRING_MASK {
+            IN_BASIC => Some("\"\"\""),
+            IN_LITERAL => Some("'''"),
+            _ => None,
+        };
+        if let Some(delimiter) = delimiter {
+            match find_closing(line, 0, delimiter) {
+                Some(end) => {
+                    result.append_styled(&line[..end], Self::string());
+                    pos = end;
+                }
+                None => {
+                    result.append_styled(line, Self::string());
+                    return (result, state);
+                }
+            }
+        }
+
+        // Keys only come first on a line, or inside inline tables.
+        let mut expect_key = depth == 0 && delimiter.is_none();
+        // Brackets opened on this line.
+        let mut stack = Vec::new();
+
+        while pos < line.len() {
+            let rest = &line[pos..];
+            let c = rest.chars().next().unwrap();
+            let (len, style) = match c {
+                '#' => (rest.len(), Self::comment()),
+                '[' if expect_key && depth == 0 && stack.is_empty() => {
+                    expect_key = false;
+                    let len = if rest.starts_with("[[") {
+                        rest.find("]]").map(|i| i + 2)
+                    } else {
+                        rest.find(']').map(|i| i + 1)
+                    };
+                    (len.unwrap_or(rest.len()), Self::header())
+                }
+                '"' | '\'' if rest.starts_with("\"\"\"") ||
+                              rest.starts_with("'''") => {
+                    let delimiter = &rest[..3];
+                    match find_closing(rest, 3, delimiter) {
+                        Some(end) => (end, Self::string()),
+                        None => {
+                            result.append_styled(rest, Self::string());
+                            let kind = if c == '"' {
+                                IN_BASIC
+                            } else {
+                                IN_LITERAL
+                            };
+                            return (result, kind | depth << DEPTH_SHIFT);
+                        }
+                    }
+                }
+                '"' | '\'' => {
+                    let delimiter = &rest[..1];
+                    let end = find_closing(rest, 1, delimiter)
+                        .unwrap_or(rest.len());
+                    if expect_key {
+                        (end, Self::key())
+                    } else {
+                        (end, Self::string())
+                    }
+                }
+                '=' => {
+                    expect_key = false;
+                    (1, Style::none())
+                }
+                '[' | '{' => {
+                    stack.push(c);
+                    expect_key = c == '{';
+                    (1, Style::none())
+                }
+                ']' | '}' => {
+                    if stack.pop().is_none() && c == ']' {
+                        depth = depth.saturating_sub(1);
+                    }
+                    (1, Style::none())
+                }
+                ',' => {
+                    expect_key = stack.last() == Some(&'{');
+                    (1, Style::none())
+                }
+                c if c.is_whitespace() => (c.len_utf8(), Style::none()),
+                _ => {
+                    let len = rest.find(|c: char| {
+                            c.is_whitespace() || "=,[]{}#\"'".contains(c)
+                        })
+                        .unwrap_or(rest.len());
+                    if expect_key {
+                        (len, Self::key())
+                    } else {
+                        (len, Self::value())
+                    }
+                }
+            };
+            result.append_styled(&rest[..len], style);
+            pos += len;
+        }
+
+        // Arrays still open continue on the next lines.
+        depth += stack.iter().filter(|&&c| c == '[').count();
+        (result, depth << DEPTH_SHIFT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns the styled spans, skipping plain punctuation.
+    fn spans(text: &StyledString) -> Vec<(&str, Style)> {
+        text.spans()
+            .into_iter()
+            .filter(|&(_, style)| !style.is_none())
+            .collect()
+    }
+
+    #[test]
+    fn toml_line() {
+        let h = TomlHighlighter;
+        let (text, state) = h.highlight("[dependencies.toml] # deps", 0);
+        assert_eq!(0, state);
+        assert_eq!(vec![("[dependencies.toml]", TomlHighlighter::header()),
+                        ("# deps", TomlHighlighter::comment())],
+                   spans(&text));
+
+        let (text, state) = h.highlight("a = { b = \"x\", c = 1 }", 0);
+        assert_eq!(0, state);
+        assert_eq!(vec![("a", TomlHighlighter::key()),
+                        ("b", TomlHighlighter::key()),
+                        ("\"x\"", TomlHighlighter::string()),
+                        ("c", TomlHighlighter::key()),
+                        ("1", TomlHighlighter::value())],
+                   spans(&text));
+    }
+
+    #[test]
+    fn toml_state() {
+        let h = TomlHighlighter;
+        let (_, state) = h.highlight("text = \"\"\"first", 0);
+        let (text, state) = h.highlight("still \\\"\"\" text", state);
+        assert_eq!(IN_BASIC, state);
+        assert_eq!(vec![("still \\\"\"\" text", TomlHighlighter::string())],
+                   text.spans());
+        let (_, state) = h.highlight("end\"\"\"", state);
+        assert_eq!(0, state);
+
+        let (_, state) = h.highlight("list = [", 0);
+        let (text, state) = h.highlight("  [1, 2],", state);
+        assert_eq!(1 << DEPTH_SHIFT, state);
+        assert_eq!(vec![("1", TomlHighlighter::value()),
+                        ("2", TomlHighlighter::value())],
+                   spans(&text));
+        let (_, state) = h.highlight("]", state);
+        assert_eq!(0, state);
+    }
+}
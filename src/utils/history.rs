@@ -0,0 +1,198 @@
+//! History of submitted input.
+//!
+//! An [`InputHistory`] remembers what was submitted in an [`EditView`], so
+//! it can be browsed with the arrows or searched with `Ctrl-R`. It can be
+//! saved to a file, to persist across runs.
+//!
+//! [`InputHistory`]: struct.InputHistory.html
+//! [`EditView`]: ../../views/struct.EditView.html
+
+use std::cmp::min;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// List of previous submissions, from oldest to most recent.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::history::InputHistory;
+/// let mut history = InputHistory::new().max_size(2);
+/// history.push("ls");
+/// history.push("cd src");
+/// history.push("ls");
+/// assert_eq!(&["cd src", "ls"], history.entries());
+///
+/// history.push("make");
+/// assert_eq!(&["ls", "make"], history.entries());
+/// ```
+#[derive(Clone, Debug)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    max_size: Option<usize>,
+    dedup: bool,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        InputHistory::new()
+    }
+}
+
+impl InputHistory {
+    /// Creates a new, empty history.
+    ///
+    /// It keeps up to 1000 entries, without duplicates.
+    pub fn new() -> Self {
+        InputHistory {
+            entries: Vec::new(),
+            max_size: Some(1000),
+            dedup: true,
+        }
+    }
+
+    /// Sets the maximum number of entries.
+    ///
+    /// The oldest entries are dropped first. `None` removes the limit.
+    pub fn set_max_size(&mut self, max_size: Option<usize>) {
+        self.max_size = max_size;
+        self.truncate();
+    }
+
+    /// Sets the maximum number of entries.
+    ///
+    /// Chainable variant.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.set_max_size(Some(max_size));
+        self
+    }
+
+    /// If `dedup` is `true`, an entry submitted again replaces the
+    /// previous one, instead of being stored twice.
+    pub fn set_dedup(&mut self, dedup: bool) {
+        self.dedup = dedup;
+    }
+
+    /// Keeps duplicate entries.
+    ///
+    /// Chainable variant.
+    pub fn keep_duplicates(mut self) -> Self {
+        self.set_dedup(false);
+        self
+    }
+
+    /// Adds an entry at the end of the history.
+    ///
+    /// Empty entries are ignored.
+    pub fn push<S: Into<String>>(&mut self, entry: S) {
+        let entry = entry.into();
+        if entry.is_empty() {
+            return;
+        }
+        if self.dedup {
+            self.entries.retain(|e| *e != entry);
+        }
+        self.entries.push(entry);
+        self.truncate();
+    }
+
+    // Drops the oldest entries above the maximum size.
+    fn truncate(&mut self) {
+        if let Some(max_size) = self.max_size {
+            if self.entries.len() > max_size {
+                let extra = self.entries.len() - max_size;
+                self.entries.drain(..extra);
+            }
+        }
+    }
+
+    /// Returns the entries, from oldest to most recent.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Returns the entry at index `i`, if any.
+    pub fn get(&self, i: usize) -> Option<&str> {
+        self.entries.get(i).map(|entry| entry.as_str())
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the index of the most recent entry before `before`
+    /// containing `query`.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let before = min(before, self.entries.len());
+        self.entries[..before]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    /// Adds the entries stored in a file, one per line.
+    pub fn load<P: AsRef<Path>>(&mut self, filename: P) -> io::Result<()> {
+        let file = try!(File::open(filename));
+        for line in BufReader::new(file).lines() {
+            self.push(try!(line));
+        }
+        Ok(())
+    }
+
+    /// Writes the entries to a file, one per line.
+    pub fn save<P: AsRef<Path>>(&self, filename: P) -> io::Result<()> {
+        let mut file = try!(File::create(filename));
+        for entry in &self.entries {
+            try!(writeln!(file, "{}", entry));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn push() {
+        let mut history = InputHistory::new().keep_duplicates();
+        history.push("a");
+        history.push("");
+        history.push("b");
+        history.push("a");
+        assert_eq!(&["a", "b", "a"], history.entries());
+        assert_eq!(Some(0), history.search("a", 2));
+        assert_eq!(None, history.search("c", 3));
+
+        history.set_max_size(Some(1));
+        assert_eq!(&["a"], history.entries());
+    }
+
+    #[test]
+    fn save_load() {
+        let path = env::temp_dir().join("cursive_input_history_test");
+        let mut history = InputHistory::new();
+        history.push("cargo build");
+        history.push("cargo test");
+        history.save(&path).unwrap();
+
+        let mut loaded = InputHistory::new();
+        loaded.push("cargo build");
+        loaded.load(&path).unwrap();
+        assert_eq!(history.entries(), loaded.entries());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -49,7 +49,7 @@ impl<'a> LinesIterator<'a> {
 ///
 /// A row is made of offsets into a parent `String`.
 /// The corresponding substring should take `width` cells when printed.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Row {
     /// Beginning of the row in the parent `String`.
     pub start: usize,
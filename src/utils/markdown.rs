@@ -0,0 +1,505 @@
+//! Parse markdown into styled text.
+//!
+//! Only a common subset of markdown is supported:
+//!
+//! * Headings (`# Title`)
+//! * Emphasis (`*italic*`, `**bold**`) and inline code (`` `code` ``)
+//! * Fenced code blocks (` ``` `)
+//! * Bullet (`-`, `*`, `+`) and numbered (`1.`) lists
+//! * Block quotes (`> quote`)
+//! * Links (`[text](url)`)
+//! * Horizontal rules (`---`)
+//!
+//! Use [`parse`] to get a single [`StyledString`] for a [`TextView`], or a
+//! [`MarkdownView`] to get hanging indentation for lists and quotes.
+//!
+//! [`parse`]: fn.parse.html
+//! [`StyledString`]: ../markup/struct.StyledString.html
+//! [`TextView`]: ../../views/struct.TextView.html
+//! [`MarkdownView`]: ../../views/struct.MarkdownView.html
+
+use theme::{ColorStyle, Effect, Style};
+use utils::{LinesIterator, Row, StyledString};
+
+/// A block of a markdown document: a paragraph, a heading, a list item, ...
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// Printed before the first row of the block.
+    pub prefix: StyledString,
+    /// Printed before the other rows of the block.
+    ///
+    /// Should be as wide as `prefix`.
+    pub continuation: StyledString,
+    /// Text of the block.
+    pub content: StyledString,
+    /// If `true`, the block is a horizontal rule, and has no content.
+    pub rule: bool,
+    /// If `true`, a blank line separates this block from the previous one.
+    pub gap: bool,
+}
+
+impl Block {
+    fn new(prefix: StyledString, continuation: StyledString,
+           content: StyledString)
+           -> Self {
+        Block {
+            prefix: prefix,
+            continuation: continuation,
+            content: content,
+            rule: false,
+            gap: false,
+        }
+    }
+
+    /// Returns the rows of this block, wrapped to the given width.
+    ///
+    /// Rules take the entire width.
+    pub fn rows(&self, width: usize) -> Vec<StyledString> {
+        if self.rule {
+            let rule: String = ::std::iter::repeat("─")
+                .take(width)
+                .collect();
+            return vec![StyledString::styled(rule, ColorStyle::Secondary)];
+        }
+
+        let indent = self.prefix.width();
+        if width <= indent {
+            return Vec::new();
+        }
+
+        let mut rows = Vec::new();
+        for row in LinesIterator::new(self.content.source(), width - indent) {
+            let mut line = if rows.is_empty() {
+                self.prefix.clone()
+            } else {
+                self.continuation.clone()
+            };
+            for (text, style) in self.content.row_spans(&row) {
+                line.append_styled(text, style);
+            }
+            rows.push(line);
+        }
+
+        rows
+    }
+}
+
+/// Parses a markdown document into styled text.
+///
+/// List items and quotes are not indented when wrapped: use a
+/// [`MarkdownView`] for that.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::markdown;
+/// let text = markdown::parse("# Title\n\nSome *text*.");
+/// assert_eq!("Title\n\nSome text.", text.source());
+/// ```
+///
+/// [`MarkdownView`]: ../../views/struct.MarkdownView.html
+pub fn parse(text: &str) -> StyledString {
+    let mut result = StyledString::new();
+    for (i, block) in blocks(text).into_iter().enumerate() {
+        if i > 0 {
+            result.append_plain(if block.gap { "\n\n" } else { "\n" });
+        }
+        if block.rule {
+            result.append_styled("───", ColorStyle::Secondary);
+            continue;
+        }
+
+        // Code blocks can have multiple lines: keep the prefix on each.
+        let mut offset = 0;
+        for (j, line) in block.content.source().split('\n').enumerate() {
+            if j == 0 {
+                result.append(block.prefix.clone());
+            } else {
+                result.append_plain("\n");
+                result.append(block.continuation.clone());
+            }
+            let row = Row {
+                start: offset,
+                end: offset + line.len(),
+                width: 0,
+            };
+            for (text, style) in block.content.row_spans(&row) {
+                result.append_styled(text, style);
+            }
+            offset += line.len() + 1;
+        }
+    }
+
+    result
+}
+
+/// Parses a markdown document into blocks.
+pub fn blocks(text: &str) -> Vec<Block> {
+    let mut builder = Builder {
+        blocks: Vec::new(),
+        blank: false,
+        list: false,
+    };
+    // Kind of the block being built, and its lines.
+    let mut current: Option<(Kind, Vec<&str>)> = None;
+
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            builder.push(current.take());
+            let mut code = Vec::new();
+            while let Some(line) = lines.next() {
+                if line.trim().starts_with("```") {
+                    break;
+                }
+                code.push(line);
+            }
+            builder.push(Some((Kind::Code, code)));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            builder.push(current.take());
+            builder.blank = true;
+            continue;
+        }
+
+        let kind = match (line_kind(line), current.as_ref().map(|c| c.0)) {
+            // Lazy continuation lines.
+            (Kind::Paragraph, Some(Kind::Paragraph)) |
+            (Kind::Paragraph, Some(Kind::Item(..))) => {
+                current.as_mut().unwrap().1.push(trimmed);
+                continue;
+            }
+            (Kind::Paragraph, Some(Kind::Quote)) |
+            (Kind::Quote, Some(Kind::Quote)) => {
+                current.as_mut().unwrap().1.push(quote_content(line));
+                continue;
+            }
+            (kind, _) => kind,
+        };
+
+        builder.push(current.take());
+        match kind {
+            Kind::Heading(_) | Kind::Rule => {
+                builder.push(Some((kind, vec![trimmed])));
+            }
+            Kind::Quote => current = Some((kind, vec![quote_content(line)])),
+            kind => current = Some((kind, vec![trimmed])),
+        }
+    }
+    builder.push(current);
+
+    builder.blocks
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Paragraph,
+    // Level
+    Heading(usize),
+    Code,
+    Quote,
+    // Indentation level, and number for ordered lists.
+    Item(usize, Option<usize>),
+    Rule,
+}
+
+fn line_kind(line: &str) -> Kind {
+    let trimmed = line.trim_left();
+    let indent = (line.len() - trimmed.len()) / 2;
+
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if hashes > 0 && hashes <= 6 && trimmed[hashes..].starts_with(' ') {
+        return Kind::Heading(hashes);
+    }
+
+    if is_rule(trimmed) {
+        return Kind::Rule;
+    }
+
+    if trimmed.starts_with('>') {
+        return Kind::Quote;
+    }
+
+    if trimmed.starts_with("- ") || trimmed.starts_with("* ") ||
+       trimmed.starts_with("+ ") {
+        return Kind::Item(indent, None);
+    }
+
+    let digits = trimmed.chars().take_while(|c| c.is_digit(10)).count();
+    if digits > 0 && trimmed[digits..].starts_with(". ") {
+        let n = trimmed[..digits].parse().unwrap_or(1);
+        return Kind::Item(indent, Some(n));
+    }
+
+    Kind::Paragraph
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_right();
+    line.len() >= 3 &&
+    (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '*') ||
+     line.chars().all(|c| c == '_'))
+}
+
+fn quote_content(line: &str) -> &str {
+    let line = line.trim_left();
+    let line = if line.starts_with('>') { &line[1..] } else { line };
+    line.trim()
+}
+
+struct Builder {
+    blocks: Vec<Block>,
+    // `true` if a blank line was found since the last block.
+    blank: bool,
+    // `true` if the last block is a list item.
+    list: bool,
+}
+
+impl Builder {
+    // Turns the given lines into a block.
+    fn push(&mut self, current: Option<(Kind, Vec<&str>)>) {
+        let (kind, lines) = match current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let mut block = make_block(kind, &lines);
+
+        // Consecutive list items stay together.
+        let list = match kind {
+            Kind::Item(..) => true,
+            _ => false,
+        };
+        block.gap = !self.blocks.is_empty() &&
+                    (self.blank || !(list && self.list));
+        self.blank = false;
+        self.list = list;
+
+        self.blocks.push(block);
+    }
+}
+
+fn make_block(kind: Kind, lines: &[&str]) -> Block {
+    match kind {
+        Kind::Paragraph => {
+            Block::new(StyledString::new(),
+                       StyledString::new(),
+                       parse_inline(&lines.join(" "), Style::none()))
+        }
+        Kind::Heading(level) => {
+            let text = lines[0].trim_left_matches('#').trim();
+            let color = if level <= 2 {
+                ColorStyle::TitlePrimary
+            } else {
+                ColorStyle::TitleSecondary
+            };
+            let style = Style::from(color).with_effect(Effect::Bold);
+            Block::new(StyledString::new(),
+                       StyledString::new(),
+                       parse_inline(text, style))
+        }
+        Kind::Code => {
+            let style = Style::from(ColorStyle::Secondary);
+            Block::new(StyledString::plain("    "),
+                       StyledString::plain("    "),
+                       StyledString::styled(lines.join("\n"), style))
+        }
+        Kind::Quote => {
+            let bar = StyledString::styled("│ ", ColorStyle::Tertiary);
+            let style = Style::from(Effect::Italic);
+            Block::new(bar.clone(), bar, parse_inline(&lines.join(" "), style))
+        }
+        Kind::Item(indent, number) => {
+            let first = lines[0].trim_left();
+            let (marker, text) = match number {
+                Some(n) => {
+                    let dot = first.find(". ").unwrap_or(0);
+                    (format!("{}. ", n), &first[dot + 2..])
+                }
+                None => ("• ".to_string(), &first[2..]),
+            };
+            let mut text = text.to_string();
+            for line in &lines[1..] {
+                text.push(' ');
+                text.push_str(line);
+            }
+
+            let mut prefix = StyledString::plain(spaces(2 * indent));
+            prefix.append_styled(marker.as_str(), ColorStyle::Tertiary);
+            let continuation = StyledString::plain(spaces(prefix.width()));
+            Block::new(prefix,
+                       continuation,
+                       parse_inline(&text, Style::none()))
+        }
+        Kind::Rule => {
+            let mut block = Block::new(StyledString::new(),
+                                       StyledString::new(),
+                                       StyledString::new());
+            block.rule = true;
+            block
+        }
+    }
+}
+
+fn spaces(n: usize) -> String {
+    ::std::iter::repeat(' ').take(n).collect()
+}
+
+/// Parses inline markdown: emphasis, code and links.
+///
+/// `base` is the style of the surrounding text.
+pub fn parse_inline(text: &str, base: Style) -> StyledString {
+    let mut result = StyledString::new();
+    let mut current = String::new();
+    let mut bold = false;
+    let mut italic = false;
+
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+
+        if c == '\\' && rest.len() > 1 {
+            let next = rest[1..].chars().next().unwrap();
+            current.push(next);
+            i += 1 + next.len_utf8();
+            continue;
+        }
+
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                flush(&mut result, &mut current, base, bold, italic);
+                result.append_styled(&rest[1..end + 1],
+                                     ColorStyle::Secondary);
+                i += end + 2;
+                continue;
+            }
+        }
+
+        if rest.starts_with("**") || rest.starts_with("__") {
+            if bold || rest[2..].contains(&rest[..2]) {
+                flush(&mut result, &mut current, base, bold, italic);
+                bold = !bold;
+                i += 2;
+                continue;
+            }
+        }
+
+        if c == '*' || c == '_' {
+            // Don't break snake_case words.
+            let inside_word = c == '_' &&
+                              current.chars()
+                .last()
+                .map(|c| c.is_alphanumeric())
+                .unwrap_or(false) && !italic;
+            if !inside_word && (italic || rest[1..].contains(c)) {
+                flush(&mut result, &mut current, base, bold, italic);
+                italic = !italic;
+                i += 1;
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some((label, url, len)) = parse_link(rest) {
+                flush(&mut result, &mut current, base, bold, italic);
+                let style = Style::from(ColorStyle::Tertiary)
+                    .with_effect(Effect::Underline);
+                result.append_styled(label, style);
+                if url != label {
+                    result.append_styled(format!(" <{}>", url),
+                                         ColorStyle::Secondary);
+                }
+                i += len;
+                continue;
+            }
+        }
+
+        current.push(c);
+        i += c.len_utf8();
+    }
+    flush(&mut result, &mut current, base, bold, italic);
+
+    result
+}
+
+// Parses `[label](url)`. Returns the label, the url, and the total length.
+fn parse_link(text: &str) -> Option<(&str, &str, usize)> {
+    let middle = match text.find("](") {
+        Some(middle) => middle,
+        None => return None,
+    };
+    let end = match text[middle..].find(')') {
+        Some(end) => middle + end,
+        None => return None,
+    };
+
+    Some((&text[1..middle], &text[middle + 2..end], end + 1))
+}
+
+fn flush(result: &mut StyledString, current: &mut String, base: Style,
+         bold: bool, italic: bool) {
+    let style = if bold {
+        base.with_effect(Effect::Bold)
+    } else if italic {
+        base.with_effect(Effect::Italic)
+    } else {
+        base
+    };
+    let text = ::std::mem::replace(current, String::new());
+    result.append_styled(text, style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{ColorStyle, Effect, Style};
+
+    #[test]
+    fn inline() {
+        let text = parse_inline("a **b** *c* `d` [e](f) g_h", Style::none());
+        let link = Style::from(ColorStyle::Tertiary)
+            .with_effect(Effect::Underline);
+        assert_eq!(vec![("a ", Style::none()),
+                        ("b", Style::from(Effect::Bold)),
+                        (" ", Style::none()),
+                        ("c", Style::from(Effect::Italic)),
+                        (" ", Style::none()),
+                        ("d", Style::from(ColorStyle::Secondary)),
+                        (" ", Style::none()),
+                        ("e", link),
+                        (" <f>", Style::from(ColorStyle::Secondary)),
+                        (" g_h", Style::none())],
+                   text.spans());
+    }
+
+    #[test]
+    fn blocks_and_gaps() {
+        let text = "# Title\nSome\ntext\n\n- a\n- b\n  more\n\n> q\n";
+        let blocks = blocks(text);
+        let sources: Vec<_> = blocks.iter()
+            .map(|block| (block.content.source(), block.gap))
+            .collect();
+        assert_eq!(vec![("Title", false),
+                        ("Some text", true),
+                        ("a", true),
+                        ("b more", false),
+                        ("q", true)],
+                   sources);
+    }
+
+    #[test]
+    fn hanging_indent() {
+        let blocks = blocks("1. one two three");
+        let rows: Vec<_> = blocks[0]
+            .rows(10)
+            .into_iter()
+            .map(|row| row.source().to_string())
+            .collect();
+        assert_eq!(vec!["1. one two", "   three"], rows);
+    }
+}
@@ -0,0 +1,369 @@
+//! Styled text and inline markup.
+//!
+//! A [`StyledString`] is a piece of text where each part can use a different
+//! color or effect. It can be built piece by piece, or parsed from a simple
+//! markup with [`parse`].
+//!
+//! [`StyledString`]: struct.StyledString.html
+//! [`parse`]: fn.parse.html
+
+use theme::{Color, ColorStyle, Effect, Style};
+use unicode_width::UnicodeWidthStr;
+use utils::Row;
+
+/// Text made of spans, each with its own style.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::markup::StyledString;
+/// # use cursive::theme::{BaseColor, Color, Effect};
+/// let mut text = StyledString::plain("Status: ");
+/// text.append_styled("error", Color::Dark(BaseColor::Red));
+/// text.append_styled("!", Effect::Bold);
+///
+/// assert_eq!("Status: error!", text.source());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StyledString {
+    source: String,
+    // Contiguous spans, covering all of `source`.
+    spans: Vec<Span>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Span {
+    // Byte offsets into `source`.
+    start: usize,
+    end: usize,
+    style: Style,
+}
+
+impl StyledString {
+    /// Creates a new, empty `StyledString`.
+    pub fn new() -> Self {
+        StyledString {
+            source: String::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Creates a `StyledString` with the given text, without any style.
+    pub fn plain<S: Into<String>>(text: S) -> Self {
+        StyledString::styled(text, Style::none())
+    }
+
+    /// Creates a `StyledString` with the given text and style.
+    pub fn styled<S: Into<String>, T: Into<Style>>(text: S, style: T)
+                                                   -> Self {
+        let mut result = StyledString::new();
+        result.append_styled(text, style);
+        result
+    }
+
+    /// Appends some text, without any style.
+    pub fn append_plain<S: Into<String>>(&mut self, text: S) {
+        self.append_styled(text, Style::none());
+    }
+
+    /// Appends some text with the given style.
+    pub fn append_styled<S: Into<String>, T: Into<Style>>(&mut self, text: S,
+                                                          style: T) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+        let style = style.into();
+        let start = self.source.len();
+        self.source.push_str(&text);
+        let end = self.source.len();
+
+        // Merge with the previous span when possible.
+        if let Some(last) = self.spans.last_mut() {
+            if last.style == style {
+                last.end = end;
+                return;
+            }
+        }
+
+        self.spans.push(Span {
+            start: start,
+            end: end,
+            style: style,
+        });
+    }
+
+    /// Appends another `StyledString`.
+    pub fn append(&mut self, other: StyledString) {
+        for span in other.spans {
+            self.append_styled(&other.source[span.start..span.end],
+                               span.style);
+        }
+    }
+
+    /// Returns the text without any style.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the length of the text, in bytes.
+    pub fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    /// Returns `true` if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+
+    /// Returns the width of the text, in cells.
+    pub fn width(&self) -> usize {
+        self.source.width()
+    }
+
+    /// Returns each span of text, with its style.
+    pub fn spans(&self) -> Vec<(&str, Style)> {
+        self.spans_between(0, self.source.len())
+    }
+
+    /// Returns the spans of text in the given row, with their style.
+    ///
+    /// Use it with a [`LinesIterator`] on `self.source()` to wrap a styled
+    /// text: spans are cut at the row boundaries.
+    ///
+    /// [`LinesIterator`]: ../struct.LinesIterator.html
+    pub fn row_spans(&self, row: &Row) -> Vec<(&str, Style)> {
+        self.spans_between(row.start, row.end)
+    }
+
+    // Returns the spans between the two byte offsets.
+    fn spans_between(&self, start: usize, end: usize) -> Vec<(&str, Style)> {
+        self.spans
+            .iter()
+            .filter(|span| span.end > start && span.start < end)
+            .map(|span| {
+                let from = if span.start > start { span.start } else { start };
+                let to = if span.end < end { span.end } else { end };
+                (&self.source[from..to], span.style)
+            })
+            .collect()
+    }
+}
+
+impl From<String> for StyledString {
+    fn from(text: String) -> Self {
+        StyledString::plain(text)
+    }
+}
+
+impl<'a> From<&'a str> for StyledString {
+    fn from(text: &'a str) -> Self {
+        StyledString::plain(text)
+    }
+}
+
+/// Parses some text with inline markup.
+///
+/// * `*text*` is printed in bold.
+/// * `[tag]text[/]` applies `tag` to `text`. Tags can be nested.
+///     * Colors, using the theme syntax: `[red]`, `[light blue]`,
+///       `[#ff8800]`.
+///     * Palette styles: `[primary]`, `[secondary]`, `[tertiary]`,
+///       `[title_primary]`, `[title_secondary]`, `[highlight]`,
+///       `[highlight_inactive]`.
+///     * Effects: `[bold]`, `[italic]`, `[underline]`, `[reverse]`.
+/// * `\` escapes the next character.
+///
+/// Effects of nested tags add up, and the innermost color wins.
+/// Unknown tags and unmatched `*` are kept as plain text.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::markup;
+/// let text = markup::parse("Press [red]*q*[/] to quit");
+/// assert_eq!("Press q to quit", text.source());
+/// ```
+pub fn parse(text: &str) -> StyledString {
+    let mut parser = Parser {
+        result: StyledString::new(),
+        current: String::new(),
+        stack: Vec::new(),
+        bold: false,
+    };
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let next = chars.next().map(|(_, next)| next).unwrap_or('\\');
+                parser.current.push(next);
+            }
+            '*' if parser.bold || has_closing_star(&text[i + 1..]) => {
+                parser.flush();
+                parser.bold = !parser.bold;
+            }
+            '[' => {
+                let end = match text[i..].find(']') {
+                    Some(len) => i + len,
+                    None => {
+                        parser.current.push(c);
+                        continue;
+                    }
+                };
+                if !parser.open_tag(&text[i + 1..end]) {
+                    parser.current.push(c);
+                    continue;
+                }
+                // Skip the tag.
+                while chars.peek().map(|&(j, _)| j <= end).unwrap_or(false) {
+                    chars.next();
+                }
+            }
+            c => parser.current.push(c),
+        }
+    }
+    parser.flush();
+
+    parser.result
+}
+
+struct Parser {
+    result: StyledString,
+    // Text not yet added to `result`.
+    current: String,
+    // Styles of the opened tags.
+    stack: Vec<Style>,
+    bold: bool,
+}
+
+impl Parser {
+    // Adds the pending text to the result.
+    fn flush(&mut self) {
+        if self.current.is_empty() {
+            return;
+        }
+
+        let mut style = Style::none();
+        for tag in &self.stack {
+            if tag.color.is_some() {
+                style.color = tag.color;
+            }
+            if tag.background.is_some() {
+                style.background = tag.background;
+            }
+            style.effects = style.effects.union(tag.effects);
+        }
+        if self.bold {
+            style.effects.insert(Effect::Bold);
+        }
+
+        let text = ::std::mem::replace(&mut self.current, String::new());
+        self.result.append_styled(text, style);
+    }
+
+    // Opens or closes a tag. Returns `false` if `tag` is not valid.
+    fn open_tag(&mut self, tag: &str) -> bool {
+        if tag == "/" {
+            if self.stack.is_empty() {
+                return false;
+            }
+            self.flush();
+            self.stack.pop();
+            return true;
+        }
+
+        match parse_tag(tag) {
+            Some(style) => {
+                self.flush();
+                self.stack.push(style);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn parse_tag(tag: &str) -> Option<Style> {
+    Some(match tag {
+        "bold" => Style::from(Effect::Bold),
+        "italic" => Style::from(Effect::Italic),
+        "underline" => Style::from(Effect::Underline),
+        "reverse" => Style::from(Effect::Reverse),
+        "primary" => Style::from(ColorStyle::Primary),
+        "secondary" => Style::from(ColorStyle::Secondary),
+        "tertiary" => Style::from(ColorStyle::Tertiary),
+        "title_primary" => Style::from(ColorStyle::TitlePrimary),
+        "title_secondary" => Style::from(ColorStyle::TitleSecondary),
+        "highlight" => Style::from(ColorStyle::Highlight),
+        "highlight_inactive" => Style::from(ColorStyle::HighlightInactive),
+        tag => return Color::parse(tag).map(Style::from),
+    })
+}
+
+// Returns `true` if `text` has a `*` which is not escaped.
+fn has_closing_star(text: &str) -> bool {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{BaseColor, Color, Effect, Style};
+    use utils::LinesIterator;
+
+    #[test]
+    fn append_merges_spans() {
+        let mut text = StyledString::plain("a");
+        text.append_plain("b");
+        text.append_styled("c", Effect::Bold);
+        assert_eq!(vec![("ab", Style::none()),
+                        ("c", Style::from(Effect::Bold))],
+                   text.spans());
+    }
+
+    #[test]
+    fn wrapped_rows_keep_spans() {
+        let mut text = StyledString::plain("aaa ");
+        text.append_styled("bbb ccc", Effect::Bold);
+
+        let rows: Vec<_> = LinesIterator::new(text.source(), 5).collect();
+        assert_eq!(3, rows.len());
+        assert_eq!(vec![("aaa", Style::none())], text.row_spans(&rows[0]));
+        assert_eq!(vec![("bbb", Style::from(Effect::Bold))],
+                   text.row_spans(&rows[1]));
+    }
+
+    #[test]
+    fn markup() {
+        let red = Style::from(Color::Dark(BaseColor::Red));
+        let text = parse("a *b* [red]c *d*[/] e\\*");
+        assert_eq!(vec![("a ", Style::none()),
+                        ("b", Style::from(Effect::Bold)),
+                        (" ", Style::none()),
+                        ("c ", red),
+                        ("d", red.with_effect(Effect::Bold)),
+                        (" e*", Style::none())],
+                   text.spans());
+    }
+
+    #[test]
+    fn invalid_markup() {
+        assert_eq!(StyledString::plain("[nope]a[/] 2*3"),
+                   parse("[nope]a[/] 2*3"));
+        assert_eq!(StyledString::plain("[red"), parse("[red"));
+        assert_eq!(StyledString::plain("[#€]x"), parse("[#€]x"));
+        assert_eq!(StyledString::plain("a [#é1]b"), parse("a [#é1]b"));
+        assert_eq!(StyledString::plain("[#12g]b"), parse("[#12g]b"));
+    }
+}
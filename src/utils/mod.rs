@@ -4,10 +4,26 @@ use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 mod lines_iterator;
+mod pattern;
 mod reader;
+mod rope;
+mod undo;
+
+pub mod ansi;
+pub mod clipboard;
+pub mod edit_mode;
+pub mod highlight;
+pub mod history;
+pub mod markdown;
+pub mod markup;
+pub mod validation;
 
 pub use self::lines_iterator::{LinesIterator, Row};
+pub use self::markup::StyledString;
+pub use self::pattern::{Pattern, PatternError};
 pub use self::reader::ProgressReader;
+pub use self::rope::Rope;
+pub use self::undo::{Change, UndoHistory};
 
 /// The length and width of a part of a string.
 pub struct Prefix {
@@ -100,6 +116,51 @@ pub fn simple_suffix(text: &str, width: usize) -> Prefix {
     suffix(text.graphemes(true), width, "")
 }
 
+/// Computes the length (number of bytes) and width of the shortest prefix
+/// at least `width` cells wide.
+///
+/// This is the part of `text` hidden when scrolling `width` cells to the
+/// right. It is wider than `width` if a wide grapheme is cut in half.
+pub fn skip_width(text: &str, width: usize) -> Prefix {
+    let mut result = Prefix {
+        length: 0,
+        width: 0,
+    };
+    for grapheme in text.graphemes(true) {
+        if result.width >= width {
+            break;
+        }
+        result.length += grapheme.len();
+        result.width += grapheme.width();
+    }
+    result
+}
+
+/// Matches `query` against `text`, ignoring case.
+///
+/// The characters of `query` must appear in `text` in the same order, but
+/// not necessarily next to each other. Returns the byte offsets of the
+/// matched characters in `text`, or `None` if it doesn't match.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::fuzzy_match;
+/// assert_eq!(Some(vec![0, 2, 6]), fuzzy_match("slv", "SelectView"));
+/// assert_eq!(None, fuzzy_match("vs", "SelectView"));
+/// ```
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut chars = text.char_indices();
+    for q in query.chars() {
+        match chars.find(|&(_, c)| c.to_lowercase().eq(q.to_lowercase())) {
+            Some((i, _)) => positions.push(i),
+            None => return None,
+        }
+    }
+    Some(positions)
+}
+
 #[cfg(test)]
 mod tests {
     use utils;
@@ -110,4 +171,21 @@ mod tests {
         assert_eq!(utils::prefix("abra a".split(' '), 5, " ").length, 4);
         assert_eq!(utils::prefix("a a br".split(' '), 5, " ").length, 3);
     }
+
+    #[test]
+    fn test_skip_width() {
+        assert_eq!(utils::skip_width("abcd", 2).length, 2);
+        assert_eq!(utils::skip_width("ab", 5).length, 2);
+        // A wide character is hidden entirely.
+        let skipped = utils::skip_width("a\u{4e2d}b", 2);
+        assert_eq!((4, 3), (skipped.length, skipped.width));
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(Some(vec![]), utils::fuzzy_match("", "abc"));
+        assert_eq!(Some(vec![0, 3]),
+                   utils::fuzzy_match("\u{e9}c", "\u{c9}tc"));
+        assert_eq!(None, utils::fuzzy_match("abcd", "abc"));
+    }
 }
@@ -0,0 +1,613 @@
+use std::iter::Peekable;
+use std::mem;
+use std::str::Chars;
+
+// Maximum number of instructions in a compiled pattern.
+//
+// Repetition counts copy the repeated item, so `(a{100}){100}` would
+// otherwise get huge.
+const MAX_SIZE: usize = 10_000;
+
+/// Simple regular expression, to search text.
+///
+/// Supports a common subset of the usual syntax:
+///
+/// * `.` matches any character but a newline.
+/// * `[abc]`, `[a-z]` and `[^abc]` match a class of characters.
+/// * `\d`, `\w` and `\s` match digits, word characters and spaces
+///   (`\D`, `\W` and `\S` match anything else).
+/// * `^` and `$` match the beginning and end of a line.
+/// * `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}` repeat the previous item.
+/// * `(...)` groups items, and `|` separates alternatives.
+/// * `\` escapes a special character.
+///
+/// Matching follows every possible match at once instead of backtracking,
+/// so it takes a time linear in the length of the text.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::Pattern;
+/// let pattern = Pattern::new(r"\d+-(\d+)?").unwrap();
+/// assert_eq!(Some((4, 10)), pattern.find("abc 12-345"));
+///
+/// let pattern = Pattern::literal("HELLO").case_insensitive(true);
+/// assert!(pattern.is_match("Hello world"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    program: Vec<Inst>,
+    case_insensitive: bool,
+}
+
+/// Error returned when parsing an invalid pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// A `(` is never closed.
+    UnclosedGroup,
+    /// A `)` has no matching `(`.
+    UnmatchedParen,
+    /// A `[` is never closed.
+    UnclosedClass,
+    /// The pattern ends with a `\`.
+    DanglingEscape,
+    /// A repetition like `*` follows nothing.
+    NothingToRepeat,
+    /// A `{` is not followed by a valid repetition count, like `{3,1}`.
+    InvalidRepeat,
+    /// The pattern is too large, usually because of nested repetition
+    /// counts like `(a{100}){100}`.
+    TooLarge,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Char(char),
+    Any,
+    // Inclusive ranges, and whether the class is negated.
+    Class(Vec<(char, char)>, bool),
+    LineStart,
+    LineEnd,
+    Group(Vec<Vec<Node>>),
+    // Minimum and maximum number of repetitions.
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+// Instruction of a compiled pattern.
+#[derive(Clone, Debug)]
+enum Inst {
+    // Matches a single character, with a `Char`, `Any` or `Class` node.
+    Char(Node),
+    LineStart,
+    LineEnd,
+    // Continues at both places, preferring the first one.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+impl Pattern {
+    /// Parses a regular expression.
+    pub fn new(source: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser { chars: source.chars().peekable() };
+        let alternatives = try!(parser.alternatives());
+        if parser.chars.next().is_some() {
+            // We only stop early on a closing parenthesis.
+            return Err(PatternError::UnmatchedParen);
+        }
+
+        let mut compiler = Compiler { program: Vec::new() };
+        try!(compiler.alternatives(&alternatives));
+        try!(compiler.push(Inst::Match));
+
+        Ok(Pattern {
+            program: compiler.program,
+            case_insensitive: false,
+        })
+    }
+
+    /// Creates a pattern matching exactly the given text.
+    pub fn literal(text: &str) -> Self {
+        let mut program: Vec<Inst> =
+            text.chars().map(|c| Inst::Char(Node::Char(c))).collect();
+        program.push(Inst::Match);
+        Pattern {
+            program: program,
+            case_insensitive: false,
+        }
+    }
+
+    /// Sets whether upper and lower case letters are the same.
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        self.case_insensitive = case_insensitive;
+    }
+
+    /// Sets whether upper and lower case letters are the same.
+    ///
+    /// Chainable variant.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.set_case_insensitive(case_insensitive);
+        self
+    }
+
+    /// Returns `true` if the pattern matches somewhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Returns `true` if the pattern matches the entire `text`.
+    pub fn is_full_match(&self, text: &str) -> bool {
+        self.run(text, 0, true).is_some()
+    }
+
+    /// Returns the byte range of the first match in `text`.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.find_at(text, 0)
+    }
+
+    /// Returns the byte range of the first match starting at `start` or
+    /// after.
+    pub fn find_at(&self, text: &str, start: usize)
+                   -> Option<(usize, usize)> {
+        self.run(text, start, false)
+    }
+
+    /// Returns the byte ranges of all matches in `text`.
+    ///
+    /// Matches don't overlap, and empty matches are skipped.
+    pub fn matches(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        let mut pos = 0;
+        while let Some((start, end)) = self.find_at(text, pos) {
+            if start < end {
+                result.push((start, end));
+                pos = end;
+            } else {
+                // Skip the empty match.
+                match text[start..].chars().next() {
+                    Some(c) => pos = start + c.len_utf8(),
+                    None => break,
+                }
+            }
+        }
+        result
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, PatternError> {
+        let mut alternatives = vec![try!(self.sequence())];
+        while self.chars.peek() == Some(&'|') {
+            self.chars.next();
+            alternatives.push(try!(self.sequence()));
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, PatternError> {
+        let mut nodes = Vec::new();
+        loop {
+            let c = match self.chars.peek() {
+                None | Some(&'|') | Some(&')') => return Ok(nodes),
+                Some(&c) => c,
+            };
+            self.chars.next();
+
+            let node = match c {
+                '(' => {
+                    let alternatives = try!(self.alternatives());
+                    if self.chars.next() != Some(')') {
+                        return Err(PatternError::UnclosedGroup);
+                    }
+                    Node::Group(alternatives)
+                }
+                '[' => try!(self.class()),
+                '.' => Node::Any,
+                '^' => Node::LineStart,
+                '$' => Node::LineEnd,
+                '\\' => try!(self.escape()),
+                '*' | '+' | '?' | '{' => {
+                    return Err(PatternError::NothingToRepeat)
+                }
+                c => Node::Char(c),
+            };
+            nodes.push(try!(self.repeat(node)));
+        }
+    }
+
+    // Parses the repetitions following `node`, if any.
+    fn repeat(&mut self, node: Node) -> Result<Node, PatternError> {
+        let (min, max) = match self.chars.peek() {
+            Some(&'*') => (0, None),
+            Some(&'+') => (1, None),
+            Some(&'?') => (0, Some(1)),
+            Some(&'{') => {
+                self.chars.next();
+                let min = try!(try!(self.number())
+                    .ok_or(PatternError::InvalidRepeat));
+                let max = match self.chars.next() {
+                    Some('}') => Some(min),
+                    Some(',') if self.chars.peek() == Some(&'}') => {
+                        self.chars.next();
+                        None
+                    }
+                    Some(',') => {
+                        let max = try!(self.number());
+                        if self.chars.next() != Some('}') {
+                            return Err(PatternError::InvalidRepeat);
+                        }
+                        match max {
+                            Some(max) if max >= min => Some(max),
+                            _ => return Err(PatternError::InvalidRepeat),
+                        }
+                    }
+                    _ => return Err(PatternError::InvalidRepeat),
+                };
+                return self.repeat(Node::Repeat(Box::new(node), min, max));
+            }
+            _ => return Ok(node),
+        };
+        self.chars.next();
+        self.repeat(Node::Repeat(Box::new(node), min, max))
+    }
+
+    // Fails if the number doesn't fit in a `usize`.
+    fn number(&mut self) -> Result<Option<usize>, PatternError> {
+        let mut result = None;
+        while let Some(digit) = self.chars
+            .peek()
+            .and_then(|c| c.to_digit(10)) {
+            self.chars.next();
+            let number = try!(result.unwrap_or(0usize)
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(digit as usize))
+                .ok_or(PatternError::InvalidRepeat));
+            result = Some(number);
+        }
+        Ok(result)
+    }
+
+    fn class(&mut self) -> Result<Node, PatternError> {
+        let negated = self.chars.peek() == Some(&'^');
+        if negated {
+            self.chars.next();
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = match self.chars.next() {
+                None => return Err(PatternError::UnclosedClass),
+                Some(']') if !first => {
+                    return Ok(Node::Class(ranges, negated))
+                }
+                Some(c) => c,
+            };
+            first = false;
+
+            let start = if c == '\\' {
+                match try!(self.escape()) {
+                    Node::Char(c) => c,
+                    Node::Class(ref class, false) => {
+                        ranges.extend(class);
+                        continue;
+                    }
+                    // Negated shortcuts are not supported in classes.
+                    _ => continue,
+                }
+            } else {
+                c
+            };
+
+            // Is this a range?
+            let mut lookahead = self.chars.clone();
+            if lookahead.next() == Some('-') {
+                match lookahead.next() {
+                    Some(']') | None => (),
+                    Some(end) => {
+                        self.chars = lookahead;
+                        ranges.push((start, end));
+                        continue;
+                    }
+                }
+            }
+            ranges.push((start, start));
+        }
+    }
+
+    // Parses the character following a backslash.
+    fn escape(&mut self) -> Result<Node, PatternError> {
+        let digits = vec![('0', '9')];
+        let words = vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+        let spaces = vec![(' ', ' '), ('\t', '\r')];
+
+        let c = try!(self.chars.next().ok_or(PatternError::DanglingEscape));
+        Ok(match c {
+            'd' => Node::Class(digits, false),
+            'D' => Node::Class(digits, true),
+            'w' => Node::Class(words, false),
+            'W' => Node::Class(words, true),
+            's' => Node::Class(spaces, false),
+            'S' => Node::Class(spaces, true),
+            'n' => Node::Char('\n'),
+            't' => Node::Char('\t'),
+            c => Node::Char(c),
+        })
+    }
+}
+
+// Turns the parsed nodes into a program for `Pattern::run`.
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> Result<usize, PatternError> {
+        if self.program.len() >= MAX_SIZE {
+            return Err(PatternError::TooLarge);
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node>])
+                    -> Result<(), PatternError> {
+        let (last, others) = alternatives.split_last().unwrap();
+
+        let mut jumps = Vec::new();
+        for nodes in others {
+            let split = try!(self.push(Inst::Split(0, 0)));
+            try!(self.sequence(nodes));
+            jumps.push(try!(self.push(Inst::Jump(0))));
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+        try!(self.sequence(last));
+
+        let end = self.program.len();
+        for jump in jumps {
+            self.program[jump] = Inst::Jump(end);
+        }
+        Ok(())
+    }
+
+    fn sequence(&mut self, nodes: &[Node]) -> Result<(), PatternError> {
+        for node in nodes {
+            try!(self.node(node));
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), PatternError> {
+        match *node {
+            Node::LineStart => try!(self.push(Inst::LineStart)),
+            Node::LineEnd => try!(self.push(Inst::LineEnd)),
+            Node::Group(ref alternatives) => {
+                return self.alternatives(alternatives)
+            }
+            Node::Repeat(ref node, min, max) => {
+                return self.repeat(node, min, max)
+            }
+            ref node => try!(self.push(Inst::Char(node.clone()))),
+        };
+        Ok(())
+    }
+
+    // Copies `node` `min` times, followed by the optional repetitions.
+    fn repeat(&mut self, node: &Node, min: usize, max: Option<usize>)
+              -> Result<(), PatternError> {
+        for _ in 0..min {
+            try!(self.node(node));
+        }
+
+        match max {
+            None => {
+                let split = try!(self.push(Inst::Split(0, 0)));
+                try!(self.node(node));
+                try!(self.push(Inst::Jump(split)));
+                let end = self.program.len();
+                self.program[split] = Inst::Split(split + 1, end);
+            }
+            Some(max) => {
+                // Each optional copy skips all the following ones.
+                let mut splits = Vec::new();
+                for _ in min..max {
+                    splits.push(try!(self.push(Inst::Split(0, 0))));
+                    try!(self.node(node));
+                }
+                let end = self.program.len();
+                for split in splits {
+                    self.program[split] = Inst::Split(split + 1, end);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pattern {
+    // Runs the program on `text`, following all the threads in lockstep.
+    //
+    // Threads are kept by priority, so the first one to reach `Match`
+    // gives the same match a backtracking matcher would.
+    //
+    // If `full` is `true`, only looks for a match of the entire text.
+    fn run(&self, text: &str, start: usize, full: bool)
+           -> Option<(usize, usize)> {
+        // Threads, as their instruction and the start of their match.
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        // Last step each instruction was added at, to add it only once.
+        let mut seen = vec![0; self.program.len()];
+        let mut step = 1;
+
+        let mut result = None;
+        let mut pos = start;
+        loop {
+            // Try to start a new match here, with the lowest priority.
+            if result.is_none() && (!full || pos == start) {
+                self.add_thread(text, &mut current, &mut seen, step,
+                                (0, pos), pos);
+            }
+            if current.is_empty() && (result.is_some() || full) {
+                break;
+            }
+
+            let c = text[pos..].chars().next();
+            step += 1;
+            for &(pc, match_start) in &current {
+                match self.program[pc] {
+                    Inst::Match if full => {
+                        if pos == text.len() {
+                            return Some((match_start, pos));
+                        }
+                    }
+                    Inst::Match => {
+                        // Other threads have a lower priority.
+                        result = Some((match_start, pos));
+                        break;
+                    }
+                    Inst::Char(ref node) => {
+                        if let Some(c) = c {
+                            if self.accepts(node, c) {
+                                self.add_thread(text, &mut next, &mut seen,
+                                                step, (pc + 1, match_start),
+                                                pos + c.len_utf8());
+                            }
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            match c {
+                Some(c) => pos += c.len_utf8(),
+                None => break,
+            }
+            mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+
+        result
+    }
+
+    // Adds a thread, following the jumps and checking the assertions at
+    // `pos` right away.
+    fn add_thread(&self, text: &str, threads: &mut Vec<(usize, usize)>,
+                  seen: &mut [usize], step: usize,
+                  (pc, match_start): (usize, usize), pos: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if seen[pc] == step {
+                continue;
+            }
+            seen[pc] = step;
+
+            match self.program[pc] {
+                Inst::Jump(target) => stack.push(target),
+                Inst::Split(first, second) => {
+                    stack.push(second);
+                    stack.push(first);
+                }
+                Inst::LineStart => {
+                    if pos == 0 || text[..pos].ends_with('\n') {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::LineEnd => {
+                    if pos == text.len() || text[pos..].starts_with('\n') {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::Char(_) | Inst::Match => {
+                    threads.push((pc, match_start))
+                }
+            }
+        }
+    }
+
+    // Returns `true` if `node` matches the single character `c`.
+    fn accepts(&self, node: &Node, c: char) -> bool {
+        match *node {
+            Node::Char(expected) => {
+                c == expected ||
+                (self.case_insensitive &&
+                 c.to_lowercase().eq(expected.to_lowercase()))
+            }
+            Node::Any => c != '\n',
+            Node::Class(ref ranges, negated) => {
+                let in_class =
+                    |c: char| ranges.iter().any(|&(a, b)| a <= c && c <= b);
+                let found = in_class(c) ||
+                            (self.case_insensitive &&
+                             (c.to_lowercase().any(&in_class) ||
+                              c.to_uppercase().any(&in_class)));
+                found != negated
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syntax() {
+        let find = |pattern: &str, text: &str| {
+            Pattern::new(pattern).unwrap().find(text)
+        };
+
+        assert_eq!(Some((1, 4)), find("b.d", "abcde"));
+        assert_eq!(Some((0, 5)), find("a(b|cd)*e", "abcde"));
+        assert_eq!(Some((2, 5)), find("[^a-c]+", "abdex"));
+        assert_eq!(Some((4, 6)), find(r"\w{2}$", "ab-\ncd"));
+        assert_eq!(Some((3, 4)), find(r"^c", "ab\ncd"));
+        assert_eq!(Some((0, 2)), find("x?ab?", "ab"));
+        assert_eq!(None, find(r"\d{3,}", "a12b"));
+
+        let error = |pattern: &str| Pattern::new(pattern).err();
+        assert_eq!(Some(PatternError::UnclosedGroup), error("(ab"));
+        assert_eq!(Some(PatternError::UnmatchedParen), error("ab)"));
+        assert_eq!(Some(PatternError::NothingToRepeat), error("*a"));
+        assert_eq!(Some(PatternError::InvalidRepeat), error("a{2"));
+        assert_eq!(Some(PatternError::InvalidRepeat),
+                   error("a{99999999999999999999}"));
+        assert_eq!(Some(PatternError::InvalidRepeat),
+                   error("a{1,99999999999999999999}"));
+        assert_eq!(Some(PatternError::InvalidRepeat), error("a{3,1}"));
+    }
+
+    #[test]
+    fn matches() {
+        let pattern = Pattern::literal("ab").case_insensitive(true);
+        assert_eq!(vec![(0, 2), (3, 5)], pattern.matches("aB AbA"));
+
+        let pattern = Pattern::new("x*").unwrap();
+        assert_eq!(vec![(1, 3)], pattern.matches("axxb"));
+
+        let pattern = Pattern::new(r"\d{4}-\d{2}").unwrap();
+        assert!(pattern.is_full_match("2017-06"));
+        assert!(!pattern.is_full_match("2017-06-01"));
+    }
+
+    #[test]
+    fn long_line() {
+        let line = "a".repeat(100_000);
+        let pattern = Pattern::new(".*").unwrap();
+        assert_eq!(Some((0, line.len())), pattern.find(&line));
+        assert!(pattern.is_full_match(&line));
+
+        // Would backtrack for ages.
+        let pattern = Pattern::new("(a|aa)*b").unwrap();
+        assert_eq!(None, pattern.find(&line));
+        assert!(!pattern.is_full_match(&line[..1000]));
+
+        let error = Pattern::new("((a{100}){100}){100}").err();
+        assert_eq!(Some(PatternError::TooLarge), error);
+    }
+}
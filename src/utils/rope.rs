@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+use std::fmt;
+
+/// Chunks larger than this are split, if they contain several lines.
+const MAX_CHUNK: usize = 8192;
+/// Chunks smaller than this are merged with their neighbour.
+const MIN_CHUNK: usize = 1024;
+
+/// Text buffer made for cheap edits in large documents.
+///
+/// The text is stored in chunks of a few kilobytes, so an edit only
+/// touches a small part of it.
+///
+/// Each chunk contains entire paragraphs (lines ending with `\n`), so any
+/// range inside a single paragraph can be borrowed as a `&str`.
+///
+/// This means a single line is never split: a very long line stays in one
+/// large chunk, and each edit in it copies the whole line. Editing also
+/// updates the offsets of the following chunks, which is cheap but still
+/// grows with the number of chunks.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::Rope;
+/// let mut rope = Rope::from("Hello\nworld");
+/// rope.insert(5, ",");
+/// rope.remove(0, 1);
+///
+/// assert_eq!("ello,\nworld", rope.to_string());
+/// assert_eq!("world", rope.slice(6, 11));
+/// assert_eq!(6, rope.line_start(9));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rope {
+    chunks: Vec<String>,
+    // Byte offset of the start of each chunk.
+    starts: Vec<usize>,
+    len: usize,
+    // Number of lines.
+    lines: usize,
+}
+
+new_default!(Rope);
+
+impl Rope {
+    /// Creates a new, empty rope.
+    pub fn new() -> Self {
+        Rope {
+            chunks: vec![String::new()],
+            starts: vec![0],
+            len: 0,
+            lines: 1,
+        }
+    }
+
+    /// Returns the length of the text, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of lines.
+    ///
+    /// This is one more than the number of `\n` in the text.
+    pub fn line_count(&self) -> usize {
+        self.lines
+    }
+
+    /// Inserts `text` at byte offset `pos`.
+    pub fn insert(&mut self, pos: usize, text: &str) {
+        self.replace(pos, pos, text);
+    }
+
+    /// Removes the text between the two byte offsets, and returns it.
+    pub fn remove(&mut self, start: usize, end: usize) -> String {
+        self.replace(start, end, "")
+    }
+
+    /// Replaces the text between the two byte offsets with `text`.
+    ///
+    /// Returns the replaced text.
+    pub fn replace(&mut self, start: usize, end: usize, text: &str)
+                   -> String {
+        assert!(start <= end && end <= self.len);
+
+        let i = self.chunk_at(start);
+        let j = self.chunk_at(end);
+        let local_start = start - self.starts[i];
+        let local_end = end - self.starts[j];
+
+        let removed = self.slice(start, end).into_owned();
+
+        // Merge everything into the first chunk.
+        let mut chunk = self.chunks[i][..local_start].to_string();
+        chunk.push_str(text);
+        chunk.push_str(&self.chunks[j][local_end..]);
+        self.chunks[i] = chunk;
+        self.chunks.drain(i + 1..j + 1);
+        self.starts.truncate(i + 1);
+
+        self.balance(i);
+        self.update_starts(i);
+        self.lines = self.lines + text.matches('\n').count() -
+                     removed.matches('\n').count();
+
+        removed
+    }
+
+    /// Returns the text between the two byte offsets.
+    ///
+    /// Borrows the text when possible, for instance when the range is
+    /// inside a single paragraph.
+    pub fn slice(&self, start: usize, end: usize) -> Cow<str> {
+        let i = self.chunk_at(start);
+        let chunk_start = self.starts[i];
+        if end <= chunk_start + self.chunks[i].len() {
+            return Cow::Borrowed(&self.chunks[i][start - chunk_start..
+                                                 end - chunk_start]);
+        }
+
+        let mut result = String::with_capacity(end - start);
+        for (chunk, &chunk_start) in self.chunks[i..]
+            .iter()
+            .zip(&self.starts[i..]) {
+            if chunk_start >= end {
+                break;
+            }
+            let from = if start > chunk_start {
+                start - chunk_start
+            } else {
+                0
+            };
+            let to = if end < chunk_start + chunk.len() {
+                end - chunk_start
+            } else {
+                chunk.len()
+            };
+            result.push_str(&chunk[from..to]);
+        }
+        Cow::Owned(result)
+    }
+
+    /// Returns the byte offset of the start of the line containing `pos`.
+    pub fn line_start(&self, pos: usize) -> usize {
+        let i = self.chunk_at(pos);
+        let chunk_start = self.starts[i];
+        self.chunks[i][..pos - chunk_start]
+            .rfind('\n')
+            .map(|n| chunk_start + n + 1)
+            .unwrap_or(chunk_start)
+    }
+
+    /// Returns the byte offset of the end of the line containing `pos`.
+    ///
+    /// This is the offset of the `\n` ending the line, or the length of the
+    /// text for the last line.
+    pub fn line_end(&self, pos: usize) -> usize {
+        let i = self.chunk_at(pos);
+        let chunk_start = self.starts[i];
+        self.chunks[i][pos - chunk_start..]
+            .find('\n')
+            .map(|n| pos + n)
+            .unwrap_or(chunk_start + self.chunks[i].len())
+    }
+
+    /// Returns the line containing `pos`, without the ending `\n`.
+    pub fn line(&self, pos: usize) -> &str {
+        let i = self.chunk_at(pos);
+        let chunk_start = self.starts[i];
+        let start = self.line_start(pos) - chunk_start;
+        let end = self.line_end(pos) - chunk_start;
+        &self.chunks[i][start..end]
+    }
+
+    // Returns the index of the chunk containing `pos`.
+    //
+    // On a boundary, returns the chunk starting there.
+    fn chunk_at(&self, pos: usize) -> usize {
+        match self.starts.binary_search(&pos) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        }
+    }
+
+    // Splits or merges the chunk `i` to keep chunks at a reasonable size.
+    fn balance(&mut self, i: usize) {
+        if self.chunks[i].len() > MAX_CHUNK {
+            let (left, right) = split(&self.chunks[i]);
+            if !left.is_empty() && !right.is_empty() {
+                let (left, right) = (left.to_string(), right.to_string());
+                self.chunks[i] = left;
+                self.chunks.insert(i + 1, right);
+                self.balance(i + 1);
+                self.balance(i);
+            }
+        } else if self.chunks[i].len() < MIN_CHUNK && self.chunks.len() > 1 {
+            // Merge with the next chunk, or the previous one for the last.
+            let (a, b) = if i + 1 < self.chunks.len() {
+                (i, i + 1)
+            } else {
+                (i - 1, i)
+            };
+            if self.chunks[a].len() + self.chunks[b].len() <= MAX_CHUNK ||
+               self.chunks[i].is_empty() {
+                let next = self.chunks.remove(b);
+                self.chunks[a].push_str(&next);
+            }
+        }
+    }
+
+    // Recomputes the chunk start offsets, from chunk `i`.
+    fn update_starts(&mut self, i: usize) {
+        let i = if i < self.starts.len() { i } else { 0 };
+        self.starts.truncate(i);
+        let mut start = self.starts
+            .last()
+            .map(|&last| last + self.chunks[i - 1].len())
+            .unwrap_or(0);
+        for chunk in &self.chunks[i..] {
+            self.starts.push(start);
+            start += chunk.len();
+        }
+        self.len = start;
+    }
+}
+
+// Splits a chunk in two halves, at a line boundary.
+fn split(chunk: &str) -> (&str, &str) {
+    let mut middle = chunk.len() / 2;
+    while !chunk.is_char_boundary(middle) {
+        middle += 1;
+    }
+    let cut = chunk[middle..]
+        .find('\n')
+        .map(|n| middle + n + 1)
+        .or_else(|| chunk[..middle].rfind('\n').map(|n| n + 1))
+        .unwrap_or(chunk.len());
+    chunk.split_at(cut)
+}
+
+impl<'a> From<&'a str> for Rope {
+    fn from(text: &'a str) -> Self {
+        let mut rope = Rope::new();
+        rope.insert(0, text);
+        rope
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        Rope::from(text.as_str())
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in &self.chunks {
+            try!(f.write_str(chunk));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(rope: &Rope) {
+        // Chunks contain whole lines, and are not empty.
+        for (i, chunk) in rope.chunks.iter().enumerate() {
+            assert!(chunk.len() <= MAX_CHUNK);
+            if i + 1 < rope.chunks.len() {
+                assert!(chunk.ends_with('\n'));
+            }
+            if rope.chunks.len() > 1 {
+                assert!(!chunk.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn large_edits() {
+        let line = "0123456789abcdefghijklmnopqrstuvwxyz\n";
+        let text: String = ::std::iter::repeat(line).take(1000).collect();
+        let mut rope = Rope::from(text.as_str());
+        let mut expected = text.clone();
+        check(&rope);
+        assert!(rope.chunks.len() > 2);
+
+        rope.insert(10_000, "hello\nworld");
+        expected.insert_str(10_000, "hello\nworld");
+        check(&rope);
+        assert_eq!(expected, rope.to_string());
+
+        rope.remove(5_000, 30_000);
+        expected.drain(5_000..30_000);
+        check(&rope);
+        assert_eq!(expected, rope.to_string());
+        assert_eq!(expected.matches('\n').count() + 1, rope.line_count());
+
+        assert_eq!(&expected[100..10_000], rope.slice(100, 10_000));
+        let pos = 5_010;
+        assert_eq!(expected[..pos].rfind('\n').unwrap() + 1,
+                   rope.line_start(pos));
+        assert_eq!(pos + expected[pos..].find('\n').unwrap(),
+                   rope.line_end(pos));
+
+        rope.remove(0, rope.len());
+        check(&rope);
+        assert!(rope.is_empty());
+    }
+}
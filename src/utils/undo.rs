@@ -0,0 +1,289 @@
+use std::time::{Duration, Instant};
+
+/// Edits closer than this are grouped together.
+const GROUP_DELAY_MS: u64 = 1000;
+
+/// Edit history for a text buffer, to undo and redo changes.
+///
+/// Each change is recorded with [`record`]. Consecutive insertions (or
+/// deletions) are grouped together, until a word ends, the user pauses, or
+/// [`seal`] is called, so a single undo reverts a whole word.
+///
+/// [`record`]: #method.record
+/// [`seal`]: #method.seal
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::UndoHistory;
+/// let mut text = String::from("ab");
+/// let mut history = UndoHistory::new();
+///
+/// text.push('c');
+/// history.record(2, "", "c", 2, 3);
+///
+/// let change = history.undo().unwrap();
+/// change.apply(&mut text);
+/// assert_eq!("ab", text);
+/// assert_eq!(2, change.cursor);
+///
+/// history.redo().unwrap().apply(&mut text);
+/// assert_eq!("abc", text);
+/// ```
+pub struct UndoHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+
+    // When `true`, the next edit starts a new group.
+    sealed: bool,
+    last_time: Option<Instant>,
+}
+
+/// Change to apply to a text buffer to undo or redo an edit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// Byte offset of the start of the replaced text.
+    pub start: usize,
+    /// Byte offset of the end of the replaced text.
+    pub end: usize,
+    /// Text to insert instead.
+    pub text: String,
+    /// Where the cursor should be after the change.
+    pub cursor: usize,
+}
+
+impl Change {
+    /// Applies this change to a string.
+    pub fn apply(&self, content: &mut String) {
+        for _ in content.drain(self.start..self.end) {}
+        content.insert_str(self.start, &self.text);
+    }
+}
+
+// A single change: `removed` was replaced by `inserted` at `pos`.
+struct Edit {
+    pos: usize,
+    removed: String,
+    inserted: String,
+    cursor_before: usize,
+    cursor_after: usize,
+}
+
+new_default!(UndoHistory);
+
+impl UndoHistory {
+    /// Creates a new, empty history.
+    pub fn new() -> Self {
+        UndoHistory {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            sealed: true,
+            last_time: None,
+        }
+    }
+
+    /// Records a change in the buffer.
+    ///
+    /// `removed` was replaced with `inserted`, at byte offset `pos`.
+    /// `cursor_before` and `cursor_after` are the positions of the cursor
+    /// before and after the change, restored by `undo` and `redo`.
+    ///
+    /// This forgets any undone change.
+    pub fn record(&mut self, pos: usize, removed: &str, inserted: &str,
+                  cursor_before: usize, cursor_after: usize) {
+        self.redo.clear();
+
+        let now = Instant::now();
+        let recent = self.last_time
+            .map(|time| {
+                now.duration_since(time) <
+                Duration::from_millis(GROUP_DELAY_MS)
+            })
+            .unwrap_or(false);
+        self.last_time = Some(now);
+
+        if !self.sealed && recent {
+            if let Some(last) = self.undo.last_mut() {
+                if last.merge(pos, removed, inserted, cursor_after) {
+                    return;
+                }
+            }
+        }
+
+        self.sealed = false;
+        self.undo.push(Edit {
+            pos: pos,
+            removed: removed.to_string(),
+            inserted: inserted.to_string(),
+            cursor_before: cursor_before,
+            cursor_after: cursor_after,
+        });
+    }
+
+    /// Ends the current group of changes.
+    ///
+    /// The next recorded change will be undone separately.
+    /// Call this when the cursor moves, for instance.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Reverts the last group of changes.
+    ///
+    /// Returns the change to apply to the buffer, or `None` if there was
+    /// nothing to undo.
+    pub fn undo(&mut self) -> Option<Change> {
+        self.seal();
+        self.undo.pop().map(|edit| {
+            let change = Change {
+                start: edit.pos,
+                end: edit.pos + edit.inserted.len(),
+                text: edit.removed.clone(),
+                cursor: edit.cursor_before,
+            };
+            self.redo.push(edit);
+            change
+        })
+    }
+
+    /// Applies again the last undone group of changes.
+    ///
+    /// Returns the change to apply to the buffer, or `None` if there was
+    /// nothing to redo.
+    pub fn redo(&mut self) -> Option<Change> {
+        self.seal();
+        self.redo.pop().map(|edit| {
+            let change = Change {
+                start: edit.pos,
+                end: edit.pos + edit.removed.len(),
+                text: edit.inserted.clone(),
+                cursor: edit.cursor_after,
+            };
+            self.undo.push(edit);
+            change
+        })
+    }
+
+    /// Returns `true` if there is something to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is something to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all recorded changes.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.seal();
+    }
+}
+
+impl Edit {
+    // Tries to extend this edit with the next one.
+    //
+    // Returns `false` if they should be kept separate.
+    fn merge(&mut self, pos: usize, removed: &str, inserted: &str,
+             cursor_after: usize)
+             -> bool {
+        let typing = self.removed.is_empty() && removed.is_empty() &&
+                     pos == self.pos + self.inserted.len();
+        let erasing = self.inserted.is_empty() && inserted.is_empty();
+
+        if typing {
+            // Stop at the end of a word.
+            let space = |c: char| c.is_whitespace();
+            if inserted.contains('\n') ||
+               (self.inserted.ends_with(&space) &&
+                !inserted.starts_with(&space)) {
+                return false;
+            }
+            self.inserted.push_str(inserted);
+        } else if erasing && pos + removed.len() == self.pos {
+            // Backspace
+            self.removed.insert_str(0, removed);
+            self.pos = pos;
+        } else if erasing && pos == self.pos {
+            // Delete
+            self.removed.push_str(removed);
+        } else {
+            return false;
+        }
+
+        self.cursor_after = cursor_after;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(content: &mut String, history: &mut UndoHistory,
+                 text: &str) {
+        for c in text.chars() {
+            let pos = content.len();
+            content.push(c);
+            history.record(pos, "", &c.to_string(), pos, content.len());
+        }
+    }
+
+    fn undo(content: &mut String, history: &mut UndoHistory)
+            -> Option<usize> {
+        history.undo().map(|change| {
+            change.apply(content);
+            change.cursor
+        })
+    }
+
+    fn redo(content: &mut String, history: &mut UndoHistory)
+            -> Option<usize> {
+        history.redo().map(|change| {
+            change.apply(content);
+            change.cursor
+        })
+    }
+
+    #[test]
+    fn group_words() {
+        let mut content = String::new();
+        let mut history = UndoHistory::new();
+        type_text(&mut content, &mut history, "hello big world");
+
+        assert_eq!(Some(10), undo(&mut content, &mut history));
+        assert_eq!("hello big ", content);
+        assert_eq!(Some(6), undo(&mut content, &mut history));
+        assert_eq!("hello ", content);
+
+        assert_eq!(Some(10), redo(&mut content, &mut history));
+        assert_eq!("hello big ", content);
+    }
+
+    #[test]
+    fn group_deletions() {
+        let mut content = String::from("abc");
+        let mut history = UndoHistory::new();
+
+        // Backspace twice, then type.
+        content.pop();
+        history.record(2, "c", "", 3, 2);
+        content.pop();
+        history.record(1, "b", "", 2, 1);
+        history.seal();
+        type_text(&mut content, &mut history, "x");
+
+        assert_eq!(Some(1), undo(&mut content, &mut history));
+        assert_eq!("a", content);
+        assert_eq!(Some(3), undo(&mut content, &mut history));
+        assert_eq!("abc", content);
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+
+        // A new edit forgets the undone ones.
+        type_text(&mut content, &mut history, "d");
+        assert!(!history.can_redo());
+    }
+}
@@ -0,0 +1,422 @@
+//! Validation of user input.
+//!
+//! A [`Validator`] checks the content of an [`EditView`]. It can either
+//! mark invalid content with an error style, or reject the keystrokes
+//! which would make the content invalid.
+//!
+//! [`Validator`]: trait.Validator.html
+//! [`EditView`]: ../../views/struct.EditView.html
+
+use utils::{Pattern, PatternError};
+
+/// Whether some content is valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Validity {
+    /// The content is valid.
+    Valid,
+    /// The content is not valid yet, but may become so with more input.
+    Incomplete,
+    /// The content is not valid, for the given reason.
+    Invalid(String),
+}
+
+impl Validity {
+    /// Returns `true` if the content is valid.
+    pub fn is_valid(&self) -> bool {
+        *self == Validity::Valid
+    }
+
+    /// Returns `true` if the content is invalid.
+    ///
+    /// Incomplete content is not invalid.
+    pub fn is_invalid(&self) -> bool {
+        match *self {
+            Validity::Invalid(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Checks some content.
+///
+/// Any closure from a `&str` to a [`Validity`] is a `Validator`.
+///
+/// [`Validity`]: enum.Validity.html
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::validation::{Validator, Validity};
+/// let lowercase = |content: &str| {
+///     if content.chars().all(char::is_lowercase) {
+///         Validity::Valid
+///     } else {
+///         Validity::Invalid("Only lowercase letters".to_string())
+///     }
+/// };
+///
+/// assert!(lowercase.validate("abc").is_valid());
+/// assert!(lowercase.validate("aBc").is_invalid());
+/// ```
+pub trait Validator {
+    /// Returns the validity of `content`.
+    fn validate(&self, content: &str) -> Validity;
+}
+
+impl<F> Validator for F
+    where F: Fn(&str) -> Validity
+{
+    fn validate(&self, content: &str) -> Validity {
+        self(content)
+    }
+}
+
+/// Accepts numbers.
+///
+/// By default, only non-negative integers are valid.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Numeric {
+    decimal: bool,
+    negative: bool,
+}
+
+impl Numeric {
+    /// Creates a validator for non-negative integers.
+    pub fn new() -> Self {
+        Numeric {
+            decimal: false,
+            negative: false,
+        }
+    }
+
+    /// Also accepts decimal numbers, like `3.14`.
+    pub fn decimal(self) -> Self {
+        Numeric { decimal: true, ..self }
+    }
+
+    /// Also accepts negative numbers.
+    pub fn negative(self) -> Self {
+        Numeric { negative: true, ..self }
+    }
+}
+
+impl Validator for Numeric {
+    fn validate(&self, content: &str) -> Validity {
+        let digits = if self.negative && content.starts_with('-') {
+            &content[1..]
+        } else {
+            content
+        };
+
+        let mut point = false;
+        for c in digits.chars() {
+            match c {
+                c if c.is_digit(10) => (),
+                '.' if self.decimal && !point => point = true,
+                _ => return Validity::Invalid("Not a number".to_string()),
+            }
+        }
+
+        if digits.chars().any(|c| c.is_digit(10)) {
+            Validity::Valid
+        } else {
+            Validity::Incomplete
+        }
+    }
+}
+
+/// Accepts numbers within a range, bounds included.
+#[derive(Clone, Copy, Debug)]
+pub struct Range {
+    min: f64,
+    max: f64,
+}
+
+impl Range {
+    /// Creates a validator for numbers between `min` and `max`.
+    pub fn new(min: f64, max: f64) -> Self {
+        Range { min: min, max: max }
+    }
+}
+
+impl Validator for Range {
+    fn validate(&self, content: &str) -> Validity {
+        if content.is_empty() {
+            return Validity::Incomplete;
+        }
+
+        match content.parse::<f64>() {
+            // `NaN` and `inf` parse, but are not in any range.
+            Ok(n) if !n.is_finite() => {
+                Validity::Invalid("Not a number".to_string())
+            }
+            Err(_) => Validity::Invalid("Not a number".to_string()),
+            Ok(n) if n < self.min || n > self.max => {
+                Validity::Invalid(format!("Must be between {} and {}",
+                                          self.min,
+                                          self.max))
+            }
+            Ok(_) => Validity::Valid,
+        }
+    }
+}
+
+/// Accepts content entirely matching a pattern.
+///
+/// See [`Pattern`] for the supported syntax.
+///
+/// [`Pattern`]: ../struct.Pattern.html
+#[derive(Clone, Debug)]
+pub struct Matches {
+    pattern: Pattern,
+}
+
+impl Matches {
+    /// Creates a validator for the given regular expression.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        Pattern::new(pattern).map(|pattern| Matches { pattern: pattern })
+    }
+}
+
+impl Validator for Matches {
+    fn validate(&self, content: &str) -> Validity {
+        if self.pattern.is_full_match(content) {
+            Validity::Valid
+        } else if content.is_empty() {
+            Validity::Incomplete
+        } else {
+            Validity::Invalid("Invalid format".to_string())
+        }
+    }
+}
+
+/// Template for fixed-format input, like dates or phone numbers.
+///
+/// In the template:
+///
+/// * `#` stands for a digit.
+/// * `A` stands for a letter.
+/// * `*` stands for any character.
+/// * Anything else is a literal, which is inserted automatically.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::utils::validation::{Mask, Validity};
+/// let mask = Mask::new("####-##-##");
+/// assert_eq!(Some("-0".to_string()), mask.complete("2017", '0'));
+/// assert_eq!(Validity::Incomplete, mask.check("2017-0"));
+/// assert_eq!(Validity::Valid, mask.check("2017-06-01"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    template: Vec<char>,
+}
+
+impl Mask {
+    /// Creates a mask from a template.
+    pub fn new(template: &str) -> Self {
+        Mask { template: template.chars().collect() }
+    }
+
+    /// Returns the number of characters in the template.
+    pub fn len(&self) -> usize {
+        self.template.len()
+    }
+
+    /// Returns `true` if the template is empty.
+    pub fn is_empty(&self) -> bool {
+        self.template.is_empty()
+    }
+
+    // Returns `true` if `slot` of the template accepts `c`.
+    fn accepts(slot: char, c: char) -> bool {
+        match slot {
+            '#' => c.is_digit(10),
+            'A' => c.is_alphabetic(),
+            '*' => true,
+            literal => literal == c,
+        }
+    }
+
+    // Returns `true` if `slot` is a literal.
+    fn is_literal(slot: char) -> bool {
+        match slot {
+            '#' | 'A' | '*' => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the text to insert when `c` is typed after `before`.
+    ///
+    /// Literals expected before `c` are inserted with it. Returns `None` if
+    /// `c` doesn't fit there.
+    pub fn complete(&self, before: &str, c: char) -> Option<String> {
+        let mut result = String::new();
+        for &slot in self.template.iter().skip(before.chars().count()) {
+            if Mask::accepts(slot, c) {
+                result.push(c);
+                return Some(result);
+            } else if Mask::is_literal(slot) {
+                result.push(slot);
+            } else {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Returns the characters of `content` typed in the slots of the
+    /// template, without the literals.
+    pub fn strip(&self, content: &str) -> String {
+        content.chars()
+            .zip(&self.template)
+            .filter(|&(_, &slot)| !Mask::is_literal(slot))
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    /// Lays out typed characters in the template, adding the literals
+    /// expected before each of them.
+    ///
+    /// This is the reverse of [`strip`]. Returns `None` if the characters
+    /// don't fit in the template.
+    ///
+    /// [`strip`]: #method.strip
+    pub fn fill(&self, typed: &str) -> Option<String> {
+        let mut result = String::new();
+        let mut typed = typed.chars().peekable();
+        for &slot in &self.template {
+            let c = match typed.peek() {
+                Some(&c) => c,
+                None => return Some(result),
+            };
+            if Mask::is_literal(slot) {
+                result.push(slot);
+            } else if Mask::accepts(slot, c) {
+                result.push(c);
+                typed.next();
+            } else {
+                return None;
+            }
+        }
+
+        if typed.peek().is_some() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    /// Checks `content` against the template.
+    ///
+    /// Content shorter than the template is incomplete.
+    pub fn check(&self, content: &str) -> Validity {
+        let mut count = 0;
+        for (c, &slot) in content.chars().zip(&self.template) {
+            if !Mask::accepts(slot, c) {
+                return Validity::Invalid("Invalid format".to_string());
+            }
+            count += 1;
+        }
+
+        if count < content.chars().count() {
+            Validity::Invalid("Too long".to_string())
+        } else if count < self.template.len() {
+            Validity::Incomplete
+        } else {
+            Validity::Valid
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+    use utils::edit_mode::Editor;
+    use view::View;
+    use views::EditView;
+
+    fn type_keys(view: &mut EditView, keys: &str) {
+        for c in keys.chars() {
+            view.on_event(Event::Char(c));
+        }
+    }
+
+    #[test]
+    fn numeric() {
+        let integer = Numeric::new();
+        assert!(integer.validate("42").is_valid());
+        assert!(integer.validate("-42").is_invalid());
+        assert!(integer.validate("4.2").is_invalid());
+        assert_eq!(Validity::Incomplete, integer.validate(""));
+
+        let number = Numeric::new().decimal().negative();
+        assert!(number.validate("-4.2").is_valid());
+        assert!(number.validate("4.2.1").is_invalid());
+        assert_eq!(Validity::Incomplete, number.validate("-"));
+
+        let range = Range::new(1.0, 10.0);
+        assert!(range.validate("10").is_valid());
+        assert_eq!(Validity::Invalid("Must be between 1 and 10".to_string()),
+                   range.validate("11"));
+        assert!(range.validate("NaN").is_invalid());
+        assert!(range.validate("inf").is_invalid());
+    }
+
+    #[test]
+    fn mask() {
+        let mask = Mask::new("(###) ###");
+        assert_eq!(Some("(5".to_string()), mask.complete("", '5'));
+        assert_eq!(Some(") 5".to_string()), mask.complete("(555", '5'));
+        assert_eq!(None, mask.complete("(555", 'a'));
+        assert_eq!(None, mask.complete("(555) 555", '5'));
+
+        assert_eq!(Validity::Incomplete, mask.check("(555"));
+        assert!(mask.check("(555) 555").is_valid());
+        assert!(mask.check("(5a").is_invalid());
+        assert!(mask.check("(555) 5555").is_invalid());
+
+        assert_eq!("555555", mask.strip("(555) 555"));
+        assert_eq!(Some("(555) 5".to_string()), mask.fill("5555"));
+        assert_eq!(None, mask.fill("55a"));
+        assert_eq!(None, mask.fill("5555555"));
+    }
+
+    #[test]
+    fn edit_view() {
+        let mut edit = EditView::new()
+            .max_length(3)
+            .filter(Numeric::new())
+            .validator(Range::new(10.0, 100.0));
+        type_keys(&mut edit, "1a2");
+        assert_eq!("12", &*edit.get_content());
+        assert!(edit.validity().is_valid());
+        type_keys(&mut edit, "34");
+        assert_eq!("123", &*edit.get_content());
+        assert!(edit.validity().is_invalid());
+
+        let mut edit = EditView::new().mask("####-##-##");
+        type_keys(&mut edit, "2017x06");
+        assert_eq!("2017-06", &*edit.get_content());
+        assert_eq!(Validity::Incomplete, edit.validity());
+        type_keys(&mut edit, "01");
+        assert_eq!(Validity::Valid, edit.validity());
+
+        // Deleting moves the next characters back in the mask.
+        edit.set_cursor(6);
+        edit.on_event(Event::Key(Key::Backspace));
+        assert_eq!("2017-60-1", &*edit.get_content());
+        assert_eq!(4, edit.cursor());
+        type_keys(&mut edit, "1");
+        assert_eq!("2017-16-01", &*edit.get_content());
+        edit.on_event(Event::Key(Key::Del));
+        assert_eq!("2017-10-1", &*edit.get_content());
+
+        edit.undo();
+        assert_eq!("2017-16-01", &*edit.get_content());
+        edit.set_content("2017-6-01");
+        assert_eq!("2017-16-01", &*edit.get_content());
+    }
+}
@@ -42,6 +42,36 @@ pub trait Boxable: View + Sized {
         BoxView::with_full_height(self)
     }
 
+    /// Wraps `self` into a `BoxView` using a ratio of the available space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cursive::views::TextView;
+    /// # use cursive::traits::*;
+    /// // Half the width and a third of the height of the parent.
+    /// let view = TextView::new("Hello!").size_ratio(0.5, 1f32 / 3f32);
+    /// ```
+    fn size_ratio(self, width: f32, height: f32) -> BoxView<Self> {
+        BoxView::with_size_ratio(width, height, self)
+    }
+
+    /// Wraps `self` into a `BoxView` using a ratio of the available width.
+    fn width_ratio(self, ratio: f32) -> BoxView<Self> {
+        BoxView::with_width_ratio(ratio, self)
+    }
+
+    /// Wraps `self` into a `BoxView` using a ratio of the available height.
+    fn height_ratio(self, ratio: f32) -> BoxView<Self> {
+        BoxView::with_height_ratio(ratio, self)
+    }
+
+    /// Wraps `self` into a `BoxView` as big as possible, with a fixed
+    /// width / height ratio.
+    fn keep_aspect_ratio(self, ratio: f32) -> BoxView<Self> {
+        BoxView::with_aspect_ratio(ratio, self)
+    }
+
     /// Wraps `self` into a limited-size `BoxView`.
     fn max_size<S: Into<Vec2>>(self, size: S) -> BoxView<Self> {
         BoxView::with_max_size(size, self)
@@ -0,0 +1,226 @@
+use Printer;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use vec::Vec2;
+use view::View;
+
+/// Layout of a view, recorded for layout debugging.
+///
+/// See [`Cursive::set_layout_debug`].
+///
+/// [`Cursive::set_layout_debug`]: ../struct.Cursive.html#method.set_layout_debug
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutInfo {
+    /// Full type name of the view.
+    pub name: &'static str,
+    /// Constraint given to the last `required_size` call.
+    pub constraint: Option<Vec2>,
+    /// Size returned by the last `required_size` call.
+    pub required: Option<Vec2>,
+    /// Size given to the last `layout` call.
+    pub size: Option<Vec2>,
+}
+
+impl LayoutInfo {
+    /// Creates an empty record for a view with the given type name.
+    pub fn new(name: &'static str) -> Self {
+        LayoutInfo {
+            name: name,
+            constraint: None,
+            required: None,
+            size: None,
+        }
+    }
+
+    /// Returns the type name without its path and parameters.
+    ///
+    /// `a::b::Foo<c::Bar>` becomes `Foo`.
+    pub fn short_name(&self) -> &'static str {
+        let name = self.name.split('<').next().unwrap_or(self.name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Returns the violations of the `View` contract detected so far.
+    pub fn violations(&self) -> Vec<String> {
+        let mut result = Vec::new();
+
+        if let (Some(constraint), Some(required)) = (self.constraint,
+                                                     self.required) {
+            if !required.fits_in(constraint) {
+                result.push(format!("{}: required_size {:?} exceeds the \
+                                     constraint {:?}",
+                                    self.short_name(),
+                                    required,
+                                    constraint));
+            }
+        }
+
+        if self.size.is_none() {
+            result.push(format!("{}: drawn before layout",
+                                self.short_name()));
+        }
+
+        result
+    }
+
+    /// Describes the recorded sizes on a single line.
+    pub fn describe(&self) -> String {
+        format!("{}: constraint {}, required {}, layout {}",
+                self.short_name(),
+                describe_size(self.constraint),
+                describe_size(self.required),
+                describe_size(self.size))
+    }
+
+    /// Adds this record to the layout report of `printer`, if any.
+    ///
+    /// `printer` should be the one given to the view.
+    pub fn report(&self, printer: &Printer) {
+        let report = match printer.layout_report() {
+            Some(report) => report,
+            None => return,
+        };
+        let violations = self.violations();
+        let mut report = report.borrow_mut();
+
+        // Inner views are drawn later,
+        // so the innermost focused view has the last word.
+        if printer.focused {
+            report.focused = Some(self.describe());
+        }
+        report.name_area(printer.offset,
+                         printer.size,
+                         self.short_name(),
+                         !violations.is_empty());
+        report.violations.extend(violations);
+    }
+}
+
+fn describe_size(size: Option<Vec2>) -> String {
+    match size {
+        Some(size) => format!("{}x{}", size.x, size.y),
+        None => "?".to_string(),
+    }
+}
+
+// Views are identified by their address and type name: a wrapper and the
+// view it wraps may share the same address.
+type Key = (usize, &'static str);
+
+thread_local! {
+    // `None` when layout debugging is disabled.
+    static RECORDS: RefCell<Option<HashMap<Key, LayoutInfo>>> =
+        RefCell::new(None);
+}
+
+fn key(view: &View) -> Key {
+    (view as *const View as *const () as usize, view.type_name())
+}
+
+// Updates the record of `view`, if layout debugging is enabled.
+fn record<F: FnOnce(&mut LayoutInfo)>(view: &View, f: F) {
+    RECORDS.with(|records| {
+        if let Some(ref mut records) = *records.borrow_mut() {
+            let key = key(view);
+            f(records.entry(key).or_insert_with(|| LayoutInfo::new(key.1)));
+        }
+    });
+}
+
+/// Starts or stops recording the layout of views.
+///
+/// Records are dropped when it is stopped.
+#[doc(hidden)]
+pub fn set_recording(enabled: bool) {
+    RECORDS.with(|records| {
+        let mut records = records.borrow_mut();
+        if enabled != records.is_some() {
+            *records = if enabled { Some(HashMap::new()) } else { None };
+        }
+    });
+}
+
+/// Calls `required_size` on a child view.
+///
+/// Containers should use this rather than calling it directly, so the
+/// child can be described when layout debugging is enabled.
+pub fn child_required_size(view: &mut View, constraint: Vec2) -> Vec2 {
+    let size = view.required_size(constraint);
+    record(view, |info| {
+        info.constraint = Some(constraint);
+        info.required = Some(size);
+    });
+    size
+}
+
+/// Calls `layout` on a child view.
+///
+/// See [`child_required_size`](fn.child_required_size.html).
+pub fn child_layout(view: &mut View, size: Vec2) {
+    record(view, |info| info.size = Some(size));
+    view.layout(size);
+}
+
+/// Calls `draw` on a child view.
+///
+/// See [`child_required_size`](fn.child_required_size.html).
+pub fn child_draw(view: &View, printer: &Printer) {
+    if printer.layout_report().is_some() {
+        let info = RECORDS.with(|records| {
+            records.borrow()
+                .as_ref()
+                .and_then(|records| records.get(&key(view)).cloned())
+        });
+        info.unwrap_or_else(|| LayoutInfo::new(view.type_name()))
+            .report(printer);
+    }
+    view.draw(printer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::{Dialog, DummyView, TextView};
+
+    #[test]
+    fn records() {
+        let mut view = TextView::new("Hello");
+        child_layout(&mut view, Vec2::new(3, 3));
+
+        set_recording(true);
+        let required = child_required_size(&mut view, Vec2::new(3, 3));
+        assert_eq!(Vec2::new(3, 2), required);
+        let info = RECORDS.with(|records| {
+            records.borrow().as_ref().unwrap()[&key(&view)].clone()
+        });
+        assert_eq!("TextView", info.short_name());
+        assert_eq!(Some(Vec2::new(3, 2)), info.required);
+        assert_eq!(1, info.violations().len());
+
+        set_recording(false);
+        child_layout(&mut DummyView, Vec2::new(1, 1));
+        assert!(RECORDS.with(|records| records.borrow().is_none()));
+    }
+
+    #[test]
+    fn nested() {
+        set_recording(true);
+        let mut dialog = Dialog::around(TextView::new("Hello"))
+            .button("Ok", |_| ());
+        let size = child_required_size(&mut dialog, Vec2::new(20, 10));
+        child_layout(&mut dialog, size);
+
+        let mut names: Vec<_> = RECORDS.with(|records| {
+            records.borrow()
+                .as_ref()
+                .unwrap()
+                .values()
+                .filter(|info| info.violations().is_empty())
+                .map(|info| info.short_name())
+                .collect()
+        });
+        names.sort();
+        assert_eq!(vec!["Button", "Dialog", "SizedView", "TextView"], names);
+        set_recording(false);
+    }
+}
@@ -34,11 +34,20 @@
 //! * The value returned by `required_size` should be an actually viable size,
 //!   no matter what the request is. This means calling `View::layout()` with
 //!   a size returned by `required_size` is **never** an error.
+//!
+//! Containers should call these methods on their children through
+//! [`child_required_size`], [`child_layout`] and [`child_draw`], so layout
+//! debugging can describe them.
+//!
+//! [`child_required_size`]: fn.child_required_size.html
+//! [`child_layout`]: fn.child_layout.html
+//! [`child_draw`]: fn.child_draw.html
 
 #[macro_use]
 mod view_wrapper;
 
 // Essentials components
+mod layout_debug;
 mod position;
 mod size_cache;
 mod size_constraint;
@@ -53,6 +62,11 @@ mod boxable;
 pub use self::boxable::Boxable;
 pub use self::identifiable::Identifiable;
 
+pub use self::layout_debug::{LayoutInfo, child_draw, child_layout,
+                             child_required_size};
+#[doc(hidden)]
+pub use self::layout_debug::set_recording;
+
 pub use self::position::{Offset, Position};
 
 pub use self::scroll::{ScrollBase, ScrollStrategy};
@@ -144,6 +158,13 @@ pub trait View {
         let _ = source;
         false
     }
+
+    /// Returns the name of the type of this view.
+    ///
+    /// Used to describe views when layout debugging is enabled.
+    fn type_name(&self) -> &'static str {
+        ::std::any::type_name::<Self>()
+    }
 }
 
 /// Provides `call_on<V: View>` to views.
@@ -27,6 +27,13 @@ pub struct ScrollBase {
 
     /// Blank between the text and the scrollbar.
     pub right_padding: usize,
+
+    /// First column visible
+    pub start_column: usize,
+    /// Content width
+    pub content_width: usize,
+    /// Number of columns displayed
+    pub view_width: usize,
 }
 
 /// Defines the scrolling behaviour on content or size change
@@ -54,6 +61,9 @@ impl ScrollBase {
             view_height: 0,
             scrollbar_offset: 0,
             right_padding: 1,
+            start_column: 0,
+            content_width: 0,
+            view_width: 0,
         }
     }
 
@@ -143,6 +153,65 @@ impl ScrollBase {
         }
     }
 
+    /// Call this method when the content or the view width changes.
+    ///
+    /// Views scrolling horizontally should keep a row at the bottom for
+    /// the horizontal scrollbar when the content is wider than the view.
+    pub fn set_widths(&mut self, view_width: usize, content_width: usize) {
+        self.view_width = view_width;
+        self.content_width = content_width;
+
+        if self.h_scrollable() {
+            self.start_column = min(self.start_column,
+                                    self.content_width - self.view_width);
+        } else {
+            self.start_column = 0;
+        }
+    }
+
+    /// Returns `TRUE` if the view needs to scroll horizontally.
+    pub fn h_scrollable(&self) -> bool {
+        self.view_width < self.content_width
+    }
+
+    /// Returns `TRUE` unless we are at the left edge.
+    pub fn can_scroll_left(&self) -> bool {
+        self.start_column > 0
+    }
+
+    /// Returns `TRUE` unless we are at the right edge.
+    pub fn can_scroll_right(&self) -> bool {
+        self.start_column + self.view_width < self.content_width
+    }
+
+    /// Makes sure that the given column is visible, scrolling if needed.
+    pub fn scroll_to_column(&mut self, x: usize) {
+        if x >= self.start_column + self.view_width {
+            self.start_column = 1 + x - self.view_width;
+        } else if x < self.start_column {
+            self.start_column = x;
+        }
+    }
+
+    /// Scroll right by the given number of columns.
+    ///
+    /// Never further than the right edge of the content.
+    pub fn scroll_right(&mut self, n: usize) {
+        if self.h_scrollable() {
+            self.start_column = min(self.start_column + n,
+                                    self.content_width - self.view_width);
+        }
+    }
+
+    /// Scroll left by the given number of columns.
+    ///
+    /// Never further than the left edge of the content.
+    pub fn scroll_left(&mut self, n: usize) {
+        if self.h_scrollable() {
+            self.start_column -= min(self.start_column, n);
+        }
+    }
+
     /// Draws the scroll bar and the content using the given drawer.
     ///
     /// `line_drawer` will be called once for each line that needs to be drawn.
@@ -150,6 +219,10 @@ impl ScrollBase {
     /// It will also be given a printer with the correct offset,
     /// so it should only print on the first line.
     ///
+    /// When scrolling horizontally, `line_drawer` should skip the first
+    /// `start_column` cells itself; the horizontal scrollbar is drawn on
+    /// the last row of the printer.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -216,12 +289,40 @@ impl ScrollBase {
                 ColorStyle::HighlightInactive
             };
 
+            // Leave the last row to the horizontal scrollbar.
+            let bar_height = if self.h_scrollable() {
+                printer.size.y.saturating_sub(1)
+            } else {
+                printer.size.y
+            };
+
             // TODO: use 1 instead of 2
             let scrollbar_x = printer.size.x - 1 - self.scrollbar_offset;
-            printer.print_vline((scrollbar_x, 0), printer.size.y, "|");
+            printer.print_vline((scrollbar_x, 0), bar_height, "|");
             printer.with_color(color, |printer| {
                 printer.print_vline((scrollbar_x, start), height, "▒");
             });
         }
+
+        if self.h_scrollable() && printer.size.y > 0 {
+            let width = max(1,
+                            self.view_width * self.view_width /
+                            self.content_width);
+            let steps = self.view_width - width + 1;
+            let start = steps * self.start_column /
+                        (1 + self.content_width - self.view_width);
+
+            let color = if printer.focused {
+                ColorStyle::Highlight
+            } else {
+                ColorStyle::HighlightInactive
+            };
+
+            let scrollbar_y = printer.size.y - 1;
+            printer.print_hline((0, scrollbar_y), self.view_width, "-");
+            printer.with_color(color, |printer| {
+                printer.print_hline((start, scrollbar_y), width, "▒");
+            });
+        }
     }
 }
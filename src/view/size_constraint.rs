@@ -1,4 +1,4 @@
-use std::cmp::min;
+use std::cmp::{max, min};
 
 /// Single-dimensional constraint on a view size.
 ///
@@ -17,6 +17,16 @@ pub enum SizeConstraint {
     AtMost(usize),
     /// Returns the maximum of the included value and the child view's size.
     AtLeast(usize),
+    /// Takes the given fraction of the available space.
+    ///
+    /// `Ratio(0.5)` uses half of the available space, no matter what the
+    /// child needs.
+    Ratio(f32),
+    /// Takes a fraction of the available space, within bounds.
+    ///
+    /// `BoundedRatio(ratio, min, max)` behaves like `Ratio(ratio)`, but never
+    /// returns less than `min` or more than `max`.
+    BoundedRatio(f32, usize, usize),
 }
 
 impl SizeConstraint {
@@ -31,19 +41,69 @@ impl SizeConstraint {
             // If the available space is too small, always give in.
             SizeConstraint::Fixed(value) |
             SizeConstraint::AtMost(value) => min(value, available),
+            SizeConstraint::Ratio(ratio) => {
+                min(apply_ratio(ratio, available), available)
+            }
+            SizeConstraint::BoundedRatio(ratio, min_value, max_value) => {
+                let value = apply_ratio(ratio, available);
+                min(max(min(value, max_value), min_value), available)
+            }
         }
     }
 
     /// Returns the size the child view should actually use.
     ///
     /// When it said it wanted `result`.
+    ///
+    /// `available` is the size given to the child, as returned by
+    /// `available()`.
     pub fn result(self, (result, available): (usize, usize)) -> usize {
         match self {
             SizeConstraint::AtLeast(value) if result < value => value,
             SizeConstraint::AtMost(value) if result > value => value,
             SizeConstraint::Fixed(value) => value,
-            SizeConstraint::Full => available,
+            // The ratio was already applied in `available()`.
+            SizeConstraint::Full |
+            SizeConstraint::Ratio(_) => available,
+            SizeConstraint::BoundedRatio(_, min_value, _) => {
+                max(available, min_value)
+            }
             _ => result,
         }
     }
 }
+
+// Returns `ratio * available`, rounded to the nearest integer.
+fn apply_ratio(ratio: f32, available: usize) -> usize {
+    if ratio <= 0f32 {
+        0
+    } else {
+        (ratio * available as f32).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio() {
+        let third = SizeConstraint::Ratio(1f32 / 3f32);
+        assert_eq!(10, third.available(30));
+        assert_eq!(10, third.result((25, 10)));
+        assert_eq!(30, SizeConstraint::Ratio(2f32).available(30));
+        assert_eq!(0, SizeConstraint::Ratio(-1f32).available(30));
+    }
+
+    #[test]
+    fn bounded_ratio() {
+        let c = SizeConstraint::BoundedRatio(0.5, 20, 30);
+        assert_eq!(25, c.available(50));
+        assert_eq!(30, c.available(100));
+        assert_eq!(20, c.available(30));
+
+        // Like `Fixed`, the minimum is kept even when space runs out.
+        assert_eq!(10, c.available(10));
+        assert_eq!(20, c.result((5, 10)));
+    }
+}
@@ -4,7 +4,8 @@ use direction::Direction;
 use event::{Event, EventResult};
 use std::any::Any;
 use vec::Vec2;
-use view::{Selector, View};
+use view::{Selector, View, child_draw, child_layout,
+           child_required_size};
 
 /// Generic wrapper around a view.
 ///
@@ -28,12 +29,13 @@ pub trait ViewWrapper {
 
     /// Wraps the `draw` method.
     fn wrap_draw(&self, printer: &Printer) {
-        self.with_view(|v| v.draw(printer));
+        self.with_view(|v| child_draw(v, printer));
     }
 
     /// Wraps the `required_size` method.
     fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
-        self.with_view_mut(|v| v.required_size(req)).unwrap_or_else(Vec2::zero)
+        self.with_view_mut(|v| child_required_size(v, req))
+            .unwrap_or_else(Vec2::zero)
     }
 
     /// Wraps the `on_event` method.
@@ -43,7 +45,7 @@ pub trait ViewWrapper {
 
     /// Wraps the `layout` method.
     fn wrap_layout(&mut self, size: Vec2) {
-        self.with_view_mut(|v| v.layout(size));
+        self.with_view_mut(|v| child_layout(v, size));
     }
 
     /// Wraps the `take_focus` method.
@@ -1,6 +1,7 @@
+use With;
 use XY;
 use vec::Vec2;
-use view::{SizeConstraint, View, ViewWrapper};
+use view::{SizeConstraint, View, ViewWrapper, child_required_size};
 
 /// Wrapper around another view, with a controlled size.
 ///
@@ -10,8 +11,11 @@ use view::{SizeConstraint, View, ViewWrapper};
 /// * Use **all** available size
 /// * Use **at most** a given size
 /// * Use **at least** a given size
+/// * Use a **ratio** of the available size
 /// * Let the wrapped view decide.
 ///
+/// The resulting size can additionally be locked to an aspect ratio.
+///
 /// # Examples
 ///
 /// ```
@@ -30,6 +34,9 @@ pub struct BoxView<T: View> {
     /// For instance, try to return the child's desires size.
     squishable: bool,
 
+    /// If set, width / height ratio to keep.
+    aspect_ratio: Option<f32>,
+
     /// The actual view we're wrapping.
     view: T,
 }
@@ -43,6 +50,7 @@ impl<T: View> BoxView<T> {
         BoxView {
             size: (width, height).into(),
             squishable: false,
+            aspect_ratio: None,
             view: view,
         }
     }
@@ -60,6 +68,39 @@ impl<T: View> BoxView<T> {
         self
     }
 
+    /// Locks the size of `self` to the given width / height ratio.
+    ///
+    /// The largest size respecting both the constraints and the ratio is
+    /// used. Note that terminal cells are usually about twice as tall as
+    /// they are wide: a ratio of `2.0` looks roughly square.
+    ///
+    /// Ratios of zero or less remove the lock.
+    pub fn set_aspect_ratio(&mut self, ratio: f32) {
+        self.aspect_ratio = if ratio > 0f32 { Some(ratio) } else { None };
+    }
+
+    /// Locks the size of `self` to the given width / height ratio.
+    ///
+    /// Chainable variant.
+    pub fn aspect_ratio(self, ratio: f32) -> Self {
+        self.with(|s| s.set_aspect_ratio(ratio))
+    }
+
+    // Shrinks `size` on one axis to match the aspect ratio, if any.
+    fn lock_ratio(&self, size: Vec2) -> Vec2 {
+        let ratio = match self.aspect_ratio {
+            Some(ratio) => ratio,
+            None => return size,
+        };
+
+        let width = (size.y as f32 * ratio) as usize;
+        if width <= size.x {
+            Vec2::new(width, size.y)
+        } else {
+            Vec2::new(size.x, (size.x as f32 / ratio) as usize)
+        }
+    }
+
     /// Wraps `view` in a new `BoxView` with the given size.
     pub fn with_fixed_size<S: Into<Vec2>>(size: S, view: T) -> Self {
         let size = size.into();
@@ -94,6 +135,29 @@ impl<T: View> BoxView<T> {
         BoxView::new(SizeConstraint::Free, SizeConstraint::Full, view)
     }
 
+    /// Wraps `view` in a `BoxView` using the given ratio of available space.
+    pub fn with_size_ratio(width: f32, height: f32, view: T) -> Self {
+        BoxView::new(SizeConstraint::Ratio(width),
+                     SizeConstraint::Ratio(height),
+                     view)
+    }
+
+    /// Wraps `view` in a `BoxView` using the given ratio of available width.
+    pub fn with_width_ratio(ratio: f32, view: T) -> Self {
+        BoxView::new(SizeConstraint::Ratio(ratio), SizeConstraint::Free, view)
+    }
+
+    /// Wraps `view` in a `BoxView` using the given ratio of available height.
+    pub fn with_height_ratio(ratio: f32, view: T) -> Self {
+        BoxView::new(SizeConstraint::Free, SizeConstraint::Ratio(ratio), view)
+    }
+
+    /// Wraps `view` in a `BoxView` taking as much space as possible, while
+    /// keeping the given width / height ratio.
+    pub fn with_aspect_ratio(ratio: f32, view: T) -> Self {
+        BoxView::with_full_screen(view).aspect_ratio(ratio)
+    }
+
     /// Wraps `view` in a `BoxView` which will never be bigger than `size`.
     pub fn with_max_size<S: Into<Vec2>>(size: S, view: T) -> Self {
         let size = size.into();
@@ -155,9 +219,11 @@ impl<T: View> ViewWrapper for BoxView<T> {
     fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
 
         let req = self.size.zip_map(req, SizeConstraint::available);
-        let child_size = self.view.required_size(req);
+        let req = self.lock_ratio(req);
+        let child_size = child_required_size(&mut self.view, req);
         let result = self.size
             .zip_map(child_size.zip(req), SizeConstraint::result);
+        let result = self.lock_ratio(result);
 
         if self.squishable {
             // We respect the request if we're less or equal.
@@ -235,6 +301,28 @@ mod tests {
         assert_eq!(Vec2::new(5, 5), max_s.required_size(Vec2::new(10, 10)));
     }
 
+    #[test]
+    fn ratio() {
+        let mut half = DummyView.width_ratio(0.5);
+
+        assert_eq!(Vec2::new(5, 1), half.required_size(Vec2::new(10, 10)));
+        assert_eq!(Vec2::new(2, 1), half.required_size(Vec2::new(3, 10)));
+
+        let mut third = DummyView.size_ratio(1f32 / 3f32, 0.5);
+        assert_eq!(Vec2::new(10, 5), third.required_size(Vec2::new(30, 10)));
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        let mut canvas = DummyView.keep_aspect_ratio(2f32);
+
+        assert_eq!(Vec2::new(20, 10), canvas.required_size(Vec2::new(80, 10)));
+        assert_eq!(Vec2::new(10, 5), canvas.required_size(Vec2::new(10, 24)));
+
+        let mut fixed = DummyView.fixed_size((10, 10)).aspect_ratio(0.5);
+        assert_eq!(Vec2::new(5, 10), fixed.required_size(Vec2::new(80, 24)));
+    }
+
     #[test]
     fn full_screen() {
         let mut full = DummyView.full_screen();
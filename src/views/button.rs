@@ -5,7 +5,7 @@ use align::HAlign;
 use direction::Direction;
 use event::*;
 use theme::ColorStyle;
-use unicode_width::UnicodeWidthStr;
+use utils::StyledString;
 use vec::Vec2;
 use view::View;
 
@@ -20,7 +20,7 @@ use view::View;
 /// let quit_button = Button::new("Quit", |s| s.quit());
 /// ```
 pub struct Button {
-    label: String,
+    label: StyledString,
     callback: Callback,
     enabled: bool,
 }
@@ -29,6 +29,17 @@ impl Button {
     /// Creates a new button with the given content and callback.
     pub fn new<F, S: Into<String>>(label: S, cb: F) -> Self
         where F: Fn(&mut Cursive) + 'static
+    {
+        Button::new_styled(label.into(), cb)
+    }
+
+    /// Creates a new button with a styled label.
+    ///
+    /// See [`StyledString`].
+    ///
+    /// [`StyledString`]: ../utils/markup/struct.StyledString.html
+    pub fn new_styled<F, S: Into<StyledString>>(label: S, cb: F) -> Self
+        where F: Fn(&mut Cursive) + 'static
     {
         Button {
             label: label.into(),
@@ -92,10 +103,12 @@ impl View for Button {
         };
 
         let offset =
-            HAlign::Center.get_offset(self.label.len() + 2, printer.size.x);
+            HAlign::Center.get_offset(self.label.width() + 2, printer.size.x);
 
         printer.with_color(style, |printer| {
-            printer.print((offset, 0), &format!("<{}>", self.label));
+            printer.print((offset, 0), "<");
+            printer.print_styled((offset + 1, 0), &self.label);
+            printer.print((offset + 1 + self.label.width(), 0), ">");
         });
     }
 
@@ -0,0 +1,133 @@
+use Printer;
+use vec::Vec2;
+use view::{LayoutInfo, View, ViewWrapper};
+
+/// Wrapper recording the layout of a view, to help debugging it.
+///
+/// It remembers the constraint given to `required_size`, the size it
+/// returned, and the size given to `layout`.
+///
+/// Layout debugging already describes the children of the views of this
+/// crate. This gives the same information from code, and describes views
+/// placed in containers that don't use [`child_draw`] and the like.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::traits::*;
+/// # use cursive::vec::Vec2;
+/// # use cursive::views::{DebugView, TextView};
+/// let mut view = DebugView::new(TextView::new("Hello!"));
+/// view.required_size(Vec2::new(3, 10));
+/// view.layout(Vec2::new(3, 2));
+///
+/// assert_eq!(Some(Vec2::new(3, 2)), view.last_required_size());
+/// assert!(view.violations().is_empty());
+/// ```
+///
+/// [`child_draw`]: ../view/fn.child_draw.html
+pub struct DebugView<V: View> {
+    view: V,
+    info: LayoutInfo,
+}
+
+impl<V: View> DebugView<V> {
+    /// Wraps `view` in a new `DebugView`.
+    pub fn new(view: V) -> Self {
+        DebugView {
+            view: view,
+            info: LayoutInfo::new(::std::any::type_name::<V>()),
+        }
+    }
+
+    /// Returns the constraint given to the last `required_size` call.
+    pub fn last_constraint(&self) -> Option<Vec2> {
+        self.info.constraint
+    }
+
+    /// Returns the size returned by the last `required_size` call.
+    pub fn last_required_size(&self) -> Option<Vec2> {
+        self.info.required
+    }
+
+    /// Returns the size given to the last `layout` call.
+    pub fn last_size(&self) -> Option<Vec2> {
+        self.info.size
+    }
+
+    /// Returns the violations of the `View` contract detected so far.
+    pub fn violations(&self) -> Vec<String> {
+        self.info.violations()
+    }
+
+    /// Gets access to the inner view.
+    pub fn get_inner(&self) -> &V {
+        &self.view
+    }
+
+    /// Gets mutable access to the inner view.
+    pub fn get_inner_mut(&mut self) -> &mut V {
+        &mut self.view
+    }
+}
+
+impl<V: View> ViewWrapper for DebugView<V> {
+    wrap_impl!(self.view: V);
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        let size = self.view.required_size(req);
+        self.info.constraint = Some(req);
+        self.info.required = Some(size);
+        size
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.info.size = Some(size);
+        self.view.layout(size);
+    }
+
+    fn wrap_draw(&self, printer: &Printer) {
+        self.info.report(printer);
+        self.view.draw(printer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LayoutReport;
+    use view::{Boxable, View};
+    use views::{DummyView, TextView};
+
+    #[test]
+    fn type_name() {
+        let view = DebugView::new(TextView::new("Hello").fixed_width(3));
+        assert_eq!("BoxView", view.info.short_name());
+    }
+
+    #[test]
+    fn violations() {
+        let mut view = DebugView::new(DummyView.fixed_width(5));
+        assert_eq!(1, view.violations().len());
+
+        view.required_size(Vec2::new(10, 10));
+        view.layout(Vec2::new(5, 1));
+        assert!(view.violations().is_empty());
+
+        view.required_size(Vec2::new(3, 10));
+        assert_eq!(1, view.violations().len());
+        assert_eq!(Some(Vec2::new(5, 1)), view.last_required_size());
+    }
+
+    #[test]
+    fn name_area() {
+        let mut report = LayoutReport::default();
+        report.name_area(Vec2::new(1, 2), Vec2::new(3, 4), "Outer", false);
+        report.name_area(Vec2::new(1, 2), Vec2::new(3, 4), "Inner", true);
+        report.name_area(Vec2::new(1, 2), Vec2::new(3, 3), "Other", false);
+
+        assert_eq!(2, report.areas.len());
+        assert_eq!(Some("Inner".to_string()), report.areas[0].name);
+        assert!(report.areas[0].invalid);
+    }
+}
@@ -10,10 +10,12 @@ use std::any::Any;
 use std::cmp::max;
 use theme::ColorStyle;
 
-use unicode_width::UnicodeWidthStr;
+use utils::StyledString;
 use vec::{Vec2, Vec4};
-use view::{Selector, View};
+use view::{Selector, View, child_draw, child_layout,
+           child_required_size};
 use views::{Button, DummyView, SizedView, TextView};
+use views::flow_layout::Flow;
 
 #[derive(PartialEq)]
 enum Focus {
@@ -23,6 +25,8 @@ enum Focus {
 
 /// Popup-like view with a main content, and optional buttons under it.
 ///
+/// When the buttons don't fit on a single row, they wrap to new rows.
+///
 /// # Examples
 ///
 /// ```
@@ -31,7 +35,7 @@ enum Focus {
 ///                     .button("Ok", |s| s.quit());
 /// ```
 pub struct Dialog {
-    title: String,
+    title: StyledString,
     content: Box<View>,
 
     buttons: Vec<SizedView<Button>>,
@@ -42,6 +46,9 @@ pub struct Dialog {
     focus: Focus,
 
     align: Align,
+
+    /// Placement of the buttons, computed during the last layout.
+    buttons_flow: Flow,
 }
 
 new_default!(Dialog);
@@ -59,11 +66,12 @@ impl Dialog {
         Dialog {
             content: Box::new(view),
             buttons: Vec::new(),
-            title: String::new(),
+            title: StyledString::new(),
             focus: Focus::Content,
             padding: Vec4::new(1, 1, 0, 0),
             borders: Vec4::new(1, 1, 1, 1),
             align: Align::top_right(),
+            buttons_flow: Flow::empty(),
         }
     }
 
@@ -86,6 +94,15 @@ impl Dialog {
         Self::around(TextView::new(text))
     }
 
+    /// Convenient method to create a dialog with a styled text content.
+    ///
+    /// See [`StyledString`].
+    ///
+    /// [`StyledString`]: ../utils/markup/struct.StyledString.html
+    pub fn text_styled<S: Into<StyledString>>(text: S) -> Self {
+        Self::around(TextView::new_styled(text))
+    }
+
     /// Convenient method to create an infobox.
     ///
     /// It will contain the given text and a `Ok` dismiss button.
@@ -136,6 +153,22 @@ impl Dialog {
 
     /// Sets the title of the dialog.
     pub fn set_title<S: Into<String>>(&mut self, label: S) {
+        self.set_title_styled(label.into());
+    }
+
+    /// Sets a styled title for the dialog.
+    ///
+    /// Chainable variant.
+    pub fn title_styled<S: Into<StyledString>>(self, label: S) -> Self {
+        self.with(|s| s.set_title_styled(label))
+    }
+
+    /// Sets a styled title for the dialog.
+    ///
+    /// See [`StyledString`].
+    ///
+    /// [`StyledString`]: ../utils/markup/struct.StyledString.html
+    pub fn set_title_styled<S: Into<StyledString>>(&mut self, label: S) {
         self.title = label.into();
     }
 
@@ -169,49 +202,63 @@ impl Dialog {
         self.padding.right = padding;
         self
     }
+
+    // Places the buttons in rows fitting in `width`.
+    fn place_buttons(&mut self, req: Vec2) -> Flow {
+        let sizes = self.buttons
+            .iter_mut()
+            .map(|button| child_required_size(&mut button.view, req))
+            .collect();
+        Flow::place(sizes, req.x, 1, 0, &self.align.h)
+    }
+
+    // Height taken by the buttons, including the blank line above them.
+    fn buttons_height(flow: &Flow) -> usize {
+        if flow.sizes.is_empty() {
+            0
+        } else {
+            flow.size.y + 1
+        }
+    }
+
+    // Finds the closest button on the row above (or below) the button `i`.
+    fn button_row_neighbour(&self, i: usize, up: bool) -> Option<usize> {
+        if self.buttons_flow.rows.len() != self.buttons.len() {
+            return None;
+        }
+        self.buttons_flow.row_neighbours(i, up).into_iter().next()
+    }
 }
 
 impl View for Dialog {
     fn draw(&self, printer: &Printer) {
 
         // This will be the buttons_height used by the buttons.
-        let mut buttons_height = 0;
-        // Current horizontal position of the next button we'll draw.
+        let buttons_height = Dialog::buttons_height(&self.buttons_flow);
 
-        // Sum of the sizes + len-1 for margins
-        let width = if self.buttons.is_empty() {
-            0
-        } else {
-            self.buttons
-                .iter()
-                .map(|button| button.size.x)
-                .fold(0, |a, b| a + b) + self.buttons.len() - 1
-        };
         let overhead = self.padding + self.borders;
         if printer.size.x < overhead.horizontal() {
             return;
         }
-        let mut offset = overhead.left +
-                         self.align
-            .h
-            .get_offset(width, printer.size.x - overhead.horizontal());
 
-        let overhead_bottom = self.padding.bottom + self.borders.bottom + 1;
+        // The buttons rows are right above the bottom padding.
+        let overhead_bottom = self.padding.bottom + self.borders.bottom +
+                              self.buttons_flow.size.y;
         if overhead_bottom > printer.size.y {
             return;
         }
         let y = printer.size.y - overhead_bottom;
 
-        for (i, button) in self.buttons.iter().enumerate() {
-            let size = button.size;
+        for (i, (button, offset)) in self.buttons
+            .iter()
+            .zip(self.buttons_flow.offsets.iter())
+            .enumerate() {
+            let offset = Vec2::new(overhead.left, y) + *offset;
             // Add some special effect to the focused button
-            button.draw(&printer.sub_printer(Vec2::new(offset, y),
-                                             size,
-                                             self.focus == Focus::Button(i)));
-            // Keep 1 blank between two buttons
-            offset += size.x + 1;
-            // Also keep 1 blank above the buttons
-            buttons_height = max(buttons_height, size.y + 1);
+            child_draw(button,
+                       &printer.sub_printer(offset,
+                                            button.size,
+                                            self.focus == Focus::Button(i)));
         }
 
         // What do we have left?
@@ -222,11 +269,11 @@ impl View for Dialog {
         }
         let inner_size = printer.size - taken;
 
-        self.content
-            .draw(&printer.sub_printer(self.borders.top_left() +
-                                       self.padding.top_left(),
-                                       inner_size,
-                                       self.focus == Focus::Content));
+        child_draw(&*self.content,
+                   &printer.sub_printer(self.borders.top_left() +
+                                        self.padding.top_left(),
+                                        inner_size,
+                                        self.focus == Focus::Content));
 
         printer.print_box(Vec2::new(0, 0), printer.size, false);
 
@@ -242,7 +289,7 @@ impl View for Dialog {
             });
 
             printer.with_color(ColorStyle::TitlePrimary,
-                               |p| p.print((x, 0), &self.title));
+                               |p| p.print_styled((x, 0), &self.title));
         }
 
     }
@@ -251,16 +298,15 @@ impl View for Dialog {
         // Padding and borders are not available for kids.
         let nomans_land = self.padding.combined() + self.borders.combined();
 
-        // Buttons are not flexible, so their size doesn't depend on ours.
-        let mut buttons_size = Vec2::new(0, 0);
-        if !self.buttons.is_empty() {
-            buttons_size.x += self.buttons.len() - 1;
-        }
-        for button in &mut self.buttons {
-            let s = button.view.required_size(req);
-            buttons_size.x += s.x;
-            buttons_size.y = max(buttons_size.y, s.y + 1);
-        }
+        // Buttons are not flexible, but they may wrap on multiple rows.
+        let buttons_req = if nomans_land.fits_in(req) {
+            req - nomans_land
+        } else {
+            Vec2::zero()
+        };
+        let flow = self.place_buttons(buttons_req);
+        let buttons_size = Vec2::new(flow.size.x,
+                                     Dialog::buttons_height(&flow));
 
         // We also remove one row for the buttons.
         let taken = nomans_land + Vec2::new(0, buttons_size.y);
@@ -270,7 +316,8 @@ impl View for Dialog {
         }
         let content_req = req - taken;
 
-        let content_size = self.content.required_size(content_req);
+        let content_size = child_required_size(&mut *self.content,
+                                               content_req);
 
         // On the Y axis, we add buttons and content.
         // On the X axis, we take the max.
@@ -298,18 +345,20 @@ impl View for Dialog {
         };
 
         // Buttons are kings, we give them everything they want.
-        let mut buttons_height = 0;
-        for button in self.buttons.iter_mut().rev() {
-            let size = button.required_size(size);
-            buttons_height = max(buttons_height, size.y + 1);
-            button.layout(size);
+        self.buttons_flow = self.place_buttons(size);
+        let mut buttons_height = Dialog::buttons_height(&self.buttons_flow);
+        for (button, &size) in self.buttons
+            .iter_mut()
+            .zip(self.buttons_flow.sizes.iter()) {
+            child_layout(button, size);
         }
 
         // Poor content will have to make do with what's left.
         if buttons_height > size.y {
             buttons_height = size.y;
         }
-        self.content.layout(size - Vec2::new(0, buttons_height));
+        child_layout(&mut *self.content,
+                     size - Vec2::new(0, buttons_height));
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
@@ -337,9 +386,14 @@ impl View for Dialog {
                 match self.buttons[i].on_event(event.clone()) {
                     EventResult::Ignored => {
                         match event {
-                            // Up goes back to the content
+                            // Up goes to the previous row of buttons,
+                            // or back to the content
                             Event::Key(Key::Up) => {
-                                if self.content
+                                let above = self.button_row_neighbour(i, true);
+                                if let Some(j) = above {
+                                    self.focus = Focus::Button(j);
+                                    EventResult::Consumed(None)
+                                } else if self.content
                                     .take_focus(Direction::down()) {
                                     self.focus = Focus::Content;
                                     EventResult::Consumed(None)
@@ -347,6 +401,16 @@ impl View for Dialog {
                                     EventResult::Ignored
                                 }
                             }
+                            // Down goes to the next row of buttons
+                            Event::Key(Key::Down) => {
+                                let below = self.button_row_neighbour(i, false);
+                                if let Some(j) = below {
+                                    self.focus = Focus::Button(j);
+                                    EventResult::Consumed(None)
+                                } else {
+                                    EventResult::Ignored
+                                }
+                            }
                             Event::Shift(Key::Tab) => {
                                 if self.content
                                     .take_focus(Direction::back()) {
@@ -1,17 +1,24 @@
 
 
-use {Cursive, Printer, With};
+use {Cursive, Printer, With, XY};
 use direction::Direction;
 use event::{Callback, Event, EventResult, Key};
-use std::cell::RefCell;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::{max, min};
+use std::mem;
 
 use std::rc::Rc;
-use theme::{ColorStyle, Effect};
+use theme::{BaseColor, Color, ColorStyle, Effect, Style};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
-use utils::simple_suffix;
+use utils::{Change, UndoHistory, simple_suffix};
+use utils::clipboard::{self, Clipboard};
+use utils::edit_mode::{EditMode, Editor};
+use utils::history::InputHistory;
+use utils::validation::{Mask, Validator, Validity};
 use vec::Vec2;
-use view::View;
+use view::{Position, View};
 
 
 /// Input box where the user can enter and edit text.
@@ -59,6 +66,64 @@ use view::View;
 ///
 /// # }
 /// ```
+///
+/// # Validation
+///
+/// Input can be restricted with a maximum length, a [`Mask`], and
+/// [`Validator`]s. Filters reject the keystrokes making the content
+/// invalid, while validators only show it with an error style.
+///
+/// [`Mask`]: ../utils/validation/struct.Mask.html
+/// [`Validator`]: ../utils/validation/trait.Validator.html
+///
+/// ```rust
+/// # use cursive::views::EditView;
+/// # use cursive::utils::validation::{Numeric, Range};
+/// let port = EditView::new()
+///     .max_length(5)
+///     .filter(Numeric::new())
+///     .validator(Range::new(1.0, 65535.0));
+///
+/// let date = EditView::new().mask("####-##-##");
+/// ```
+///
+/// # Completion
+///
+/// A completer suggests candidates for the word under the cursor. They are
+/// shown in a dropdown under the view, and the selected one is previewed
+/// after the cursor. `Tab` and the arrows cycle through the candidates,
+/// `Enter` (or `Right` at the end of the content) accepts one, and `Esc`
+/// dismisses them.
+///
+/// ```rust
+/// # use cursive::views::EditView;
+/// let commands = ["open", "close", "commit"];
+/// let edit = EditView::new().completer(move |content: &str, cursor| {
+///     let word = &content[..cursor];
+///     commands.iter()
+///         .filter(|command| !word.is_empty() && command.starts_with(word))
+///         .map(|command| command.to_string())
+///         .collect()
+/// });
+/// ```
+///
+/// # History
+///
+/// With an [`InputHistory`], submitted content is remembered. `Up` and
+/// `Down` browse previous entries, keeping the content being typed as a
+/// draft, and `Ctrl-R` searches them.
+///
+/// [`InputHistory`]: ../utils/history/struct.InputHistory.html
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use cursive::views::EditView;
+/// # use cursive::utils::history::InputHistory;
+/// let history = Rc::new(RefCell::new(InputHistory::new()));
+/// let edit = EditView::new().input_history(history.clone());
+/// // Later, save `history` to reuse it next time.
+/// ```
 pub struct EditView {
     /// Current content.
     content: Rc<String>,
@@ -89,6 +154,65 @@ pub struct EditView {
     enabled: bool,
 
     style: ColorStyle,
+
+    /// Changes made to the content, to undo them.
+    history: UndoHistory,
+
+    /// Byte offset of the other end of the selection, if any.
+    anchor: Option<usize>,
+
+    /// Used to cut, copy and paste text.
+    clipboard: Rc<RefCell<Clipboard>>,
+
+    /// Handles events before the default key bindings, if any.
+    edit_mode: Option<Box<EditMode>>,
+
+    /// Mark invalid content.
+    validators: Vec<Box<Validator>>,
+
+    /// Reject keystrokes making the content invalid.
+    filters: Vec<Box<Validator>>,
+
+    /// Maximum number of characters, if any.
+    max_length: Option<usize>,
+
+    /// Template for the content, if any.
+    mask: Option<Mask>,
+
+    /// Style used when the content is invalid.
+    error_style: Style,
+
+    /// Suggests candidates for the word under the cursor, if any.
+    completer: Option<Box<Fn(&str, usize) -> Vec<String>>>,
+
+    /// Candidates for the word under the cursor.
+    completions: Vec<String>,
+
+    /// Index of the selected candidate.
+    completion: usize,
+
+    // We need the last offset to place the completion popup.
+    // We "cache" it during the draw, so we need interior mutability.
+    last_offset: Cell<Vec2>,
+
+    /// Previous submissions, if any.
+    input_history: Option<Rc<RefCell<InputHistory>>>,
+
+    /// Index of the history entry being shown, if any.
+    history_index: Option<usize>,
+
+    /// Content typed before browsing the history.
+    draft: String,
+
+    /// Search in the history, if any.
+    search: Option<HistorySearch>,
+}
+
+// Reverse incremental search in the history.
+struct HistorySearch {
+    query: String,
+    /// Index of the matching entry, if any.
+    found: Option<usize>,
 }
 
 new_default!(EditView);
@@ -107,6 +231,23 @@ impl EditView {
             filler: "_".to_string(),
             enabled: true,
             style: ColorStyle::Secondary,
+            history: UndoHistory::new(),
+            anchor: None,
+            clipboard: clipboard::local(),
+            edit_mode: None,
+            validators: Vec::new(),
+            filters: Vec::new(),
+            max_length: None,
+            mask: None,
+            error_style: Style::from(Color::Dark(BaseColor::Red)),
+            completer: None,
+            completions: Vec::new(),
+            completion: 0,
+            last_offset: Cell::new(Vec2::zero()),
+            input_history: None,
+            history_index: None,
+            draft: String::new(),
+            search: None,
         }
     }
 
@@ -298,6 +439,625 @@ impl EditView {
         self.with(|v| v.set_on_submit(callback))
     }
 
+    /// Sets the editing mode, changing the key bindings.
+    ///
+    /// The mode indicator is not shown, but is available from
+    /// [`edit_mode_indicator`](#method.edit_mode_indicator). `Enter` is not
+    /// given to the mode, and always submits the content.
+    pub fn set_edit_mode<M: EditMode + 'static>(&mut self, mode: M) {
+        self.edit_mode = Some(Box::new(mode));
+    }
+
+    /// Sets the editing mode, changing the key bindings.
+    ///
+    /// Chainable variant.
+    pub fn edit_mode<M: EditMode + 'static>(self, mode: M) -> Self {
+        self.with(|s| s.set_edit_mode(mode))
+    }
+
+    /// Goes back to the default key bindings.
+    pub fn clear_edit_mode(&mut self) {
+        self.edit_mode = None;
+    }
+
+    /// Returns the indicator of the editing mode, if any.
+    pub fn edit_mode_indicator(&self) -> Option<&str> {
+        self.edit_mode.as_ref().and_then(|mode| mode.indicator())
+    }
+
+    /// Sets the clipboard used by the editing mode to cut, copy and paste.
+    ///
+    /// Defaults to the in-process clipboard shared by all views.
+    pub fn set_clipboard(&mut self, clipboard: Rc<RefCell<Clipboard>>) {
+        self.clipboard = clipboard;
+    }
+
+    /// Sets the clipboard used by the editing mode to cut, copy and paste.
+    ///
+    /// Chainable variant.
+    pub fn clipboard(self, clipboard: Rc<RefCell<Clipboard>>) -> Self {
+        self.with(|s| s.set_clipboard(clipboard))
+    }
+
+    /// Returns the byte range of the selected text, if any.
+    ///
+    /// Text can only be selected by an editing mode.
+    pub fn selected_range(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((min(anchor, self.cursor), max(anchor, self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a validator, marking invalid content with the error style.
+    pub fn add_validator<V: Validator + 'static>(&mut self, validator: V) {
+        self.validators.push(Box::new(validator));
+    }
+
+    /// Adds a validator, marking invalid content with the error style.
+    ///
+    /// Chainable variant.
+    pub fn validator<V: Validator + 'static>(self, validator: V) -> Self {
+        self.with(|s| s.add_validator(validator))
+    }
+
+    /// Adds a filter, rejecting keystrokes which make the content invalid.
+    ///
+    /// Incomplete content is accepted.
+    pub fn add_filter<V: Validator + 'static>(&mut self, filter: V) {
+        self.filters.push(Box::new(filter));
+    }
+
+    /// Adds a filter, rejecting keystrokes which make the content invalid.
+    ///
+    /// Chainable variant.
+    pub fn filter<V: Validator + 'static>(self, filter: V) -> Self {
+        self.with(|s| s.add_filter(filter))
+    }
+
+    /// Sets the maximum number of characters in the content.
+    ///
+    /// `None` removes the limit.
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+    }
+
+    /// Sets the maximum number of characters in the content.
+    ///
+    /// Chainable variant.
+    pub fn max_length(self, max_length: usize) -> Self {
+        self.with(|s| s.set_max_length(Some(max_length)))
+    }
+
+    /// Sets the template the content must follow.
+    ///
+    /// Literals of the mask are inserted when typing, and characters not
+    /// fitting in the mask are rejected. Typing or deleting a character
+    /// moves the following ones into their new slots. `None` removes the
+    /// mask.
+    pub fn set_mask(&mut self, mask: Option<Mask>) {
+        self.mask = mask;
+    }
+
+    /// Sets the template the content must follow.
+    ///
+    /// Chainable variant. See [`Mask`] for the syntax.
+    ///
+    /// [`Mask`]: ../utils/validation/struct.Mask.html
+    pub fn mask(self, template: &str) -> Self {
+        self.with(|s| s.set_mask(Some(Mask::new(template))))
+    }
+
+    /// Sets the style used when the content is invalid.
+    ///
+    /// Defaults to red. When the view is enabled, the style will be reversed.
+    pub fn set_error_style<S: Into<Style>>(&mut self, style: S) {
+        self.error_style = style.into();
+    }
+
+    /// Sets the style used when the content is invalid.
+    ///
+    /// Chainable variant.
+    pub fn error_style<S: Into<Style>>(self, style: S) -> Self {
+        self.with(|s| s.set_error_style(style))
+    }
+
+    /// Returns the validity of the current content.
+    ///
+    /// Checks the mask, then the validators and filters. Invalid content
+    /// takes precedence over incomplete content.
+    pub fn validity(&self) -> Validity {
+        let mut result = match self.mask {
+            Some(ref mask) => mask.check(&self.content),
+            None => Validity::Valid,
+        };
+
+        let validators = self.validators.iter().chain(&self.filters);
+        for validator in validators {
+            if result.is_invalid() {
+                break;
+            }
+            match validator.validate(&self.content) {
+                Validity::Valid => (),
+                Validity::Incomplete => result = Validity::Incomplete,
+                invalid => result = invalid,
+            }
+        }
+        result
+    }
+
+    // Returns `false` if `content` breaks the maximum length, the mask or
+    // a filter.
+    fn accepts(&self, content: &str) -> bool {
+        if let Some(max_length) = self.max_length {
+            if content.chars().count() > max_length {
+                return false;
+            }
+        }
+        if let Some(ref mask) = self.mask {
+            if mask.check(content).is_invalid() {
+                return false;
+            }
+        }
+        !self.filters
+            .iter()
+            .any(|filter| filter.validate(content).is_invalid())
+    }
+
+    // Returns the text to insert when `ch` is typed, if it is accepted.
+    fn typed_text(&self, ch: char) -> Option<String> {
+        let before = &self.content[..self.cursor];
+        let text = match self.mask {
+            Some(ref mask) => {
+                match mask.complete(before, ch) {
+                    Some(text) => text,
+                    None => return None,
+                }
+            }
+            None => ch.to_string(),
+        };
+
+        let content = format!("{}{}{}", before, text,
+                              &self.content[self.cursor..]);
+        if self.accepts(&content) {
+            Some(text)
+        } else {
+            None
+        }
+    }
+
+    // Returns the characters typed in the mask before and after the cursor.
+    fn masked_split(&self, mask: &Mask) -> (String, String) {
+        let mut before = mask.strip(&self.content);
+        let count = mask.strip(&self.content[..self.cursor]).len();
+        let after = before.split_off(count);
+        (before, after)
+    }
+
+    // Returns the content and cursor after typing `ch` before the end of a
+    // masked content, if it is accepted.
+    //
+    // The following characters move forward in the mask.
+    fn masked_insert(&self, mask: &Mask, ch: char)
+                     -> Option<(String, usize)> {
+        let (before, after) = self.masked_split(mask);
+        let typed = format!("{}{}", before, ch);
+
+        let content = match mask.fill(&format!("{}{}", typed, after)) {
+            Some(content) => content,
+            None => return None,
+        };
+        let cursor = mask.fill(&typed).unwrap().len();
+        if self.accepts(&content) {
+            Some((content, cursor))
+        } else {
+            None
+        }
+    }
+
+    // Returns the content and cursor after removing the character typed
+    // before the cursor (or after it if `forward` is `true`) in a masked
+    // content, if it is accepted.
+    //
+    // The following characters move back in the mask, and literals are
+    // skipped.
+    fn masked_remove(&self, mask: &Mask, forward: bool)
+                     -> Option<(String, usize)> {
+        let (mut before, mut after) = self.masked_split(mask);
+        let removed = if forward {
+            if after.is_empty() {
+                None
+            } else {
+                Some(after.remove(0))
+            }
+        } else {
+            before.pop()
+        };
+        if removed.is_none() {
+            return None;
+        }
+
+        let content = match mask.fill(&format!("{}{}", before, after)) {
+            Some(content) => content,
+            None => return None,
+        };
+        let cursor = mask.fill(&before).unwrap().len();
+        if self.accepts(&content) {
+            Some((content, cursor))
+        } else {
+            None
+        }
+    }
+
+    // Returns `true` if removing the text between `start` and `end` leaves
+    // an accepted content.
+    fn accepts_removal(&self, start: usize, end: usize) -> bool {
+        let content = format!("{}{}",
+                              &self.content[..start],
+                              &self.content[end..]);
+        self.accepts(&content)
+    }
+
+    // Handles `Backspace` or `Del` (if `forward` is `true`) with a mask.
+    fn on_masked_remove(&mut self, forward: bool) -> EventResult {
+        let removed = match self.mask {
+            Some(ref mask) => self.masked_remove(mask, forward),
+            None => None,
+        };
+        match removed {
+            Some((content, cursor)) => {
+                self.set_masked_content(content, cursor);
+                self.history_index = None;
+                self.update_completions();
+                self.keep_cursor_in_view();
+                self.edited()
+            }
+            // Rejected keystroke
+            None => EventResult::Consumed(None),
+        }
+    }
+
+    // Replaces the content after a masked edit, recording the change.
+    fn set_masked_content(&mut self, content: String, cursor: usize) {
+        // Only record the part which changed.
+        let start = common_prefix(&self.content, &content);
+        let end = common_suffix(&self.content[start..], &content[start..]);
+        self.history.record(start,
+                            &self.content[start..self.content.len() - end],
+                            &content[start..content.len() - end],
+                            self.cursor,
+                            cursor);
+        *Rc::make_mut(&mut self.content) = content;
+        self.cursor = cursor;
+    }
+
+    /// Sets the completer, suggesting candidates as the content is edited.
+    ///
+    /// `completer` will be called with the view content and the cursor
+    /// position, and returns the candidates for the word under the cursor.
+    /// An accepted candidate replaces this word.
+    pub fn set_completer<F>(&mut self, completer: F)
+        where F: Fn(&str, usize) -> Vec<String> + 'static
+    {
+        self.completer = Some(Box::new(completer));
+    }
+
+    /// Sets the completer, suggesting candidates as the content is edited.
+    ///
+    /// Chainable variant. See [`set_completer`](#method.set_completer).
+    pub fn completer<F>(self, completer: F) -> Self
+        where F: Fn(&str, usize) -> Vec<String> + 'static
+    {
+        self.with(|s| s.set_completer(completer))
+    }
+
+    /// Removes the completer.
+    pub fn clear_completer(&mut self) {
+        self.completer = None;
+        self.completions.clear();
+    }
+
+    /// Returns the candidates currently suggested.
+    pub fn completions(&self) -> &[String] {
+        &self.completions
+    }
+
+    /// Returns the selected candidate, if any.
+    pub fn selected_completion(&self) -> Option<&str> {
+        self.completions.get(self.completion).map(|c| c.as_str())
+    }
+
+    // Returns the byte range of the word under the cursor.
+    fn word_range(&self) -> (usize, usize) {
+        let start = self.content[..self.cursor]
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| c.is_whitespace())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(0);
+        let end = self.content[self.cursor..]
+            .find(char::is_whitespace)
+            .map(|i| self.cursor + i)
+            .unwrap_or(self.content.len());
+        (start, end)
+    }
+
+    // Asks the completer for candidates.
+    fn update_completions(&mut self) {
+        self.completion = 0;
+        self.completions = match self.completer {
+            Some(ref completer) if !self.secret => {
+                completer(&self.content, self.cursor)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    // Returns the end of the selected candidate, shown after the cursor.
+    //
+    // Only when the cursor is at the end of the content, and the candidate
+    // continues the word before it.
+    fn ghost_text(&self) -> Option<&str> {
+        if self.cursor != self.content.len() {
+            return None;
+        }
+        let (start, _) = self.word_range();
+        let word = &self.content[start..];
+        self.selected_completion()
+            .filter(|candidate| candidate.starts_with(word))
+            .map(|candidate| &candidate[word.len()..])
+            .filter(|ghost| !ghost.is_empty())
+    }
+
+    // Replaces the word under the cursor with the selected candidate.
+    fn accept_completion(&mut self) -> EventResult {
+        let candidate = self.completions[self.completion].clone();
+        let (start, end) = self.word_range();
+        self.completions.clear();
+        self.replace_range(start, end, &candidate);
+        self.keep_cursor_in_view();
+        self.edited()
+    }
+
+    // Handles the keys browsing the candidates.
+    fn on_completion_event(&mut self, event: &Event) -> Option<EventResult> {
+        let n = self.completions.len();
+        match *event {
+            Event::Key(Key::Tab) |
+            Event::Key(Key::Down) => self.completion = (self.completion + 1) % n,
+            Event::Shift(Key::Tab) |
+            Event::Key(Key::Up) => self.completion = (self.completion + n - 1) % n,
+            Event::Key(Key::Enter) => return Some(self.accept_completion()),
+            Event::Key(Key::Right) |
+            Event::Key(Key::End) if self.ghost_text().is_some() => {
+                return Some(self.accept_completion())
+            }
+            Event::Key(Key::Esc) => {
+                self.completions.clear();
+                return Some(EventResult::Consumed(None));
+            }
+            _ => return None,
+        }
+        Some(EventResult::Consumed(self.completion_popup()))
+    }
+
+    // Returns a callback showing the candidates under the word, if any.
+    fn completion_popup(&self) -> Option<Callback> {
+        if self.completions.is_empty() {
+            return None;
+        }
+
+        // Align the candidates with the word.
+        let (start, _) = self.word_range();
+        let first = if self.content.width() < self.last_length {
+            0
+        } else {
+            min(self.offset, start)
+        };
+        let x = self.content[first..start].width();
+        // The popup is placed:
+        // * just below the view
+        // * shifted left of the border of the popup
+        let offset = self.last_offset.get() + (x, 1) - (min(x, 1), 0);
+
+        let candidates = self.completions.clone();
+        let selected = self.completion;
+        Some(Callback::from_fn(move |s| {
+            let current_offset = s.screen().offset();
+            let offset = XY::<isize>::from(offset) - current_offset;
+            let popup = CompletionPopup {
+                candidates: candidates.clone(),
+                selected: selected,
+            };
+            s.screen_mut().add_layer_at(Position::parent(offset), popup);
+        }))
+    }
+
+    /// Sets the history of submitted content.
+    ///
+    /// The content is added to the history when `<Enter>` is pressed. The
+    /// history can be shared with other views, and saved by the caller.
+    pub fn set_input_history(&mut self,
+                             history: Rc<RefCell<InputHistory>>) {
+        self.input_history = Some(history);
+    }
+
+    /// Sets the history of submitted content.
+    ///
+    /// Chainable variant.
+    pub fn input_history(self, history: Rc<RefCell<InputHistory>>) -> Self {
+        self.with(|s| s.set_input_history(history))
+    }
+
+    /// Returns the query of the history search in progress, if any.
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.query.as_str())
+    }
+
+    // Returns the history entry at index `i`, if any.
+    fn history_entry(&self, i: usize) -> Option<String> {
+        self.input_history
+            .as_ref()
+            .and_then(|history| history.borrow().get(i).map(String::from))
+    }
+
+    // Replaces the whole content, as one change which can be undone.
+    //
+    // Does nothing if the new content is not accepted.
+    fn replace_content(&mut self, text: &str) {
+        if !self.accepts(text) {
+            return;
+        }
+        let removed = mem::replace(Rc::make_mut(&mut self.content),
+                                   text.to_string());
+        self.history.seal();
+        self.history.record(0, &removed, text, self.cursor, text.len());
+        self.history.seal();
+        self.cursor = text.len();
+        self.keep_cursor_in_view();
+    }
+
+    // Shows the previous history entry.
+    //
+    // The current content is kept as a draft when leaving it.
+    fn history_previous(&mut self) -> EventResult {
+        let len = match self.input_history {
+            Some(ref history) => history.borrow().len(),
+            None => return EventResult::Ignored,
+        };
+        let i = match self.history_index {
+            None if len > 0 => {
+                self.draft = (*self.content).clone();
+                len - 1
+            }
+            None => return EventResult::Ignored,
+            Some(i) if i > 0 => i - 1,
+            // Nothing older
+            Some(_) => return EventResult::Consumed(None),
+        };
+        if let Some(entry) = self.history_entry(i) {
+            self.history_index = Some(i);
+            self.replace_content(&entry);
+        }
+        self.edited()
+    }
+
+    // Shows the next history entry, or the draft after the last one.
+    fn history_next(&mut self) -> EventResult {
+        let i = match self.history_index {
+            Some(i) => i + 1,
+            None => return EventResult::Ignored,
+        };
+        match self.history_entry(i) {
+            Some(entry) => {
+                self.history_index = Some(i);
+                self.replace_content(&entry);
+            }
+            None => {
+                self.history_index = None;
+                let draft = mem::replace(&mut self.draft, String::new());
+                self.replace_content(&draft);
+            }
+        }
+        self.edited()
+    }
+
+    // Adds the content to the history.
+    fn remember(&mut self) {
+        if let Some(ref history) = self.input_history {
+            history.borrow_mut().push((*self.content).clone());
+        }
+        self.history_index = None;
+        self.draft.clear();
+    }
+
+    // Looks for the most recent entry before `before` matching the query.
+    fn search_before(&mut self, before: usize) {
+        let found = match (self.search.as_ref(), self.input_history.as_ref()) {
+            (Some(search), Some(history)) if !search.query.is_empty() => {
+                history.borrow().search(&search.query, before)
+            }
+            _ => None,
+        };
+        if let Some(ref mut search) = self.search {
+            search.found = found;
+        }
+    }
+
+    // Handles an event during a history search.
+    //
+    // Returns `None` when the event ends the search and should be handled
+    // normally.
+    fn on_search_event(&mut self, event: &Event) -> Option<EventResult> {
+        let (current, query_len) = match self.search {
+            Some(ref search) => (search.found, search.query.len()),
+            None => return None,
+        };
+        match *event {
+            Event::Char(c) => {
+                self.search.as_mut().unwrap().query.push(c);
+                // The current match may still match the longer query.
+                let before = current.map(|i| i + 1)
+                    .unwrap_or(usize::max_value());
+                self.search_before(before);
+            }
+            Event::Key(Key::Backspace) if query_len > 0 => {
+                self.search.as_mut().unwrap().query.pop();
+                self.search_before(usize::max_value());
+            }
+            Event::CtrlChar('r') => {
+                let found = current.and_then(|i| {
+                    let query = &self.search.as_ref().unwrap().query;
+                    self.input_history
+                        .as_ref()
+                        .and_then(|h| h.borrow().search(query, i))
+                });
+                if found.is_some() {
+                    self.search.as_mut().unwrap().found = found;
+                }
+            }
+            Event::Key(Key::Esc) |
+            Event::CtrlChar('g') => {
+                self.search = None;
+            }
+            _ => {
+                // Take the match, then handle the event.
+                let entry = current.and_then(|i| self.history_entry(i));
+                self.search = None;
+                if let Some(entry) = entry {
+                    self.history_index = None;
+                    self.replace_content(&entry);
+                }
+                return match *event {
+                    Event::Key(Key::Enter) => Some(self.edited()),
+                    _ => None,
+                };
+            }
+        }
+        Some(EventResult::Consumed(None))
+    }
+
+    // Draws the search query and the matching entry.
+    fn draw_search(&self, printer: &Printer, search: &HistorySearch) {
+        let entry = search.found.and_then(|i| self.history_entry(i));
+        let style = if entry.is_none() && !search.query.is_empty() {
+            self.error_style
+        } else {
+            Style::from(self.style)
+        };
+        let text = format!("({}) {}",
+                           search.query,
+                           entry.unwrap_or_default());
+        printer.with_style(style, |printer| {
+            printer.with_effect(Effect::Reverse, |printer| {
+                printer.print_hline((0, 0), printer.size.x, " ");
+                printer.print((0, 0), &text);
+            });
+        });
+    }
+
     /// Enable or disable this view.
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -309,11 +1069,19 @@ impl EditView {
     }
 
     /// Replace the entire content of the view with the given one.
+    ///
+    /// This clears the undo history. Content breaking the maximum length,
+    /// the mask or a filter is ignored.
     pub fn set_content<S: Into<String>>(&mut self, content: S) {
         let content = content.into();
+        if !self.accepts(&content) {
+            return;
+        }
         let len = content.len();
 
         self.content = Rc::new(content);
+        self.history.clear();
+        self.anchor = None;
         self.offset = 0;
         self.set_cursor(len);
     }
@@ -339,21 +1107,110 @@ impl EditView {
     }
 
     /// Insert `ch` at the current cursor position.
+    ///
+    /// Unlike typing, this doesn't check the maximum length, the mask or
+    /// the filters.
     pub fn insert(&mut self, ch: char) {
         // `make_mut` applies copy-on-write
         // It means it'll just return a ref if no one else has a ref,
         // and it will clone it into `self.content` otherwise.
         Rc::make_mut(&mut self.content).insert(self.cursor, ch);
-        self.cursor += ch.len_utf8();
+
+        let start = self.cursor;
+        let end = start + ch.len_utf8();
+        self.history.record(start, "", &self.content[start..end], start, end);
+        self.cursor = end;
     }
 
     /// Remove the character at the current cursor position.
+    ///
+    /// Unlike typing, this doesn't check the maximum length, the mask or
+    /// the filters.
     pub fn remove(&mut self, len: usize) {
+        let cursor = self.cursor;
+        self.remove_from(len, cursor);
+    }
+
+    // Removes `len` bytes at the cursor.
+    //
+    // `cursor_before` is where the cursor was before the edit.
+    fn remove_from(&mut self, len: usize, cursor_before: usize) {
         let start = self.cursor;
         let end = self.cursor + len;
+        self.history.record(start,
+                            &self.content[start..end],
+                            "",
+                            cursor_before,
+                            start);
         for _ in Rc::make_mut(&mut self.content).drain(start..end) {}
     }
 
+    /// Reverts the last group of changes.
+    ///
+    /// Bound to `Ctrl-Z`.
+    ///
+    /// A change breaking the maximum length, the mask or a filter, which
+    /// were maybe set after the change was made, is not reverted.
+    pub fn undo(&mut self) {
+        if let Some(change) = self.history.undo() {
+            if !self.apply_change(&change) {
+                // Put it back.
+                self.history.redo();
+            }
+        }
+    }
+
+    /// Applies again the last undone group of changes.
+    ///
+    /// Bound to `Ctrl-Y`. On macOS and BSD, this is also the delayed suspend
+    /// character (DSUSP), which only reaches the application because the
+    /// terminal is in raw mode.
+    pub fn redo(&mut self) {
+        if let Some(change) = self.history.redo() {
+            if !self.apply_change(&change) {
+                self.history.undo();
+            }
+        }
+    }
+
+    // Applies a change from the history, if the result is accepted.
+    fn apply_change(&mut self, change: &Change) -> bool {
+        let mut content = (*self.content).clone();
+        change.apply(&mut content);
+        if !self.accepts(&content) {
+            return false;
+        }
+        self.content = Rc::new(content);
+        self.set_cursor(change.cursor);
+        true
+    }
+
+    /// Forgets all changes made so far, so they cannot be undone.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    // Returns the result of an event which may have changed the content.
+    fn edited(&self) -> EventResult {
+        let cb = self.on_edit.clone().map(|cb| {
+
+            // Get a new Rc on the content
+            let content = self.content.clone();
+            let cursor = self.cursor;
+
+            Callback::from_fn(move |s| { cb(s, &content, cursor); })
+        });
+        match (cb, self.completion_popup()) {
+            (Some(cb), Some(popup)) => {
+                EventResult::with_cb(move |s| {
+                    cb(s);
+                    popup(s);
+                })
+            }
+            (cb, popup) => EventResult::Consumed(cb.or(popup)),
+        }
+    }
+
     fn keep_cursor_in_view(&mut self) {
         // keep cursor in [offset, offset+last_length] by changing offset
         // so keep offset in [last_length-cursor,cursor]
@@ -399,6 +1256,25 @@ impl EditView {
     }
 }
 
+// Returns the length in bytes of the common start of `a` and `b`.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|&((_, x), y)| x != y)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| min(a.len(), b.len()))
+}
+
+// Returns the length in bytes of the common end of `a` and `b`.
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|&(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
 /// Returns a `&str` with `length` characters `*`.
 ///
 /// Only works for small `length` (1 or 2).
@@ -408,15 +1284,83 @@ fn make_small_stars(length: usize) -> &'static str {
     &"****"[..length]
 }
 
+// Maximum number of completion candidates shown at once.
+const MAX_COMPLETIONS: usize = 8;
+
+// Dropdown showing the completion candidates of an `EditView`.
+//
+// It only displays them: events go back to the `EditView`, which shows the
+// popup again while candidates remain.
+struct CompletionPopup {
+    candidates: Vec<String>,
+    selected: usize,
+}
+
+impl View for CompletionPopup {
+    fn draw(&self, printer: &Printer) {
+        if printer.size.x < 2 || printer.size.y < 2 {
+            return;
+        }
+
+        printer.print_box((0, 0), printer.size, false);
+
+        let printer = printer.sub_printer((1, 1), printer.size - (2, 2), true);
+        let height = printer.size.y;
+        // Keep the selected candidate visible.
+        let first = (self.selected + 1).saturating_sub(height);
+        let candidates = self.candidates.iter().enumerate().skip(first);
+        for (y, (i, candidate)) in candidates.take(height).enumerate() {
+            printer.with_selection(i == self.selected, |printer| {
+                printer.print_hline((0, y), printer.size.x, " ");
+                printer.print((0, y), candidate);
+            });
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let w = 2 +
+                self.candidates
+            .iter()
+            .map(|candidate| candidate.width())
+            .max()
+            .unwrap_or(1);
+        let h = 2 + min(self.candidates.len(), MAX_COMPLETIONS);
+
+        Vec2::new(w, h)
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        // Give the event back to the view below.
+        EventResult::with_cb(move |s| {
+            s.pop_layer();
+            match s.screen_mut().on_event(event.clone()) {
+                EventResult::Ignored => s.on_event(event.clone()),
+                result => result.process(s),
+            }
+        })
+    }
+}
+
 impl View for EditView {
     fn draw(&self, printer: &Printer) {
         assert_eq!(printer.size.x, self.last_length,
                 "Was promised {}, received {}",
                 self.last_length,
                 printer.size.x);
+        self.last_offset.set(printer.offset);
+
+        if let Some(ref search) = self.search {
+            self.draw_search(printer, search);
+            return;
+        }
 
         let width = self.content.width();
-        printer.with_color(self.style, |printer| {
+        let style = if self.validity().is_invalid() {
+            self.error_style
+        } else {
+            Style::from(self.style)
+        };
+        printer.with_style(style, |printer| {
             let effect = if self.enabled {
                 Effect::Reverse
             } else {
@@ -462,9 +1406,39 @@ impl View for EditView {
                 }
             });
 
+            // Highlight the visible part of the selection.
+            if let (false, Some((start, end))) =
+                (self.secret, self.selected_range()) {
+                let first = if width < self.last_length {
+                    0
+                } else {
+                    self.offset
+                };
+                let start = max(start, first);
+                if start < end {
+                    let x = self.content[first..start].width();
+                    printer.with_color(ColorStyle::Highlight, |printer| {
+                        printer.print((x, 0), &self.content[start..end]);
+                    });
+                }
+            }
+
+            // Preview the selected candidate after the cursor.
+            let ghost = self.ghost_text();
+            if let Some(ghost) = ghost {
+                let x = self.content[self.offset..self.cursor].width();
+                printer.with_color(ColorStyle::Tertiary, |printer| {
+                    printer.with_effect(Effect::Reverse, |printer| {
+                        printer.print((x, 0), ghost);
+                    });
+                });
+            }
+
             // Now print cursor
             if printer.focused {
-                let c: &str = if self.cursor == self.content.len() {
+                let c: &str = if let Some(ghost) = ghost {
+                    ghost.graphemes(true).next().unwrap()
+                } else if self.cursor == self.content.len() {
                     &self.filler
                 } else {
                     // Get the char from the string... Is it so hard?
@@ -496,9 +1470,82 @@ impl View for EditView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        if let Some(result) = self.on_search_event(&event) {
+            return result;
+        }
+        if !self.completions.is_empty() {
+            if let Some(result) = self.on_completion_event(&event) {
+                return result;
+            }
+        }
+        // Other events dismiss the candidates, unless they edit the word.
+        self.completions.clear();
+
+        // `Enter` submits the content, whatever the editing mode.
+        let actions = match event {
+            Event::Key(Key::Enter) => None,
+            _ => {
+                self.edit_mode.as_mut().and_then(|mode| mode.on_event(&event))
+            }
+        };
+        if let Some(actions) = actions {
+            self.history.seal();
+            self.history_index = None;
+            for action in actions {
+                self.apply(action);
+            }
+            self.keep_cursor_in_view();
+            return self.edited();
+        }
+
+        // Without an editing mode, nothing can be selected.
+        self.anchor = None;
+
+        // Moving the cursor around ends the current group of changes.
+        match event {
+            Event::Char(_) |
+            Event::Key(Key::Backspace) |
+            Event::Key(Key::Del) => (),
+            _ => self.history.seal(),
+        }
 
         match event {
-            Event::Char(ch) => self.insert(ch),
+            Event::CtrlChar('z') => self.undo(),
+            Event::CtrlChar('y') => self.redo(),
+            Event::CtrlChar('r') if self.input_history.is_some() => {
+                self.search = Some(HistorySearch {
+                    query: String::new(),
+                    found: None,
+                });
+                return EventResult::Consumed(None);
+            }
+            Event::Key(Key::Up) => return self.history_previous(),
+            Event::Key(Key::Down) => return self.history_next(),
+            Event::Char(ch) => {
+                let masked = match self.mask {
+                    Some(ref mask) if self.cursor < self.content.len() => {
+                        Some(self.masked_insert(mask, ch))
+                    }
+                    _ => None,
+                };
+                match masked {
+                    Some(Some((content, cursor))) => {
+                        self.set_masked_content(content, cursor)
+                    }
+                    Some(None) => return EventResult::Consumed(None),
+                    None => {
+                        match self.typed_text(ch) {
+                            Some(text) => {
+                                for ch in text.chars() {
+                                    self.insert(ch);
+                                }
+                            }
+                            // Rejected keystroke
+                            None => return EventResult::Consumed(None),
+                        }
+                    }
+                }
+            }
             // TODO: handle ctrl-key?
             Event::Key(Key::Home) => self.cursor = 0,
             Event::Key(Key::End) => self.cursor = self.content.len(),
@@ -519,40 +1566,217 @@ impl View for EditView {
                 self.cursor += len;
             }
             Event::Key(Key::Backspace) if self.cursor > 0 => {
+                if self.mask.is_some() {
+                    return self.on_masked_remove(false);
+                }
                 let len = self.content[..self.cursor]
                     .graphemes(true)
                     .last()
                     .unwrap()
                     .len();
+                let cursor = self.cursor;
+                if !self.accepts_removal(cursor - len, cursor) {
+                    return EventResult::Consumed(None);
+                }
                 self.cursor -= len;
-                self.remove(len);
+                self.remove_from(len, cursor);
             }
             Event::Key(Key::Del) if self.cursor < self.content.len() => {
+                if self.mask.is_some() {
+                    return self.on_masked_remove(true);
+                }
                 let len = self.content[self.cursor..]
                     .graphemes(true)
                     .next()
                     .unwrap()
                     .len();
+                let cursor = self.cursor;
+                if !self.accepts_removal(cursor, cursor + len) {
+                    return EventResult::Consumed(None);
+                }
                 self.remove(len);
             }
-            Event::Key(Key::Enter) if self.on_submit.is_some() => {
-                let cb = self.on_submit.clone().unwrap();
-                let content = self.content.clone();
-                return EventResult::with_cb(move |s| { cb(s, &content); });
+            Event::Key(Key::Enter) => {
+                self.remember();
+                return match self.on_submit.clone() {
+                    Some(cb) => {
+                        let content = self.content.clone();
+                        EventResult::with_cb(move |s| { cb(s, &content); })
+                    }
+                    None => EventResult::Ignored,
+                };
             }
             _ => return EventResult::Ignored,
         }
 
+        match event {
+            Event::Char(_) |
+            Event::Key(Key::Backspace) |
+            Event::Key(Key::Del) => {
+                // Editing an entry makes it the new draft.
+                self.history_index = None;
+                self.update_completions();
+            }
+            _ => (),
+        }
+
         self.keep_cursor_in_view();
+        self.edited()
+    }
+}
 
-        let cb = self.on_edit.clone().map(|cb| {
+impl Editor for EditView {
+    fn content_len(&self) -> usize {
+        self.content.len()
+    }
 
-            // Get a new Rc on the content
-            let content = self.content.clone();
-            let cursor = self.cursor;
+    fn text(&self, start: usize, end: usize) -> Cow<str> {
+        Cow::Borrowed(&self.content[start..end])
+    }
 
-            Callback::from_fn(move |s| { cb(s, &content, cursor); })
-        });
-        EventResult::Consumed(cb)
+    fn line_at(&self, _: usize) -> (usize, &str) {
+        (0, &self.content)
+    }
+
+    fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    fn anchor(&self) -> Option<usize> {
+        self.anchor
+    }
+
+    fn set_anchor(&mut self, anchor: Option<usize>) {
+        self.anchor = anchor;
+    }
+
+    fn replace_range(&mut self, start: usize, end: usize, text: &str) {
+        // We only have one line.
+        let text = text.replace('\n', "");
+        let content = format!("{}{}{}",
+                              &self.content[..start],
+                              text,
+                              &self.content[end..]);
+        if !self.accepts(&content) {
+            return;
+        }
+
+        let removed = self.content[start..end].to_string();
+        {
+            let content = Rc::make_mut(&mut self.content);
+            content.drain(start..end);
+            content.insert_str(start, &text);
+        }
+
+        let cursor_after = start + text.len();
+        self.history.seal();
+        self.history
+            .record(start, &removed, &text, self.cursor, cursor_after);
+        self.history.seal();
+        self.cursor = cursor_after;
+    }
+
+    fn undo(&mut self) {
+        EditView::undo(self);
+    }
+
+    fn redo(&mut self) {
+        EditView::redo(self);
+    }
+
+    fn get_clipboard(&self) -> Rc<RefCell<Clipboard>> {
+        self.clipboard.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_keys(view: &mut EditView, keys: &str) {
+        for c in keys.chars() {
+            view.on_event(Event::Char(c));
+        }
+    }
+
+    fn commands(content: &str, cursor: usize) -> Vec<String> {
+        let word = content[..cursor].split(' ').last().unwrap();
+        ["cargo", "cat", "cd"]
+            .iter()
+            .filter(|command| !word.is_empty() && command.starts_with(word))
+            .map(|command| command.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completion() {
+        let mut edit = EditView::new().completer(commands);
+        type_keys(&mut edit, "git ca");
+        assert_eq!(&["cargo", "cat"], edit.completions());
+        assert_eq!(Some("rgo"), edit.ghost_text());
+
+        edit.on_event(Event::Key(Key::Tab));
+        assert_eq!(Some("cat"), edit.selected_completion());
+        assert_eq!(Some("t"), edit.ghost_text());
+        edit.on_event(Event::Key(Key::Down));
+        assert_eq!(Some("cargo"), edit.selected_completion());
+        edit.on_event(Event::Key(Key::Up));
+        edit.on_event(Event::Key(Key::Enter));
+        assert_eq!("git cat", &*edit.get_content());
+        assert!(edit.completions().is_empty());
+
+        edit.on_event(Event::Key(Key::Backspace));
+        edit.on_event(Event::Key(Key::Right));
+        assert_eq!("git cargo", &*edit.get_content());
+        edit.undo();
+        assert_eq!("git ca", &*edit.get_content());
+
+        // Other keys dismiss the candidates.
+        type_keys(&mut edit, "r");
+        edit.on_event(Event::Key(Key::Left));
+        assert!(edit.completions().is_empty());
+        type_keys(&mut edit, "a");
+        assert_eq!("git caar", &*edit.get_content());
+        assert_eq!(None, edit.ghost_text());
+        edit.on_event(Event::Key(Key::Esc));
+        assert!(edit.completions().is_empty());
+    }
+
+    #[test]
+    fn input_history() {
+        let history = Rc::new(RefCell::new(InputHistory::new()));
+        let mut edit = EditView::new().input_history(history.clone());
+        for entry in &["make", "cargo build", "cargo test", "make"] {
+            edit.set_content(*entry);
+            edit.on_event(Event::Key(Key::Enter));
+        }
+        assert_eq!(&["cargo build", "cargo test", "make"],
+                   history.borrow().entries());
+
+        edit.set_content("draft");
+        edit.on_event(Event::Key(Key::Up));
+        edit.on_event(Event::Key(Key::Up));
+        assert_eq!("cargo test", &*edit.get_content());
+        type_keys(&mut edit, "s");
+        edit.on_event(Event::Key(Key::Up));
+        assert_eq!("make", &*edit.get_content());
+        // The edited entry became the draft.
+        edit.on_event(Event::Key(Key::Down));
+        assert_eq!("cargo tests", &*edit.get_content());
+        assert!(!edit.on_event(Event::Key(Key::Down)).is_consumed());
+
+        edit.on_event(Event::CtrlChar('r'));
+        type_keys(&mut edit, "cargo");
+        assert_eq!(Some("cargo"), edit.search_query());
+        edit.on_event(Event::CtrlChar('r'));
+        edit.on_event(Event::Key(Key::Enter));
+        assert_eq!(None, edit.search_query());
+        assert_eq!("cargo build", &*edit.get_content());
+        edit.undo();
+        assert_eq!("cargo tests", &*edit.get_content());
     }
 }
@@ -0,0 +1,550 @@
+use Cursive;
+use With;
+use event::Callback;
+use std::any::Any;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vec::Vec2;
+use view::{Boxable, Selector, View, ViewWrapper, child_layout,
+           child_required_size};
+use views::{Checkbox, Dialog, EditView, IdView, LinearLayout, SelectView,
+            TextView};
+
+// Used to give each file dialog a unique id.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Open,
+    Save,
+    Directory,
+}
+
+/// Dialog to choose a file to open or save, or a directory.
+///
+/// It shows the content of a directory, where `<Enter>` opens the selected
+/// directory or chooses the selected file. A file name (or path) can also
+/// be typed directly.
+///
+/// Files can be filtered with glob patterns, and hidden files can be shown
+/// with a checkbox. In save mode, choosing an existing file asks for
+/// confirmation first.
+///
+/// In directory mode, only directories are shown, and `<Enter>` on the list
+/// still opens them. The `Choose` button (or `<Enter>` in the name field)
+/// chooses the directory typed in the name field, or else the selected
+/// directory, or else the one currently shown.
+///
+/// Once a path is chosen, the dialog removes its layer and calls the
+/// `on_choose` callback.
+///
+/// # Examples
+///
+/// ```no_run
+/// # extern crate cursive;
+/// # use cursive::Cursive;
+/// # use cursive::views::FileDialog;
+/// # fn main() {
+/// let mut siv = Cursive::new();
+/// siv.add_layer(FileDialog::open()
+///     .extension("toml")
+///     .on_choose(|s, path| {
+///         s.add_layer(cursive::views::Dialog::info(format!("{:?}",
+///                                                          path)));
+///     }));
+/// # }
+/// ```
+pub struct FileDialog {
+    dialog: Dialog,
+    id: String,
+    mode: Mode,
+
+    dir: PathBuf,
+    filters: Vec<String>,
+    show_hidden: bool,
+    // `true` if the list must be read again before the next layout.
+    dirty: bool,
+
+    location: IdView<TextView>,
+    list: IdView<SelectView<PathBuf>>,
+    name: IdView<EditView>,
+    status: IdView<TextView>,
+
+    on_choose: Option<Rc<Fn(&mut Cursive, PathBuf)>>,
+}
+
+impl FileDialog {
+    /// Creates a dialog to choose an existing file.
+    pub fn open() -> Self {
+        FileDialog::new(Mode::Open)
+    }
+
+    /// Creates a dialog to choose a file to write to.
+    pub fn save() -> Self {
+        FileDialog::new(Mode::Save)
+    }
+
+    /// Creates a dialog to choose an existing directory.
+    pub fn directory() -> Self {
+        FileDialog::new(Mode::Directory)
+    }
+
+    fn new(mode: Mode) -> Self {
+        let id = format!("file_dialog_{}",
+                         NEXT_ID.fetch_add(1, Ordering::Relaxed));
+
+        let location = IdView::new(format!("{}/location", id),
+                                   TextView::empty());
+        let status = IdView::new(format!("{}/status", id), TextView::empty());
+
+        let submit_id = id.clone();
+        let select_id = id.clone();
+        let select = SelectView::<PathBuf>::new()
+            .on_submit(move |s, path: &PathBuf| {
+                call_dialog(s, &submit_id, |d| d.choose(path.clone()))
+            })
+            .on_select(move |s, path| {
+                call_dialog(s, &select_id, |d| {
+                    d.select(path);
+                    None
+                })
+            });
+        let list = IdView::new(format!("{}/list", id), select);
+
+        let name_id = id.clone();
+        let name = IdView::new(format!("{}/name", id),
+                               EditView::new().on_submit(move |s, _| {
+                                   call_dialog(s, &name_id, |d| d.submit())
+                               }));
+
+        let hidden_id = id.clone();
+        let hidden = Checkbox::new().on_change(move |s, checked| {
+            call_dialog(s, &hidden_id, |d| {
+                d.set_show_hidden(checked);
+                None
+            })
+        });
+
+        let (title, label) = match mode {
+            Mode::Open => ("Open file", "Open"),
+            Mode::Save => ("Save file", "Save"),
+            Mode::Directory => ("Choose directory", "Choose"),
+        };
+        let button_id = id.clone();
+
+        let content = LinearLayout::vertical()
+            .child(location.clone())
+            .child(list.clone().fixed_height(12).min_width(40))
+            .child(LinearLayout::horizontal()
+                .child(TextView::new("Name: "))
+                .child(name.clone().full_width()))
+            .child(LinearLayout::horizontal()
+                .child(hidden)
+                .child(TextView::new(" Show hidden files")))
+            .child(status.clone());
+
+        FileDialog {
+            dialog: Dialog::around(content)
+                .title(title)
+                .button(label,
+                        move |s| call_dialog(s, &button_id, |d| d.submit()))
+                .dismiss_button("Cancel"),
+            id: id,
+            mode: mode,
+            dir: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            filters: Vec::new(),
+            show_hidden: false,
+            dirty: true,
+            location: location,
+            list: list,
+            name: name,
+            status: status,
+            on_choose: None,
+        }
+    }
+
+    /// Sets the directory to show.
+    pub fn set_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.dir = dir.into();
+        self.dirty = true;
+    }
+
+    /// Sets the directory to show.
+    ///
+    /// Chainable variant.
+    pub fn dir<P: Into<PathBuf>>(self, dir: P) -> Self {
+        self.with(|s| s.set_dir(dir))
+    }
+
+    /// Returns the directory currently shown.
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Only shows files matching the given glob pattern.
+    ///
+    /// `*` matches any sequence of characters and `?` any single character.
+    /// When several patterns are added, files matching any of them are
+    /// shown. Directories are always shown.
+    pub fn add_filter<S: Into<String>>(&mut self, pattern: S) {
+        self.filters.push(pattern.into());
+        self.dirty = true;
+    }
+
+    /// Only shows files matching the given glob pattern.
+    ///
+    /// Chainable variant.
+    pub fn filter<S: Into<String>>(self, pattern: S) -> Self {
+        self.with(|s| s.add_filter(pattern))
+    }
+
+    /// Only shows files with the given extension.
+    ///
+    /// Chainable variant.
+    pub fn extension(self, extension: &str) -> Self {
+        self.filter(format!("*.{}", extension))
+    }
+
+    /// Shows or hides hidden files (starting with a dot).
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        self.show_hidden = show_hidden;
+        self.dirty = true;
+    }
+
+    /// Sets the name initially suggested.
+    ///
+    /// Mostly useful in save mode.
+    pub fn set_file_name<S: Into<String>>(&mut self, name: S) {
+        self.name.get_mut().set_content(name);
+    }
+
+    /// Sets the name initially suggested.
+    ///
+    /// Chainable variant.
+    pub fn file_name<S: Into<String>>(self, name: S) -> Self {
+        self.with(|s| s.set_file_name(name))
+    }
+
+    /// Sets the title of the dialog.
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.dialog.set_title(title);
+
+        self
+    }
+
+    /// Sets the callback called with the chosen path.
+    pub fn set_on_choose<F>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, PathBuf) + 'static
+    {
+        self.on_choose = Some(Rc::new(cb));
+    }
+
+    /// Sets the callback called with the chosen path.
+    ///
+    /// Chainable variant.
+    pub fn on_choose<F>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, PathBuf) + 'static
+    {
+        self.with(|s| s.set_on_choose(cb))
+    }
+
+    fn is_visible(&self, path: &Path, is_dir: bool) -> bool {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => return false,
+        };
+
+        if !self.show_hidden && name.starts_with('.') {
+            return false;
+        }
+
+        if is_dir {
+            return true;
+        }
+
+        self.mode != Mode::Directory &&
+        (self.filters.is_empty() ||
+         self.filters.iter().any(|pattern| glob_match(pattern, &name)))
+    }
+
+    // Reads the current directory again.
+    fn refresh(&mut self) {
+        self.dirty = false;
+        self.location.get_mut().set_content(self.dir.to_string_lossy());
+        self.status.get_mut().set_content("");
+
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        match fs::read_dir(&self.dir) {
+            Ok(entries) => {
+                for entry in entries.filter_map(|entry| entry.ok()) {
+                    let path = entry.path();
+                    let is_dir = path.is_dir();
+                    if !self.is_visible(&path, is_dir) {
+                        continue;
+                    }
+                    if is_dir {
+                        dirs.push(path);
+                    } else {
+                        files.push(path);
+                    }
+                }
+            }
+            Err(err) => {
+                self.status
+                    .get_mut()
+                    .set_content(format!("Cannot read directory: {}", err));
+            }
+        }
+        dirs.sort();
+        files.sort();
+
+        let mut list = self.list.get_mut();
+        list.clear();
+        if let Some(parent) = self.dir.parent() {
+            list.add_item("../", parent.to_path_buf());
+        }
+        for dir in dirs {
+            let label = format!("{}/", file_name(&dir));
+            list.add_item(label, dir);
+        }
+        for file in files {
+            let label = file_name(&file);
+            list.add_item(label, file);
+        }
+    }
+
+    // Puts the name of the selected file in the name field.
+    fn select(&mut self, path: &Path) {
+        if !path.is_dir() {
+            self.name.get_mut().set_content(file_name(path));
+        }
+    }
+
+    // Chooses the file typed in the name field.
+    fn submit(&mut self) -> Option<Callback> {
+        if self.mode == Mode::Directory {
+            return self.chosen_dir().map(|dir| self.done(dir));
+        }
+
+        let name = self.name.get_mut().get_content();
+        if name.is_empty() {
+            let selected = self.list.get_mut().selected_id();
+            return selected.and_then(|_| {
+                let path = self.list.get_mut().selection();
+                self.choose((*path).clone())
+            });
+        }
+
+        let path = self.dir.join(&*name);
+        self.choose(path)
+    }
+
+    // Returns the directory typed in the name field, or else the selected
+    // one, or else the current one.
+    fn chosen_dir(&mut self) -> Option<PathBuf> {
+        let name = self.name.get_mut().get_content();
+        if !name.is_empty() {
+            let path = self.dir.join(&*name);
+            if !path.is_dir() {
+                self.status
+                    .get_mut()
+                    .set_content(format!("{} is not a directory",
+                                         path.display()));
+                return None;
+            }
+            return Some(path);
+        }
+
+        // `../` is selected first, and means the current directory here.
+        let list = self.list.get_mut();
+        if list.selected_id().is_some() {
+            let path = list.selection();
+            if path.parent() == Some(self.dir.as_path()) {
+                return Some((*path).clone());
+            }
+        }
+        Some(self.dir.clone())
+    }
+
+    // Opens a directory, or chooses a file.
+    fn choose(&mut self, path: PathBuf) -> Option<Callback> {
+        if path.is_dir() {
+            self.dir = path;
+            self.name.get_mut().set_content("");
+            self.refresh();
+            return None;
+        }
+
+        if self.mode == Mode::Open && !path.exists() {
+            self.status
+                .get_mut()
+                .set_content(format!("{} does not exist", path.display()));
+            return None;
+        }
+
+        let on_choose = self.on_choose.clone();
+        if self.mode == Mode::Save && path.exists() {
+            let message = format!("{} already exists.\nReplace it?",
+                                  file_name(&path));
+            return Some(Callback::from_fn(move |s| {
+                let on_choose = on_choose.clone();
+                let path = path.clone();
+                s.add_layer(Dialog::text(message.clone())
+                    .title("Confirm")
+                    .button("Replace", move |s| {
+                        // Remove the confirmation and the file dialog.
+                        s.pop_layer();
+                        s.pop_layer();
+                        if let Some(ref cb) = on_choose {
+                            cb(s, path.clone());
+                        }
+                    })
+                    .dismiss_button("Cancel"));
+            }));
+        }
+
+        Some(self.done(path))
+    }
+
+    // Removes the dialog and calls `on_choose` with `path`.
+    fn done(&self, path: PathBuf) -> Callback {
+        let on_choose = self.on_choose.clone();
+        Callback::from_fn(move |s| {
+            s.pop_layer();
+            if let Some(ref cb) = on_choose {
+                cb(s, path.clone());
+            }
+        })
+    }
+}
+
+impl ViewWrapper for FileDialog {
+    wrap_impl!(self.dialog: Dialog);
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        if self.dirty {
+            self.refresh();
+        }
+        child_layout(&mut self.dialog, size);
+    }
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        if self.dirty {
+            self.refresh();
+        }
+        child_required_size(&mut self.dialog, req)
+    }
+
+    fn wrap_call_on_any<'a>(&mut self, selector: &Selector,
+                            mut callback: Box<FnMut(&mut Any) + 'a>) {
+        match selector {
+            &Selector::Id(id) if id == self.id => callback(self),
+            s => self.dialog.call_on_any(s, callback),
+        }
+    }
+}
+
+// Runs `f` on the file dialog with the given id, and runs the callback it
+// returns, if any.
+fn call_dialog<F>(s: &mut Cursive, id: &str, f: F)
+    where F: FnOnce(&mut FileDialog) -> Option<Callback>
+{
+    if let Some(Some(cb)) = s.call_on_id(id, f) {
+        cb(s);
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+// Matches `name` against a pattern with `*` and `?` wildcards.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*`, and of the name when we reached it.
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // Let the last `*` eat one more character.
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("*.rs", "lib.rs"));
+        assert!(glob_match("*.rs", ".rs"));
+        assert!(!glob_match("*.rs", "lib.rs.bak"));
+        assert!(glob_match("lib?.*", "lib2.tar.gz"));
+        assert!(glob_match("*a*b", "xaxxab"));
+        assert!(!glob_match("a?", "a"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn visible() {
+        let dialog = FileDialog::open().extension("rs");
+        assert!(dialog.is_visible(Path::new("src/lib.rs"), false));
+        assert!(!dialog.is_visible(Path::new("Cargo.toml"), false));
+        assert!(dialog.is_visible(Path::new("src"), true));
+        assert!(!dialog.is_visible(Path::new(".git"), true));
+
+        let dialog = FileDialog::directory();
+        assert!(!dialog.is_visible(Path::new("Cargo.toml"), false));
+        assert!(dialog.is_visible(Path::new("src"), true));
+    }
+
+    #[test]
+    fn choose_directory() {
+        let root = env::temp_dir().join("cursive_file_dialog_test");
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::File::create(root.join("file")).unwrap();
+
+        let mut dialog = FileDialog::directory().dir(root.clone());
+        dialog.refresh();
+        // `../` and `a/`: files are not listed.
+        assert_eq!(2, dialog.list.get_mut().len());
+        assert_eq!(Some(root.clone()), dialog.chosen_dir());
+
+        dialog.list.get_mut().set_selection(1);
+        assert_eq!(Some(root.join("a")), dialog.chosen_dir());
+
+        dialog.set_file_name("file");
+        assert_eq!(None, dialog.chosen_dir());
+        dialog.set_file_name("a/b");
+        assert_eq!(Some(root.join("a/b")), dialog.chosen_dir());
+
+        // The list still opens directories.
+        assert!(dialog.choose(root.join("a")).is_none());
+        assert_eq!(root.join("a"), dialog.get_dir());
+        assert!(dialog.submit().is_some());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,406 @@
+use Printer;
+use With;
+use align::HAlign;
+use direction::{Direction, Orientation, Relative};
+use event::{Event, EventResult, Key};
+use std::any::Any;
+use std::cmp::{max, min};
+use vec::Vec2;
+use view::{Selector, View, child_draw, child_layout,
+           child_required_size};
+
+/// Arranges its children from left to right, wrapping to a new row when
+/// the width runs out.
+///
+/// Unlike a horizontal [`LinearLayout`], children that don't fit on the
+/// current row are moved to the next one instead of being squished.
+///
+/// [`LinearLayout`]: struct.LinearLayout.html
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::{Button, FlowLayout};
+/// # use cursive::align::HAlign;
+/// let toolbar = FlowLayout::new()
+///     .child(Button::new("New", |_| ()))
+///     .child(Button::new("Open", |_| ()))
+///     .child(Button::new("Save", |_| ()))
+///     .gap(2)
+///     .h_align(HAlign::Center);
+/// ```
+pub struct FlowLayout {
+    children: Vec<Child>,
+    gap: usize,
+    row_gap: usize,
+    align: HAlign,
+    focus: usize,
+
+    /// Placement computed during the last layout.
+    flow: Flow,
+}
+
+struct Child {
+    view: Box<View>,
+    size: Vec2,
+}
+
+/// Placement of items in rows of limited width.
+///
+/// Shared with other views laying out a row of items, like `Dialog` buttons.
+pub struct Flow {
+    /// Size of each item.
+    pub sizes: Vec<Vec2>,
+    /// Offset of each item, relative to the top-left corner.
+    pub offsets: Vec<Vec2>,
+    /// Row of each item.
+    pub rows: Vec<usize>,
+    /// Size of the bounding box of all rows.
+    pub size: Vec2,
+}
+
+impl Flow {
+    /// Creates an empty placement.
+    pub fn empty() -> Self {
+        Flow {
+            sizes: Vec::new(),
+            offsets: Vec::new(),
+            rows: Vec::new(),
+            size: Vec2::zero(),
+        }
+    }
+
+    /// Places items of the given sizes in rows no wider than `width`.
+    ///
+    /// Items are separated by `gap` columns, and rows by `row_gap` lines.
+    /// Each row is aligned inside `width` according to `align`.
+    ///
+    /// Items wider than `width` get a row of their own.
+    pub fn place(sizes: Vec<Vec2>, width: usize, gap: usize, row_gap: usize,
+                 align: &HAlign)
+                 -> Self {
+        // (first item, width, height) of each row.
+        let mut row_specs: Vec<(usize, usize, usize)> = Vec::new();
+        let mut rows = Vec::with_capacity(sizes.len());
+
+        for (i, size) in sizes.iter().enumerate() {
+            let new_row = match row_specs.last() {
+                None => true,
+                Some(&(_, w, _)) => w + gap + size.x > width,
+            };
+
+            if new_row {
+                row_specs.push((i, size.x, size.y));
+            } else {
+                let row = row_specs.last_mut().unwrap();
+                row.1 += gap + size.x;
+                row.2 = max(row.2, size.y);
+            }
+            rows.push(row_specs.len() - 1);
+        }
+
+        let mut offsets = Vec::with_capacity(sizes.len());
+        let mut y = 0;
+        for (r, &(first, row_width, row_height)) in row_specs.iter()
+            .enumerate() {
+            let mut x = align.get_offset(row_width, width);
+            for i in (first..sizes.len()).take_while(|&i| rows[i] == r) {
+                offsets.push(Vec2::new(x, y));
+                x += sizes[i].x + gap;
+            }
+            y += row_height + row_gap;
+        }
+
+        let size = Vec2::new(row_specs.iter()
+                                 .map(|&(_, w, _)| w)
+                                 .max()
+                                 .unwrap_or(0),
+                             y.saturating_sub(row_gap));
+
+        Flow {
+            sizes: sizes,
+            offsets: offsets,
+            rows: rows,
+            size: size,
+        }
+    }
+
+    /// Returns the items on the row above (or below) the item `i`.
+    ///
+    /// They are sorted by horizontal distance to `i`, closest first.
+    pub fn row_neighbours(&self, i: usize, up: bool) -> Vec<usize> {
+        let row = self.rows[i];
+        if up && row == 0 {
+            return Vec::new();
+        }
+        let target = if up { row - 1 } else { row + 1 };
+
+        let center = |j: usize| 2 * self.offsets[j].x + self.sizes[j].x;
+        let origin = center(i);
+
+        let mut result: Vec<usize> = (0..self.rows.len())
+            .filter(|&j| self.rows[j] == target)
+            .collect();
+        result.sort_by_key(|&j| {
+            let c = center(j);
+            max(c, origin) - min(c, origin)
+        });
+        result
+    }
+}
+
+new_default!(FlowLayout);
+
+impl FlowLayout {
+    /// Creates a new, empty `FlowLayout`.
+    pub fn new() -> Self {
+        FlowLayout {
+            children: Vec::new(),
+            gap: 1,
+            row_gap: 0,
+            align: HAlign::Left,
+            focus: 0,
+            flow: Flow::empty(),
+        }
+    }
+
+    /// Adds a child to the layout.
+    pub fn add_child<V: View + 'static>(&mut self, view: V) {
+        self.children.push(Child {
+            view: Box::new(view),
+            size: Vec2::zero(),
+        });
+    }
+
+    /// Adds a child to the layout.
+    ///
+    /// Chainable variant.
+    pub fn child<V: View + 'static>(self, view: V) -> Self {
+        self.with(|s| s.add_child(view))
+    }
+
+    /// Returns the number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if this layout has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Sets the number of blank columns between two children.
+    ///
+    /// Defaults to 1.
+    pub fn set_gap(&mut self, gap: usize) {
+        self.gap = gap;
+    }
+
+    /// Sets the number of blank columns between two children.
+    ///
+    /// Chainable variant.
+    pub fn gap(self, gap: usize) -> Self {
+        self.with(|s| s.set_gap(gap))
+    }
+
+    /// Sets the number of blank lines between two rows.
+    ///
+    /// Defaults to 0.
+    pub fn set_row_gap(&mut self, row_gap: usize) {
+        self.row_gap = row_gap;
+    }
+
+    /// Sets the number of blank lines between two rows.
+    ///
+    /// Chainable variant.
+    pub fn row_gap(self, row_gap: usize) -> Self {
+        self.with(|s| s.set_row_gap(row_gap))
+    }
+
+    /// Sets the horizontal alignment of each row.
+    pub fn h_align(mut self, h: HAlign) -> Self {
+        self.align = h;
+
+        self
+    }
+
+    // Asks every child for its size, and place them in rows.
+    fn place(&mut self, req: Vec2) -> Flow {
+        let sizes = self.children
+            .iter_mut()
+            .map(|c| Vec2::min(child_required_size(&mut *c.view, req), req))
+            .collect();
+        Flow::place(sizes, req.x, self.gap, self.row_gap, &self.align)
+    }
+
+    fn focus_first<I>(&mut self, candidates: I, source: Direction) -> bool
+        where I: IntoIterator<Item = usize>
+    {
+        for i in candidates {
+            if self.children[i].view.take_focus(source) {
+                self.focus = i;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn move_focus<I>(&mut self, candidates: I, source: Direction)
+                     -> EventResult
+        where I: IntoIterator<Item = usize>
+    {
+        if self.focus_first(candidates, source) {
+            EventResult::Consumed(None)
+        } else {
+            EventResult::Ignored
+        }
+    }
+
+    fn move_focus_row(&mut self, up: bool) -> EventResult {
+        // No layout yet, no row.
+        if self.flow.rows.len() != self.children.len() {
+            return EventResult::Ignored;
+        }
+        let candidates = self.flow.row_neighbours(self.focus, up);
+        let source = if up { Direction::down() } else { Direction::up() };
+        self.move_focus(candidates, source)
+    }
+}
+
+impl View for FlowLayout {
+    fn draw(&self, printer: &Printer) {
+        for (i, (child, offset)) in self.children
+            .iter()
+            .zip(self.flow.offsets.iter())
+            .enumerate() {
+            child_draw(&*child.view,
+                       &printer.sub_printer(*offset,
+                                            child.size,
+                                            i == self.focus));
+        }
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        self.place(req).size
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.flow = self.place(size);
+
+        for (child, &child_size) in self.children
+            .iter_mut()
+            .zip(self.flow.sizes.iter()) {
+            child.size = child_size;
+            child_layout(&mut *child.view, child_size);
+        }
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if self.children.is_empty() {
+            return EventResult::Ignored;
+        }
+
+        match self.children[self.focus].view.on_event(event.clone()) {
+            EventResult::Ignored => (),
+            res => return res,
+        }
+
+        let focus = self.focus;
+        let len = self.children.len();
+        match event {
+            Event::Key(Key::Tab) |
+            Event::Key(Key::Right) => {
+                self.move_focus(focus + 1..len, Direction::left())
+            }
+            Event::Shift(Key::Tab) |
+            Event::Key(Key::Left) => {
+                self.move_focus((0..focus).rev(), Direction::right())
+            }
+            Event::Key(Key::Up) => self.move_focus_row(true),
+            Event::Key(Key::Down) => self.move_focus_row(false),
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, source: Direction) -> bool {
+        let len = self.children.len();
+        let from_back = source.relative(Orientation::Horizontal) ==
+                        Some(Relative::Back) ||
+                        source.relative(Orientation::Vertical) ==
+                        Some(Relative::Back);
+
+        if from_back {
+            self.focus_first((0..len).rev(), source)
+        } else {
+            self.focus_first(0..len, source)
+        }
+    }
+
+    fn call_on_any<'a>(&mut self, selector: &Selector,
+                       mut callback: Box<FnMut(&mut Any) + 'a>) {
+        for child in &mut self.children {
+            child.view.call_on_any(selector, Box::new(|any| callback(any)));
+        }
+    }
+
+    fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if child.view.focus_view(selector).is_ok() {
+                self.focus = i;
+                return Ok(());
+            }
+        }
+
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use align::HAlign;
+    use vec::Vec2;
+    use view::View;
+    use views::{Button, DummyView};
+
+    #[test]
+    fn place() {
+        let sizes = vec![Vec2::new(4, 1), Vec2::new(4, 1), Vec2::new(4, 2)];
+
+        let flow = Flow::place(sizes.clone(), 20, 1, 0, &HAlign::Left);
+        assert_eq!(Vec2::new(14, 2), flow.size);
+        assert_eq!(vec![0, 0, 0], flow.rows);
+
+        let flow = Flow::place(sizes.clone(), 10, 1, 1, &HAlign::Left);
+        assert_eq!(Vec2::new(9, 4), flow.size);
+        assert_eq!(vec![0, 0, 1], flow.rows);
+        assert_eq!(Vec2::new(0, 2), flow.offsets[2]);
+
+        let flow = Flow::place(sizes, 10, 1, 0, &HAlign::Right);
+        assert_eq!(Vec2::new(1, 0), flow.offsets[0]);
+        assert_eq!(Vec2::new(6, 1), flow.offsets[2]);
+    }
+
+    #[test]
+    fn height_from_width() {
+        let mut layout = FlowLayout::new()
+            .child(Button::new("Ok", |_| ()))
+            .child(Button::new("Cancel", |_| ()))
+            .child(DummyView);
+
+        assert_eq!(Vec2::new(15, 1), layout.required_size(Vec2::new(20, 5)));
+        assert_eq!(Vec2::new(13, 2), layout.required_size(Vec2::new(13, 5)));
+        assert_eq!(Vec2::new(8, 3), layout.required_size(Vec2::new(8, 5)));
+    }
+
+    #[test]
+    fn row_neighbours() {
+        let sizes = vec![Vec2::new(2, 1), Vec2::new(2, 1), Vec2::new(2, 1),
+                         Vec2::new(2, 1)];
+        let flow = Flow::place(sizes, 7, 1, 0, &HAlign::Left);
+        assert_eq!(vec![0, 0, 1, 1], flow.rows);
+        assert_eq!(vec![3, 2], flow.row_neighbours(1, false));
+        assert_eq!(vec![0, 1], flow.row_neighbours(2, true));
+        assert!(flow.row_neighbours(0, true).is_empty());
+    }
+}
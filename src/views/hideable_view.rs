@@ -0,0 +1,148 @@
+use Printer;
+use With;
+use direction::Direction;
+use event::{Event, EventResult};
+use vec::Vec2;
+use view::{View, ViewWrapper, child_draw, child_layout,
+           child_required_size};
+
+/// Wrapper around a view that can be hidden at runtime.
+///
+/// When hidden, the wrapped view takes no space, doesn't take focus and
+/// isn't drawn. It can still be found with `call_on_any`, so it can be
+/// shown again later.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::{HideableView, TextView};
+/// let mut view = HideableView::new(TextView::new("Advanced options"));
+/// view.hide();
+/// assert!(!view.is_visible());
+/// ```
+pub struct HideableView<V: View> {
+    view: V,
+    visible: bool,
+
+    /// `true` if the visibility changed since the last layout.
+    invalidated: bool,
+}
+
+impl<V: View> HideableView<V> {
+    /// Wraps `view` in a new, visible, `HideableView`.
+    pub fn new(view: V) -> Self {
+        HideableView {
+            view: view,
+            visible: true,
+            invalidated: true,
+        }
+    }
+
+    /// Sets the visibility of the wrapped view.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.invalidated |= self.visible != visible;
+        self.visible = visible;
+    }
+
+    /// Hides the wrapped view.
+    pub fn hide(&mut self) {
+        self.set_visible(false);
+    }
+
+    /// Shows the wrapped view.
+    pub fn unhide(&mut self) {
+        self.set_visible(true);
+    }
+
+    /// Hides the wrapped view.
+    ///
+    /// Chainable variant.
+    pub fn hidden(self) -> Self {
+        self.with(Self::hide)
+    }
+
+    /// Returns `true` if the wrapped view is visible.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Gets access to the inner view.
+    pub fn get_inner(&self) -> &V {
+        &self.view
+    }
+
+    /// Gets mutable access to the inner view.
+    pub fn get_inner_mut(&mut self) -> &mut V {
+        &mut self.view
+    }
+}
+
+impl<V: View> ViewWrapper for HideableView<V> {
+    wrap_impl!(self.view: V);
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        if self.visible {
+            child_required_size(&mut self.view, req)
+        } else {
+            Vec2::zero()
+        }
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.invalidated = false;
+        if self.visible {
+            child_layout(&mut self.view, size);
+        }
+    }
+
+    fn wrap_draw(&self, printer: &Printer) {
+        if self.visible {
+            child_draw(&self.view, printer);
+        }
+    }
+
+    fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        if self.visible {
+            self.view.on_event(event)
+        } else {
+            EventResult::Ignored
+        }
+    }
+
+    fn wrap_take_focus(&mut self, source: Direction) -> bool {
+        self.visible && self.view.take_focus(source)
+    }
+
+    fn wrap_needs_relayout(&self) -> bool {
+        self.invalidated || (self.visible && self.view.needs_relayout())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use direction::Direction;
+    use views::{Button, DummyView};
+
+    #[test]
+    fn hidden_size() {
+        let mut view = HideableView::new(DummyView);
+        assert_eq!(Vec2::new(1, 1), view.required_size(Vec2::new(5, 5)));
+
+        view.hide();
+        assert_eq!(Vec2::zero(), view.required_size(Vec2::new(5, 5)));
+        assert!(view.needs_relayout());
+
+        view.layout(Vec2::zero());
+        assert!(!view.needs_relayout());
+    }
+
+    #[test]
+    fn hidden_focus() {
+        let mut view = HideableView::new(Button::new("Ok", |_| ())).hidden();
+        assert!(!view.take_focus(Direction::none()));
+
+        view.unhide();
+        assert!(view.take_focus(Direction::none()));
+    }
+}
@@ -6,6 +6,8 @@ use std::rc::Rc;
 use view::{Selector, View, ViewWrapper};
 
 /// Wrapper around a view to provide interior mutability.
+///
+/// Cloning an `IdView` returns another handle to the same inner view.
 pub struct IdView<V: View> {
     view: Rc<RefCell<V>>,
     id: String,
@@ -41,6 +43,15 @@ impl<V: View> IdView<V> {
     }
 }
 
+impl<V: View> Clone for IdView<V> {
+    fn clone(&self) -> Self {
+        IdView {
+            view: self.view.clone(),
+            id: self.id.clone(),
+        }
+    }
+}
+
 impl<T: View + 'static> ViewWrapper for IdView<T> {
     type V = T;
 
@@ -1,5 +1,5 @@
 use Printer;
-use view::{View, ViewWrapper};
+use view::{View, ViewWrapper, child_draw};
 
 /// Wrapper view that fills the background.
 ///
@@ -24,6 +24,6 @@ impl<T: View> ViewWrapper for Layer<T> {
         for y in 0..printer.size.y {
             printer.print_hline((0, y), printer.size.x, " ");
         }
-        self.view.draw(printer);
+        child_draw(&self.view, printer);
     }
 }
@@ -9,6 +9,7 @@ use std::cmp::min;
 use vec::Vec2;
 use view::{Selector, SizeCache};
 use view::View;
+use view::{child_draw, child_layout, child_required_size};
 
 /// Arranges its children linearly according to its orientation.
 pub struct LinearLayout {
@@ -28,7 +29,7 @@ struct Child {
 impl Child {
     // Compute and caches the required size.
     fn required_size(&mut self, req: Vec2) -> Vec2 {
-        self.size = self.view.required_size(req);
+        self.size = child_required_size(&mut *self.view, req);
         self.size
     }
 
@@ -183,7 +184,7 @@ impl View for LinearLayout {
         for (i, child) in self.children.iter().enumerate() {
             let printer =
                 &printer.sub_printer(offset, child.size, i == self.focus);
-            child.view.draw(printer);
+            child_draw(&*child.view, printer);
 
             // On the axis given by the orientation,
             // add the child size to the offset.
@@ -211,7 +212,8 @@ impl View for LinearLayout {
         for child in &mut self.children {
             // Every item has the same size orthogonal to the layout
             child.size.set_axis_from(o.swap(), &size);
-            child.view.layout(size.with_axis_from(o, &child.size));
+            child_layout(&mut *child.view,
+                         size.with_axis_from(o, &child.size));
         }
     }
 
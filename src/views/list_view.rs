@@ -12,6 +12,7 @@ use vec::Vec2;
 use view::ScrollBase;
 use view::Selector;
 use view::View;
+use view::{child_draw, child_layout, child_required_size};
 
 /// Represents a child from a [`ListView`].
 ///
@@ -223,7 +224,8 @@ impl View for ListView {
         self.scrollbase.draw(printer, |printer, i| match self.children[i] {
             ListChild::Row(ref label, ref view) => {
                 printer.print((0, 0), label);
-                view.draw(&printer.offset((offset, 0), i == self.focus));
+                child_draw(&**view,
+                           &printer.offset((offset, 0), i == self.focus));
             }
             ListChild::Delimiter => (),
         });
@@ -241,7 +243,7 @@ impl View for ListView {
         let view_size = self.children
             .iter_mut()
             .filter_map(ListChild::view)
-            .map(|v| v.required_size(req).x)
+            .map(|v| child_required_size(&mut **v, req).x)
             .max()
             .unwrap_or(0);
 
@@ -276,7 +278,7 @@ impl View for ListView {
         // println_stderr!("Available: {}", available);
 
         for child in self.children.iter_mut().filter_map(ListChild::view) {
-            child.layout(Vec2::new(available, 1));
+            child_layout(&mut **child, Vec2::new(available, 1));
         }
     }
 
@@ -0,0 +1,174 @@
+use Printer;
+use With;
+use direction::Direction;
+use event::{Event, EventResult, Key};
+use utils::StyledString;
+use utils::markdown::{self, Block};
+use vec::Vec2;
+use view::{ScrollBase, View};
+
+/// A view showing a markdown document.
+///
+/// The document is wrapped to the available width. Wrapped list items and
+/// quotes keep their indentation.
+///
+/// See [`utils::markdown`] for the supported syntax.
+///
+/// [`utils::markdown`]: ../utils/markdown/index.html
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::MarkdownView;
+/// let notes = MarkdownView::new("# Release notes\n\n\
+///                                * Added a **markdown** view.");
+/// ```
+pub struct MarkdownView {
+    content: String,
+    blocks: Vec<Block>,
+
+    // Rendered rows, for the size in `last_size`.
+    rows: Vec<StyledString>,
+    last_size: Option<Vec2>,
+
+    scrollbase: ScrollBase,
+}
+
+impl MarkdownView {
+    /// Creates a new view with the given markdown content.
+    pub fn new<S: Into<String>>(content: S) -> Self {
+        let content = content.into();
+        MarkdownView {
+            blocks: markdown::blocks(&content),
+            content: content,
+            rows: Vec::new(),
+            last_size: None,
+            scrollbase: ScrollBase::new(),
+        }
+    }
+
+    /// Replaces the markdown content of this view.
+    pub fn set_content<S: Into<String>>(&mut self, content: S) {
+        self.content = content.into();
+        self.blocks = markdown::blocks(&self.content);
+        self.last_size = None;
+    }
+
+    /// Replaces the markdown content of this view.
+    ///
+    /// Chainable variant.
+    pub fn content<S: Into<String>>(self, content: S) -> Self {
+        self.with(|s| s.set_content(content))
+    }
+
+    /// Returns the markdown content of this view.
+    pub fn get_content(&self) -> &str {
+        &self.content
+    }
+
+    // Renders the document for the given width.
+    fn render(&self, width: usize) -> Vec<StyledString> {
+        let mut rows = Vec::new();
+        for block in &self.blocks {
+            if block.gap {
+                rows.push(StyledString::new());
+            }
+            rows.extend(block.rows(width));
+        }
+        rows
+    }
+
+    fn compute_rows(&mut self, size: Vec2) {
+        // Leave room for the scrollbar when we need one.
+        let mut width = size.x;
+        let mut rows = self.render(width);
+        if rows.len() > size.y && size.x > 2 {
+            width = size.x - 2;
+            rows = self.render(width);
+        }
+
+        self.rows = rows;
+        self.last_size = Some(size);
+    }
+}
+
+impl View for MarkdownView {
+    fn draw(&self, printer: &Printer) {
+        self.scrollbase.draw(printer, |printer, i| {
+            printer.print_styled((0, 0), &self.rows[i]);
+        });
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if !self.scrollbase.scrollable() {
+            return EventResult::Ignored;
+        }
+
+        match event {
+            Event::Key(Key::Home) => self.scrollbase.scroll_top(),
+            Event::Key(Key::End) => self.scrollbase.scroll_bottom(),
+            Event::Key(Key::Up) if self.scrollbase.can_scroll_up() => {
+                self.scrollbase.scroll_up(1)
+            }
+            Event::Key(Key::Down) if self.scrollbase
+                .can_scroll_down() => self.scrollbase.scroll_down(1),
+            Event::Key(Key::PageDown) => self.scrollbase.scroll_down(10),
+            Event::Key(Key::PageUp) => self.scrollbase.scroll_up(10),
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn needs_relayout(&self) -> bool {
+        self.last_size.is_none()
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        self.compute_rows(req);
+
+        let scrolling = self.rows.len() > req.y;
+        let w = self.rows
+            .iter()
+            .map(|row| row.width())
+            .max()
+            .unwrap_or(0);
+        let w = if scrolling { w + 2 } else { w };
+        let h = if scrolling { req.y } else { self.rows.len() };
+
+        Vec2::new(w, h)
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        self.scrollbase.scrollable()
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        if self.last_size != Some(size) {
+            self.compute_rows(size);
+        }
+        self.scrollbase.set_heights(size.y, self.rows.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use view::View;
+
+    #[test]
+    fn wrap_and_scroll() {
+        let mut view = MarkdownView::new("# T\n\n- one two\n- three");
+
+        assert_eq!(Vec2::new(9, 4), view.required_size(Vec2::new(20, 10)));
+
+        // Narrow: the first item wraps with a hanging indent.
+        view.layout(Vec2::new(7, 10));
+        let rows: Vec<_> = view.rows.iter().map(|row| row.source()).collect();
+        assert_eq!(vec!["T", "", "• one", "  two", "• three"], rows);
+
+        // Short: we need a scrollbar.
+        view.layout(Vec2::new(7, 2));
+        assert!(view.scrollbase.scrollable());
+    }
+}
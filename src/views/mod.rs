@@ -39,50 +39,68 @@ mod box_view;
 mod button;
 mod canvas;
 mod checkbox;
+mod debug_view;
 mod dialog;
 mod dummy;
 mod edit_view;
+mod file_dialog;
+mod flow_layout;
+mod hideable_view;
 mod id_view;
 mod key_event_view;
 mod layer;
 mod linear_layout;
 mod list_view;
+mod markdown_view;
 mod menubar;
 mod menu_popup;
 mod panel;
 mod progress_bar;
 mod radio;
+mod responsive_view;
 mod select_view;
 mod slider_view;
 mod shadow_view;
 mod sized_view;
+mod split_view;
 mod stack_view;
+mod table_view;
 mod text_area;
 mod text_view;
 mod tracked_view;
+mod tree_view;
 
 pub use self::box_view::BoxView;
 pub use self::button::Button;
 pub use self::canvas::Canvas;
 pub use self::checkbox::Checkbox;
+pub use self::debug_view::DebugView;
 pub use self::dialog::Dialog;
 pub use self::dummy::DummyView;
 pub use self::edit_view::EditView;
+pub use self::file_dialog::FileDialog;
+pub use self::flow_layout::FlowLayout;
+pub use self::hideable_view::HideableView;
 pub use self::id_view::{IdView, ViewRef};
 pub use self::key_event_view::KeyEventView;
 pub use self::layer::Layer;
 pub use self::linear_layout::LinearLayout;
 pub use self::list_view::{ListChild, ListView};
+pub use self::markdown_view::MarkdownView;
 pub use self::menu_popup::MenuPopup;
 pub use self::menubar::Menubar;
 pub use self::panel::Panel;
 pub use self::progress_bar::{Counter, ProgressBar};
 pub use self::radio::{RadioGroup, RadioButton};
+pub use self::responsive_view::ResponsiveView;
 pub use self::select_view::SelectView;
 pub use self::shadow_view::ShadowView;
 pub use self::sized_view::SizedView;
 pub use self::slider_view::SliderView;
+pub use self::split_view::{SplitPosition, SplitView};
 pub use self::stack_view::StackView;
+pub use self::table_view::{TableColumn, TableView};
 pub use self::text_area::TextArea;
 pub use self::text_view::TextView;
 pub use self::tracked_view::TrackedView;
+pub use self::tree_view::TreeView;
@@ -1,6 +1,7 @@
 use Printer;
 use vec::Vec2;
-use view::{View, ViewWrapper};
+use view::{View, ViewWrapper, child_draw, child_layout,
+           child_required_size};
 
 /// Draws a border around a wrapped view.
 pub struct Panel<V: View> {
@@ -26,21 +27,21 @@ impl<V: View> ViewWrapper for Panel<V> {
             Vec2::zero()
         };
 
-        self.view.required_size(req) + (2, 2)
+        child_required_size(&mut self.view, req) + (2, 2)
     }
 
     fn wrap_draw(&self, printer: &Printer) {
         printer.print_box((0, 0), printer.size, true);
-        self.view.draw(&printer.sub_printer((1, 1),
-                                            printer.size - (2, 2),
-                                            true));
+        child_draw(&self.view,
+                   &printer.sub_printer((1, 1), printer.size - (2, 2), true));
     }
 
     fn wrap_layout(&mut self, size: Vec2) {
-        self.view.layout(if Vec2::new(2, 2).fits_in(size) {
-                             size - (2, 2)
-                         } else {
-                             size
-                         });
+        let size = if Vec2::new(2, 2).fits_in(size) {
+            size - (2, 2)
+        } else {
+            size
+        };
+        child_layout(&mut self.view, size);
     }
 }
@@ -0,0 +1,188 @@
+use Printer;
+use With;
+use direction::Direction;
+use event::{Event, EventResult};
+use std::any::Any;
+use vec::Vec2;
+use view::{Selector, View, child_draw, child_layout,
+           child_required_size};
+
+/// Holds several alternative layouts, and shows one depending on its size.
+///
+/// Each layout is registered with a minimum size. On each call to
+/// `required_size` or `layout`, the first layout (in insertion order) whose
+/// minimum size fits in the available space is used. If none fits, the last
+/// one is used.
+///
+/// To keep the same child views (and their state) in every layout, wrap them
+/// in an [`IdView`] and give a clone of it to each layout: clones of an
+/// `IdView` share the same inner view.
+///
+/// [`IdView`]: struct.IdView.html
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::{LinearLayout, ResponsiveView, TextView};
+/// # use cursive::traits::*;
+/// let left = TextView::new("Left").with_id("left");
+/// let right = TextView::new("Right").with_id("right");
+///
+/// let view = ResponsiveView::new()
+///     .breakpoint((80, 0), LinearLayout::horizontal()
+///         .child(left.clone())
+///         .child(right.clone()))
+///     .breakpoint((0, 0), LinearLayout::vertical()
+///         .child(left)
+///         .child(right));
+/// ```
+pub struct ResponsiveView {
+    layouts: Vec<Breakpoint>,
+    active: usize,
+}
+
+struct Breakpoint {
+    min_size: Vec2,
+    view: Box<View>,
+}
+
+new_default!(ResponsiveView);
+
+impl ResponsiveView {
+    /// Creates a new `ResponsiveView` with no layout.
+    pub fn new() -> Self {
+        ResponsiveView {
+            layouts: Vec::new(),
+            active: 0,
+        }
+    }
+
+    /// Adds a layout to use when at least `min_size` is available.
+    pub fn add_breakpoint<S, V>(&mut self, min_size: S, view: V)
+        where S: Into<Vec2>,
+              V: View + 'static
+    {
+        self.layouts.push(Breakpoint {
+            min_size: min_size.into(),
+            view: Box::new(view),
+        });
+    }
+
+    /// Adds a layout to use when at least `min_size` is available.
+    ///
+    /// Chainable variant.
+    pub fn breakpoint<S, V>(self, min_size: S, view: V) -> Self
+        where S: Into<Vec2>,
+              V: View + 'static
+    {
+        self.with(|s| s.add_breakpoint(min_size, view))
+    }
+
+    /// Returns the index of the layout currently in use.
+    ///
+    /// Returns `None` if no layout was added.
+    pub fn active_layout(&self) -> Option<usize> {
+        if self.layouts.is_empty() {
+            None
+        } else {
+            Some(self.active)
+        }
+    }
+
+    // Finds the layout to use for the given size.
+    fn pick(&self, size: Vec2) -> usize {
+        self.layouts
+            .iter()
+            .position(|layout| layout.min_size.fits_in(size))
+            .unwrap_or_else(|| self.layouts.len().saturating_sub(1))
+    }
+}
+
+impl View for ResponsiveView {
+    fn draw(&self, printer: &Printer) {
+        if let Some(layout) = self.layouts.get(self.active) {
+            child_draw(&*layout.view, printer);
+        }
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        let i = self.pick(req);
+        match self.layouts.get_mut(i) {
+            Some(layout) => child_required_size(&mut *layout.view, req),
+            None => Vec2::new(1, 1),
+        }
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        let i = self.pick(size);
+        let switched = i != self.active;
+        self.active = i;
+
+        if let Some(layout) = self.layouts.get_mut(i) {
+            child_layout(&mut *layout.view, size);
+
+            // The new layout doesn't know where the focus was,
+            // so give it a chance to find something to focus.
+            if switched {
+                layout.view.take_focus(Direction::none());
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match self.layouts.get_mut(self.active) {
+            Some(layout) => layout.view.on_event(event),
+            None => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, source: Direction) -> bool {
+        match self.layouts.get_mut(self.active) {
+            Some(layout) => layout.view.take_focus(source),
+            None => false,
+        }
+    }
+
+    fn call_on_any<'a>(&mut self, selector: &Selector,
+                       callback: Box<FnMut(&mut Any) + 'a>) {
+        // Other layouts share their views with the active one,
+        // so there's no need to look into them.
+        if let Some(layout) = self.layouts.get_mut(self.active) {
+            layout.view.call_on_any(selector, callback);
+        }
+    }
+
+    fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
+        match self.layouts.get_mut(self.active) {
+            Some(layout) => layout.view.focus_view(selector),
+            None => Err(()),
+        }
+    }
+
+    fn needs_relayout(&self) -> bool {
+        self.layouts
+            .get(self.active)
+            .map(|layout| layout.view.needs_relayout())
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::DummyView;
+
+    #[test]
+    fn pick() {
+        let view = ResponsiveView::new()
+            .breakpoint((80, 0), DummyView)
+            .breakpoint((40, 10), DummyView)
+            .breakpoint((20, 0), DummyView);
+
+        assert_eq!(0, view.pick(Vec2::new(100, 5)));
+        assert_eq!(1, view.pick(Vec2::new(50, 20)));
+        assert_eq!(2, view.pick(Vec2::new(50, 5)));
+        // Nothing fits: use the last one.
+        assert_eq!(2, view.pick(Vec2::new(10, 5)));
+    }
+}
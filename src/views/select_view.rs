@@ -6,13 +6,14 @@ use align::{Align, HAlign, VAlign};
 use direction::Direction;
 use event::{Callback, Event, EventResult, Key};
 use menu::MenuTree;
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::cell::Cell;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::rc::Rc;
-use theme::ColorStyle;
+use std::time::{Duration, Instant};
+use theme::{ColorStyle, Effect};
 
-use unicode_width::UnicodeWidthStr;
+use utils::{StyledString, fuzzy_match};
 use vec::Vec2;
 use view::{Position, ScrollBase, View};
 use views::MenuPopup;
@@ -47,6 +48,40 @@ use views::MenuPopup;
 /// # }
 ///
 /// ```
+///
+/// # Searching
+///
+/// Typing the beginning of a label jumps to the next matching item. In
+/// filter mode, typed characters instead fuzzy-match the labels: other
+/// items are hidden, and matched characters are underlined. `Backspace`
+/// and `Esc` edit the query.
+///
+/// ```rust
+/// # use cursive::views::SelectView;
+/// let mut select = SelectView::new().filter_mode();
+/// select.add_all_str(vec!["apple", "banana", "cherry"]);
+/// select.set_filter_query("an");
+/// assert_eq!("banana", &*select.selection());
+/// ```
+///
+/// # Multiple selection
+///
+/// In multi-selection mode, `Space` toggles the focused item, `Shift` with
+/// the arrows selects a range, `Ctrl-A` selects all visible items and
+/// `Ctrl-D` none. Selected items are checked.
+///
+/// ```rust
+/// # use cursive::views::SelectView;
+/// let mut hosts = SelectView::new()
+///     .multiselect()
+///     .on_change(|_, hosts| {
+///         // `hosts` are the selected values.
+///         let _: &[_] = hosts;
+///     });
+/// hosts.add_all_str(vec!["alpha", "beta", "gamma"]);
+/// hosts.set_selected(vec![0, 2]);
+/// assert_eq!("gamma", &*hosts.selections()[1]);
+/// ```
 pub struct SelectView<T = String> {
     items: Vec<Item<T>>,
     enabled: bool,
@@ -65,8 +100,32 @@ pub struct SelectView<T = String> {
     // We "cache" it during the draw, so we need interior mutability.
     last_offset: Cell<Vec2>,
     last_size: Vec2,
+    // Characters typed so far, to jump to an item starting with them.
+    prefix: String,
+    // When the last character of the prefix was typed.
+    last_key: Option<Instant>,
+    // `true` if typing filters the items.
+    filter_mode: bool,
+    // Query for the filter mode.
+    query: String,
+    // Ids of the visible items, with the offsets of the matched characters.
+    // `None` when all items are visible.
+    filtered: Option<Vec<(usize, Vec<usize>)>>,
+    // `true` if several items can be selected.
+    multiselect: bool,
+    // Focused item when the range selection started, and which items were
+    // selected then.
+    range: Option<(usize, Vec<bool>)>,
+    // This callback is called when items are selected or unselected.
+    on_change: Option<Rc<Fn(&mut Cursive, &[Rc<T>])>>,
 }
 
+// Delay after which typed characters start a new prefix.
+const TYPE_AHEAD_DELAY: u64 = 1000;
+
+// Width of the checkmarks in multi-selection mode.
+const MARK_WIDTH: usize = 4;
+
 impl<T: 'static> SelectView<T> {
     /// Creates a new empty SelectView.
     pub fn new() -> Self {
@@ -81,6 +140,14 @@ impl<T: 'static> SelectView<T> {
             popup: false,
             last_offset: Cell::new(Vec2::zero()),
             last_size: Vec2::zero(),
+            prefix: String::new(),
+            last_key: None,
+            filter_mode: false,
+            query: String::new(),
+            filtered: None,
+            multiselect: false,
+            range: None,
+            on_change: None,
         }
     }
 
@@ -96,6 +163,117 @@ impl<T: 'static> SelectView<T> {
         self.popup = popup;
     }
 
+    /// If `filter_mode` is `true`, typing filters the items.
+    ///
+    /// Otherwise, typing jumps to the next item starting with the typed
+    /// characters.
+    pub fn set_filter_mode(&mut self, filter_mode: bool) {
+        self.filter_mode = filter_mode;
+        self.set_filter_query("");
+    }
+
+    /// Filters the items as the user types.
+    ///
+    /// Chainable variant.
+    pub fn filter_mode(self) -> Self {
+        self.with(|s| s.set_filter_mode(true))
+    }
+
+    /// Returns the query of the filter mode.
+    pub fn filter_query(&self) -> &str {
+        &self.query
+    }
+
+    /// Only shows the items fuzzy-matching `query`.
+    ///
+    /// An empty query shows all items.
+    pub fn set_filter_query<S: Into<String>>(&mut self, query: S) {
+        self.query = query.into();
+        self.refilter();
+    }
+
+    /// If `multiselect` is `true`, several items can be selected.
+    ///
+    /// Not available in popup mode.
+    pub fn set_multiselect(&mut self, multiselect: bool) {
+        self.multiselect = multiselect;
+    }
+
+    /// Lets the user select several items.
+    ///
+    /// Chainable variant.
+    pub fn multiselect(self) -> Self {
+        self.with(|s| s.set_multiselect(true))
+    }
+
+    /// Sets a callback to be used when items are selected or unselected.
+    ///
+    /// The values of all selected items will be given to the callback.
+    pub fn set_on_change<F>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, &[Rc<T>]) + 'static
+    {
+        self.on_change = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when items are selected or unselected.
+    ///
+    /// Chainable variant.
+    pub fn on_change<F>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, &[Rc<T>]) + 'static
+    {
+        self.with(|s| s.set_on_change(cb))
+    }
+
+    /// Returns the values of the selected items, in order.
+    pub fn selections(&self) -> Vec<Rc<T>> {
+        self.items
+            .iter()
+            .filter(|item| item.selected)
+            .map(|item| item.value.clone())
+            .collect()
+    }
+
+    /// Returns the ids of the selected items, in order.
+    pub fn selected_ids(&self) -> Vec<usize> {
+        (0..self.items.len()).filter(|&i| self.items[i].selected).collect()
+    }
+
+    /// Returns `true` if the item `id` is selected.
+    pub fn is_selected(&self, id: usize) -> bool {
+        self.items.get(id).map_or(false, |item| item.selected)
+    }
+
+    /// Selects exactly the items with the given ids.
+    ///
+    /// Ids out of range are ignored.
+    pub fn set_selected<I: IntoIterator<Item = usize>>(&mut self, ids: I) {
+        self.select_none();
+        for id in ids {
+            if let Some(item) = self.items.get_mut(id) {
+                item.selected = true;
+            }
+        }
+    }
+
+    /// Selects all visible items.
+    ///
+    /// Bound to `Ctrl-A` in multi-selection mode.
+    pub fn select_all(&mut self) {
+        for row in 0..self.visible_len() {
+            let id = self.visible_id(row);
+            self.items[id].selected = true;
+        }
+    }
+
+    /// Unselects all items.
+    ///
+    /// Bound to `Ctrl-D` in multi-selection mode.
+    pub fn select_none(&mut self) {
+        for item in &mut self.items {
+            item.selected = false;
+        }
+    }
+
     /// Disables this view.
     ///
     /// A disabled view cannot be selected.
@@ -199,11 +377,29 @@ impl<T: 'static> SelectView<T> {
     pub fn clear(&mut self) {
         self.items.clear();
         self.focus.set(0);
+        self.refilter();
     }
 
     /// Adds a item to the list, with given label and value.
     pub fn add_item<S: Into<String>>(&mut self, label: S, value: T) {
-        self.items.push(Item::new(label.into(), value));
+        self.add_item_styled(label.into(), value);
+    }
+
+    /// Adds a item to the list, with a styled label.
+    ///
+    /// See [`StyledString`].
+    ///
+    /// [`StyledString`]: ../utils/markup/struct.StyledString.html
+    pub fn add_item_styled<S: Into<StyledString>>(&mut self, label: S,
+                                                  value: T) {
+        let item = Item::new(label.into(), value);
+        if let Some(ref mut filtered) = self.filtered {
+            if let Some(positions) = fuzzy_match(&self.query,
+                                                 item.label.source()) {
+                filtered.push((self.items.len(), positions));
+            }
+        }
+        self.items.push(item);
     }
 
     /// Removes an item from the list.
@@ -213,6 +409,9 @@ impl<T: 'static> SelectView<T> {
         if focus >= id && focus > 0 {
             self.focus.set(focus - 1);
         }
+        if self.filtered.is_some() {
+            self.refilter();
+        }
     }
 
     /// Chainable variant of add_item
@@ -220,6 +419,12 @@ impl<T: 'static> SelectView<T> {
         self.with(|s| s.add_item(label, value))
     }
 
+    /// Chainable variant of add_item_styled
+    pub fn item_styled<S: Into<StyledString>>(self, label: S, value: T)
+                                              -> Self {
+        self.with(|s| s.add_item_styled(label, value))
+    }
+
     /// Adds all items from from an iterator.
     pub fn add_all<S, I>(&mut self, iter: I)
         where S: Into<String>,
@@ -241,10 +446,28 @@ impl<T: 'static> SelectView<T> {
     }
 
     fn draw_item(&self, printer: &Printer, i: usize) {
-        let l = self.items[i].label.width();
+        if !self.multiselect || self.popup {
+            self.draw_label(printer, i);
+            return;
+        }
+
+        let mark = if self.items[i].selected {
+            "[X] "
+        } else {
+            "[ ] "
+        };
+        printer.print((0, 0), mark);
+        let size = Vec2::new(printer.size.x.saturating_sub(MARK_WIDTH),
+                             printer.size.y);
+        self.draw_label(&printer.sub_printer((MARK_WIDTH, 0), size, true), i);
+    }
+
+    fn draw_label(&self, printer: &Printer, i: usize) {
+        let label = self.label(i);
+        let l = label.width();
         let x = self.align.h.get_offset(l, printer.size.x);
         printer.print_hline((0, 0), x, " ");
-        printer.print((x, 0), &self.items[i].label);
+        printer.print_styled((x, 0), &label);
         if l < printer.size.x {
             printer.print_hline((x + l, 0), printer.size.x - l - x, " ");
         }
@@ -279,19 +502,308 @@ impl<T: 'static> SelectView<T> {
     pub fn set_selection(&mut self, i: usize) {
         // TODO: Check if `i > self.len()` ?
         self.focus.set(i);
-        self.scrollbase.scroll_to(i);
+        let row = self.focus_row();
+        self.scrollbase.scroll_to(row);
     }
 
-    fn focus_up(&mut self, n: usize) {
+    // Returns the number of visible items.
+    fn visible_len(&self) -> usize {
+        match self.filtered {
+            Some(ref filtered) => filtered.len(),
+            None => self.items.len(),
+        }
+    }
+
+    // Returns the id of the item shown at `row`.
+    fn visible_id(&self, row: usize) -> usize {
+        match self.filtered {
+            Some(ref filtered) => filtered[row].0,
+            None => row,
+        }
+    }
+
+    // Returns the row showing the item `id`, if it is visible.
+    fn visible_row(&self, id: usize) -> Option<usize> {
+        match self.filtered {
+            Some(ref filtered) => {
+                filtered.binary_search_by_key(&id, |&(i, _)| i).ok()
+            }
+            None if id < self.items.len() => Some(id),
+            None => None,
+        }
+    }
+
+    // Returns the row of the focused item.
+    fn focus_row(&self) -> usize {
+        self.visible_row(self.focus()).unwrap_or(0)
+    }
+
+    // Returns the label of the item `id`, with the matched characters
+    // underlined.
+    fn label(&self, id: usize) -> Cow<StyledString> {
+        let label = &self.items[id].label;
+        match self.filtered {
+            Some(ref filtered) => {
+                let row = filtered.binary_search_by_key(&id, |&(i, _)| i);
+                match row {
+                    Ok(row) => {
+                        Cow::Owned(underline_matches(label, &filtered[row].1))
+                    }
+                    Err(_) => Cow::Borrowed(label),
+                }
+            }
+            None => Cow::Borrowed(label),
+        }
+    }
+
+    // Hides the items not matching the query.
+    fn refilter(&mut self) {
+        self.filtered = if self.query.is_empty() {
+            None
+        } else {
+            Some(self.items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    fuzzy_match(&self.query, item.label.source())
+                        .map(|positions| (i, positions))
+                })
+                .collect())
+        };
+
+        // Keep the focus on a visible item.
+        if self.visible_row(self.focus()).is_none() &&
+           self.visible_len() > 0 {
+            let id = self.visible_id(0);
+            self.focus.set(id);
+        }
+        if !self.popup {
+            let len = self.visible_len();
+            self.scrollbase.set_heights(self.last_size.y, len);
+            let row = self.focus_row();
+            self.scrollbase.scroll_to(row);
+        }
+    }
+
+    // Jumps to the next item starting with the characters typed so far.
+    fn type_ahead(&mut self, c: char) {
+        let now = Instant::now();
+        let delay = Duration::from_millis(TYPE_AHEAD_DELAY);
+        if self.last_key.map_or(true, |last| now - last > delay) {
+            self.prefix.clear();
+        }
+        self.last_key = Some(now);
+        self.prefix.extend(c.to_lowercase());
+
+        // A new prefix looks after the focus, a longer one may still
+        // match the focused item. Repeating the same character cycles
+        // through the items starting with it.
+        let single = c.to_lowercase().collect::<String>();
         let focus = self.focus();
-        let n = min(focus, n);
-        self.focus.set(focus - n);
+        let found = if self.prefix == single {
+            self.find_prefix(&single, focus + 1)
+        } else {
+            let prefix = self.prefix.clone();
+            self.find_prefix(&prefix, focus).or_else(|| {
+                if prefix.chars().all(|p| single.starts_with(p)) {
+                    self.find_prefix(&single, focus + 1)
+                } else {
+                    None
+                }
+            })
+        };
+        if let Some(i) = found {
+            self.focus.set(i);
+        }
+    }
+
+    // Returns the first item from `start` whose label starts with `prefix`,
+    // ignoring case.
+    //
+    // Cycles back to the beginning of the list when reaching the end.
+    fn find_prefix(&self, prefix: &str, start: usize) -> Option<usize> {
+        // This is achieved by chaining twice the iterator
+        let iter = self.items.iter().chain(self.items.iter());
+        iter.enumerate()
+            .skip(start)
+            .find(|&(_, item)| {
+                item.label.source().to_lowercase().starts_with(prefix)
+            })
+            // Apply modulo in case we have a hit
+            // from the chained iterator
+            .map(|(i, _)| i % self.items.len())
+    }
+
+    // Handles the keys typing a prefix or a filter query.
+    fn on_search_event(&mut self, event: &Event) -> bool {
+        if self.filter_mode {
+            let mut query = self.query.clone();
+            match *event {
+                Event::Char(c) => query.push(c),
+                Event::Key(Key::Backspace) if !query.is_empty() => {
+                    query.pop();
+                }
+                Event::Key(Key::Esc) if !query.is_empty() => query.clear(),
+                _ => return false,
+            }
+            self.set_filter_query(query);
+        } else if let Event::Char(c) = *event {
+            self.type_ahead(c);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn focus_up(&mut self, n: usize) {
+        if self.visible_len() > 0 {
+            let row = self.focus_row().saturating_sub(n);
+            let id = self.visible_id(row);
+            self.focus.set(id);
+        }
     }
 
     fn focus_down(&mut self, n: usize) {
-        let focus = min(self.focus() + n, self.items.len() - 1);
-        self.focus.set(focus);
+        let len = self.visible_len();
+        if len > 0 {
+            let row = min(self.focus_row() + n, len - 1);
+            let id = self.visible_id(row);
+            self.focus.set(id);
+        }
+    }
+
+    // Returns which items are selected.
+    fn selected_flags(&self) -> Vec<bool> {
+        self.items.iter().map(|item| item.selected).collect()
+    }
+
+    // Moves the focus with `move_focus`, selecting the items from the
+    // focus when the range selection started.
+    fn select_range<F: FnOnce(&mut Self)>(&mut self, move_focus: F) {
+        // Nothing to select, and no row to start from.
+        if self.visible_len() == 0 {
+            self.range = None;
+            return;
+        }
+        if self.range.is_none() {
+            self.range = Some((self.focus(), self.selected_flags()));
+        }
+        move_focus(self);
+
+        let (anchor, base) = self.range.clone().unwrap();
+        // Items may have changed since the range selection started.
+        if base.len() != self.items.len() {
+            self.range = None;
+            return;
+        }
+        for (item, selected) in self.items.iter_mut().zip(base) {
+            item.selected = selected;
+        }
+        let focus = self.focus_row();
+        let anchor = self.visible_row(anchor).unwrap_or(focus);
+        for row in min(anchor, focus)..max(anchor, focus) + 1 {
+            let id = self.visible_id(row);
+            self.items[id].selected = true;
+        }
     }
+
+    // Handles the keys selecting items in multi-selection mode.
+    fn on_multiselect_event(&mut self, event: &Event) -> Option<EventResult> {
+        let focus = self.focus();
+        let before = self.selected_flags();
+        let len = self.items.len();
+        match *event {
+            Event::Char(' ') if self.visible_len() > 0 => {
+                self.items[focus].selected = !self.items[focus].selected;
+            }
+            Event::CtrlChar('a') => self.select_all(),
+            Event::CtrlChar('d') => self.select_none(),
+            Event::Shift(Key::Up) => self.select_range(|s| s.focus_up(1)),
+            Event::Shift(Key::Down) => {
+                self.select_range(|s| s.focus_down(1))
+            }
+            Event::Shift(Key::PageUp) => {
+                self.select_range(|s| s.focus_up(10))
+            }
+            Event::Shift(Key::PageDown) => {
+                self.select_range(|s| s.focus_down(10))
+            }
+            Event::Shift(Key::Home) => self.select_range(|s| s.focus_up(len)),
+            Event::Shift(Key::End) => {
+                self.select_range(|s| s.focus_down(len))
+            }
+            _ => {
+                self.range = None;
+                return None;
+            }
+        }
+        // Only range selection keeps the range going.
+        match *event {
+            Event::Shift(_) => (),
+            _ => self.range = None,
+        }
+
+        let row = self.focus_row();
+        self.scrollbase.scroll_to(row);
+
+        let mut callbacks = Vec::new();
+        if focus != self.focus() {
+            if let Some(cb) = self.on_select.clone() {
+                let v = self.selection();
+                callbacks.push(Callback::from_fn(move |s| cb(s, &v)));
+            }
+        }
+        if before != self.selected_flags() {
+            if let Some(cb) = self.on_change.clone() {
+                let values = self.selections();
+                callbacks.push(Callback::from_fn(move |s| cb(s, &values)));
+            }
+        }
+        Some(match callbacks.len() {
+            0 => EventResult::Consumed(None),
+            1 => EventResult::Consumed(callbacks.pop()),
+            _ => {
+                EventResult::with_cb(move |s| {
+                    for cb in &callbacks {
+                        cb(s);
+                    }
+                })
+            }
+        })
+    }
+
+    // Returns the result of a change of focus.
+    fn focus_changed(&mut self) -> EventResult {
+        let row = self.focus_row();
+        self.scrollbase.scroll_to(row);
+
+        if self.visible_len() == 0 {
+            return EventResult::Consumed(None);
+        }
+        EventResult::Consumed(self.on_select.clone().map(|cb| {
+            let v = self.selection();
+            Callback::from_fn(move |s| cb(s, &v))
+        }))
+    }
+}
+
+// Underlines the characters of `label` at the given byte offsets.
+fn underline_matches(label: &StyledString, positions: &[usize])
+                     -> StyledString {
+    let mut result = StyledString::new();
+    let mut offset = 0;
+    for (text, style) in label.spans() {
+        for (i, c) in text.char_indices() {
+            let style = if positions.contains(&(offset + i)) {
+                style.with_effect(Effect::Underline)
+            } else {
+                style
+            };
+            result.append_styled(c.to_string(), style);
+        }
+        offset += text.len();
+    }
+    result
 }
 
 impl SelectView<String> {
@@ -360,21 +872,22 @@ impl<T: 'static> View for SelectView<T> {
                 printer.print((0, 0), "<");
                 printer.print((x - 1, 0), ">");
 
-                let label = &self.items[self.focus()].label;
+                let label = self.label(self.focus());
 
                 // And center the text?
-                let offset = HAlign::Center.get_offset(label.len(), x);
+                let offset = HAlign::Center.get_offset(label.width(), x);
 
-                printer.print((offset, 0), label);
+                printer.print_styled((offset, 0), &label);
             });
         } else {
 
-            let h = self.items.len();
+            let h = self.visible_len();
             let offset = self.align.v.get_offset(h, printer.size.y);
             let printer =
                 &printer.sub_printer(Vec2::new(0, offset), printer.size, true);
 
-            self.scrollbase.draw(printer, |printer, i| {
+            self.scrollbase.draw(printer, |printer, row| {
+                let i = self.visible_id(row);
                 printer.with_selection(i == self.focus(), |printer| {
                     if i != self.focus() && !self.enabled {
                         printer.with_color(ColorStyle::Secondary, |printer| {
@@ -406,24 +919,37 @@ impl<T: 'static> View for SelectView<T> {
 
             // Add 2 spaces for the scrollbar if we need
             let w = if scrolling { w + 2 } else { w };
+            // And room for the checkmarks
+            let w = if self.multiselect { w + MARK_WIDTH } else { w };
 
             Vec2::new(w, h)
         }
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        if self.multiselect && !self.popup {
+            if let Some(result) = self.on_multiselect_event(&event) {
+                return result;
+            }
+        }
+        if self.on_search_event(&event) {
+            return self.focus_changed();
+        }
+
         if self.popup {
             match event {
                 // TODO: add Left/Right support for quick-switch?
-                Event::Key(Key::Enter) => {
-                    // Build a shallow menu tree to mimick the items array.
+                Event::Key(Key::Enter) if self.visible_len() > 0 => {
+                    // Build a shallow menu tree to mimick the visible items.
                     // TODO: cache it?
                     let mut tree = MenuTree::new();
-                    for (i, item) in self.items.iter().enumerate() {
+                    for row in 0..self.visible_len() {
+                        let i = self.visible_id(row);
+                        let item = &self.items[i];
                         let focus = self.focus.clone();
                         let on_submit = self.on_submit.as_ref().cloned();
                         let value = item.value.clone();
-                        tree.add_leaf(item.label.clone(), move |s| {
+                        tree.add_leaf(item.label.source(), move |s| {
                             focus.set(i);
                             if let Some(ref on_submit) = on_submit {
                                 on_submit(s, &value);
@@ -434,11 +960,11 @@ impl<T: 'static> View for SelectView<T> {
                     // the callback will want to use it.
                     let tree = Rc::new(tree);
 
-                    let focus = self.focus();
+                    let focus = self.focus_row();
                     // This is the offset for the label text.
                     // We'll want to show the popup so that the text matches.
                     // It'll be soo cool.
-                    let item_length = self.items[focus].label.len();
+                    let item_length = self.items[self.focus()].label.width();
                     let text_offset = if self.last_size.x >= item_length {
                         (self.last_size.x - item_length) / 2
                     } else {
@@ -474,16 +1000,19 @@ impl<T: 'static> View for SelectView<T> {
             }
         } else {
             match event {
-                Event::Key(Key::Up) if self.focus() > 0 => self.focus_up(1),
-                Event::Key(Key::Down) if self.focus() + 1 <
-                                         self.items.len() => {
+                Event::Key(Key::Up) if self.focus_row() > 0 => {
+                    self.focus_up(1)
+                }
+                Event::Key(Key::Down) if self.focus_row() + 1 <
+                                         self.visible_len() => {
                     self.focus_down(1)
                 }
                 Event::Key(Key::PageUp) => self.focus_up(10),
                 Event::Key(Key::PageDown) => self.focus_down(10),
-                Event::Key(Key::Home) => self.focus.set(0),
-                Event::Key(Key::End) => self.focus.set(self.items.len() - 1),
-                Event::Key(Key::Enter) if self.on_submit.is_some() => {
+                Event::Key(Key::Home) => self.focus_up(self.items.len()),
+                Event::Key(Key::End) => self.focus_down(self.items.len()),
+                Event::Key(Key::Enter) if self.on_submit.is_some() &&
+                                          self.visible_len() > 0 => {
                     let cb = self.on_submit.clone().unwrap();
                     let v = self.selection();
                     // We return a Callback Rc<|s| cb(s, &*v)>
@@ -491,30 +1020,9 @@ impl<T: 'static> View for SelectView<T> {
                         cb(s, &v)
                     })));
                 }
-                Event::Char(c) => {
-                    // Starting from the current focus,
-                    // find the first item that match the char.
-                    // Cycle back to the beginning of
-                    // the list when we reach the end.
-                    // This is achieved by chaining twice the iterator
-                    let iter = self.items.iter().chain(self.items.iter());
-                    if let Some((i, _)) = iter.enumerate()
-                        .skip(self.focus() + 1)
-                        .find(|&(_, item)| item.label.starts_with(c)) {
-                        // Apply modulo in case we have a hit
-                        // from the chained iterator
-                        self.focus.set(i % self.items.len());
-                    }
-                }
                 _ => return EventResult::Ignored,
             }
-            let focus = self.focus();
-            self.scrollbase.scroll_to(focus);
-
-            EventResult::Consumed(self.on_select.clone().map(|cb| {
-                let v = self.selection();
-                Callback::from_fn(move |s| cb(s, &v))
-            }))
+            self.focus_changed()
         }
     }
 
@@ -526,21 +1034,149 @@ impl<T: 'static> View for SelectView<T> {
         self.last_size = size;
 
         if !self.popup {
-            self.scrollbase.set_heights(size.y, self.items.len());
+            self.scrollbase.set_heights(size.y, self.visible_len());
         }
     }
 }
 
 struct Item<T> {
-    label: String,
+    label: StyledString,
     value: Rc<T>,
+    selected: bool,
 }
 
 impl<T> Item<T> {
-    fn new(label: String, value: T) -> Self {
+    fn new(label: StyledString, value: T) -> Self {
         Item {
             label: label,
             value: Rc::new(value),
+            selected: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::Style;
+
+    fn type_keys<T: 'static>(view: &mut SelectView<T>, keys: &str) {
+        for c in keys.chars() {
+            view.on_event(Event::Char(c));
+        }
+    }
+
+    #[test]
+    fn type_ahead() {
+        let mut select = SelectView::new()
+            .with_all_str(vec!["Apple", "apricot", "banana", "blueberry"]);
+        type_keys(&mut select, "b");
+        assert_eq!(Some(2), select.selected_id());
+        type_keys(&mut select, "l");
+        assert_eq!(Some(3), select.selected_id());
+
+        select.prefix.clear();
+        type_keys(&mut select, "a");
+        assert_eq!(Some(0), select.selected_id());
+        // Repeating a character cycles through the matching items.
+        type_keys(&mut select, "a");
+        assert_eq!(Some(1), select.selected_id());
+        type_keys(&mut select, "a");
+        assert_eq!(Some(0), select.selected_id());
+    }
+
+    #[test]
+    fn filter() {
+        let mut select = SelectView::new()
+            .filter_mode()
+            .with_all_str(vec!["Apple", "apricot", "banana", "blueberry"]);
+        type_keys(&mut select, "ae");
+        assert_eq!("ae", select.filter_query());
+        assert_eq!(1, select.visible_len());
+        assert_eq!(Some(0), select.selected_id());
+
+        select.on_event(Event::Key(Key::Backspace));
+        assert_eq!(3, select.visible_len());
+        type_keys(&mut select, "n");
+        assert_eq!(Some(2), select.selected_id());
+        select.on_event(Event::Key(Key::Down));
+        assert_eq!(Some(2), select.selected_id());
+
+        select.add_item_str("mango");
+        assert_eq!(2, select.visible_len());
+        select.on_event(Event::Key(Key::End));
+        assert_eq!(Some(4), select.selected_id());
+        assert_eq!(vec![("m", Style::none()),
+                        ("an", Effect::Underline.into()),
+                        ("go", Style::none())],
+                   select.label(4).spans());
+
+        select.on_event(Event::Key(Key::Esc));
+        assert_eq!(5, select.visible_len());
+    }
+
+    #[test]
+    fn filter_popup() {
+        let mut select = SelectView::new()
+            .popup()
+            .filter_mode()
+            .with_all_str(vec!["Apple", "apricot", "banana"]);
+        type_keys(&mut select, "ct");
+        assert_eq!("apricot", &*select.selection());
+        assert_eq!(0, select.focus_row());
+        // As if drawn, to place the menu.
+        select.last_offset.set(Vec2::new(10, 10));
+        assert!(select.on_event(Event::Key(Key::Enter)).is_consumed());
+
+        type_keys(&mut select, "x");
+        assert_eq!(0, select.visible_len());
+        assert!(!select.on_event(Event::Key(Key::Enter)).is_consumed());
+    }
+
+    #[test]
+    fn multiselect() {
+        let mut select = SelectView::new()
+            .multiselect()
+            .with_all_str(vec!["a", "b", "c", "d", "e"]);
+        select.on_event(Event::Char(' '));
+        select.on_event(Event::Key(Key::Down));
+        select.on_event(Event::Key(Key::Down));
+        assert!(select.on_event(Event::Char(' ')).is_consumed());
+        assert_eq!(vec![0, 2], select.selected_ids());
+
+        // A range keeps the items selected before it.
+        select.on_event(Event::Shift(Key::Down));
+        select.on_event(Event::Shift(Key::Down));
+        select.on_event(Event::Shift(Key::Up));
+        assert_eq!(vec![0, 2, 3], select.selected_ids());
+        select.on_event(Event::Key(Key::Up));
+        select.on_event(Event::Shift(Key::Up));
+        assert_eq!(vec![0, 1, 2, 3], select.selected_ids());
+
+        select.on_event(Event::CtrlChar('d'));
+        assert!(select.selections().is_empty());
+        select.set_filter_query("b");
+        select.on_event(Event::CtrlChar('a'));
+        assert_eq!(vec![1], select.selected_ids());
+
+        select.set_filter_query("");
+        select.set_selected(vec![4, 3, 9]);
+        let values: Vec<_> = select.selections()
+            .iter()
+            .map(|value| value.to_string())
+            .collect();
+        assert_eq!(vec!["d", "e"], values);
+        assert!(select.is_selected(4));
+
+        // Ranges are ignored when no item is visible.
+        select.set_filter_query("zzz");
+        select.on_event(Event::Shift(Key::Down));
+        select.on_event(Event::Shift(Key::End));
+        assert_eq!(vec![3, 4], select.selected_ids());
+
+        let mut empty = SelectView::<String>::new().multiselect();
+        empty.on_event(Event::Shift(Key::Down));
+        empty.on_event(Event::Shift(Key::Home));
+        assert!(empty.selections().is_empty());
+    }
+}
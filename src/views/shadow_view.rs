@@ -1,7 +1,8 @@
 use Printer;
 use theme::ColorStyle;
 use vec::Vec2;
-use view::{View, ViewWrapper};
+use view::{View, ViewWrapper, child_draw, child_layout,
+           child_required_size};
 
 /// Wrapper view that adds a shadow.
 ///
@@ -50,12 +51,12 @@ impl<T: View> ViewWrapper for ShadowView<T> {
     fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
         // Make sure req >= offset
         let offset = self.padding().or_min(req);
-        self.view.required_size(req - offset) + offset
+        child_required_size(&mut self.view, req - offset) + offset
     }
 
     fn wrap_layout(&mut self, size: Vec2) {
         let offset = self.padding().or_min(size);
-        self.view.layout(size - offset);
+        child_layout(&mut self.view, size - offset);
     }
 
     fn wrap_draw(&self, printer: &Printer) {
@@ -83,6 +84,6 @@ impl<T: View> ViewWrapper for ShadowView<T> {
         // Draw the view background
         let printer =
             printer.sub_printer(Vec2::zero(), printer.size - (1, 1), true);
-        self.view.draw(&printer);
+        child_draw(&self.view, &printer);
     }
 }
@@ -1,6 +1,7 @@
 use vec::Vec2;
 use view::View;
 use view::ViewWrapper;
+use view::child_layout;
 
 /// Wrapper around a view that remembers its size.
 pub struct SizedView<T: View> {
@@ -25,6 +26,6 @@ impl<T: View> ViewWrapper for SizedView<T> {
 
     fn wrap_layout(&mut self, size: Vec2) {
         self.size = size;
-        self.view.layout(size);
+        child_layout(&mut self.view, size);
     }
 }
@@ -0,0 +1,553 @@
+use Printer;
+use With;
+use direction::{Direction, Orientation, Relative};
+use event::{Event, EventResult, Key};
+use std::any::Any;
+use std::cmp::{max, min};
+use vec::Vec2;
+use view::{Selector, View, child_draw, child_layout,
+           child_required_size};
+
+/// Position of the divider in a [`SplitView`].
+///
+/// [`SplitView`]: struct.SplitView.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplitPosition {
+    /// The first view takes this fraction of the available space.
+    ///
+    /// The value is clamped between `0` and `1`.
+    Ratio(f32),
+    /// The first view takes this many cells.
+    Fixed(usize),
+}
+
+/// Shows two views side by side (or one on top of the other),
+/// separated by a movable divider.
+///
+/// When the split has the focus, the divider can be moved with
+/// `Ctrl+Arrow` keys along the split orientation, and `Ctrl+Shift+Arrow`
+/// collapses the view in the direction of the arrow (or restores it).
+///
+/// `Tab` and the arrow keys move the focus between the two views.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::{SplitView, TextView};
+/// let split = SplitView::horizontal(TextView::new("Files"),
+///                                   TextView::new("Preview"))
+///     .ratio(0.3)
+///     .min_first(10);
+/// ```
+pub struct SplitView {
+    first: Pane,
+    second: Pane,
+
+    orientation: Orientation,
+    position: SplitPosition,
+
+    /// Pane currently collapsed, if any.
+    collapsed: Option<Relative>,
+    /// Pane currently in focus.
+    focus: Relative,
+
+    /// Cells along the orientation available to both panes last layout.
+    available: usize,
+    /// Number of cells the divider moves on each key press.
+    step: usize,
+}
+
+struct Pane {
+    view: Box<View>,
+    /// Number of cells along the orientation given to this pane.
+    length: usize,
+    /// Size given to the view during the last layout.
+    size: Vec2,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Pane {
+    fn new<V: View + 'static>(view: V) -> Self {
+        Pane {
+            view: Box::new(view),
+            length: 0,
+            size: Vec2::zero(),
+            min: 0,
+            max: None,
+        }
+    }
+}
+
+impl SplitView {
+    /// Creates a new split with the given orientation.
+    ///
+    /// The divider starts in the middle.
+    pub fn new<F, S>(orientation: Orientation, first: F, second: S) -> Self
+        where F: View + 'static,
+              S: View + 'static
+    {
+        SplitView {
+            first: Pane::new(first),
+            second: Pane::new(second),
+            orientation: orientation,
+            position: SplitPosition::Ratio(0.5),
+            collapsed: None,
+            focus: Relative::Front,
+            available: 0,
+            step: 1,
+        }
+    }
+
+    /// Creates a new split with `first` on the left and `second` on the right.
+    pub fn horizontal<F, S>(first: F, second: S) -> Self
+        where F: View + 'static,
+              S: View + 'static
+    {
+        SplitView::new(Orientation::Horizontal, first, second)
+    }
+
+    /// Creates a new split with `first` on top of `second`.
+    pub fn vertical<F, S>(first: F, second: S) -> Self
+        where F: View + 'static,
+              S: View + 'static
+    {
+        SplitView::new(Orientation::Vertical, first, second)
+    }
+
+    /// Sets the position of the divider.
+    pub fn set_position(&mut self, position: SplitPosition) {
+        self.position = match position {
+            SplitPosition::Ratio(r) if r < 0f32 => SplitPosition::Ratio(0f32),
+            SplitPosition::Ratio(r) if r > 1f32 => SplitPosition::Ratio(1f32),
+            other => other,
+        };
+    }
+
+    /// Sets the position of the divider.
+    ///
+    /// Chainable variant.
+    pub fn position(self, position: SplitPosition) -> Self {
+        self.with(|s| s.set_position(position))
+    }
+
+    /// Gives the first view the fraction `ratio` of the available space.
+    ///
+    /// Chainable variant.
+    pub fn ratio(self, ratio: f32) -> Self {
+        self.position(SplitPosition::Ratio(ratio))
+    }
+
+    /// Gives the first view exactly `cells` cells.
+    ///
+    /// Chainable variant.
+    pub fn fixed(self, cells: usize) -> Self {
+        self.position(SplitPosition::Fixed(cells))
+    }
+
+    /// Returns the current position of the divider.
+    pub fn get_position(&self) -> SplitPosition {
+        self.position
+    }
+
+    /// Sets the minimum size of the first view.
+    pub fn set_min_first(&mut self, min: usize) {
+        self.first.min = min;
+    }
+
+    /// Sets the minimum size of the first view.
+    ///
+    /// Chainable variant.
+    pub fn min_first(self, min: usize) -> Self {
+        self.with(|s| s.set_min_first(min))
+    }
+
+    /// Sets the maximum size of the first view.
+    pub fn set_max_first(&mut self, max: usize) {
+        self.first.max = Some(max);
+    }
+
+    /// Sets the maximum size of the first view.
+    ///
+    /// Chainable variant.
+    pub fn max_first(self, max: usize) -> Self {
+        self.with(|s| s.set_max_first(max))
+    }
+
+    /// Sets the minimum size of the second view.
+    pub fn set_min_second(&mut self, min: usize) {
+        self.second.min = min;
+    }
+
+    /// Sets the minimum size of the second view.
+    ///
+    /// Chainable variant.
+    pub fn min_second(self, min: usize) -> Self {
+        self.with(|s| s.set_min_second(min))
+    }
+
+    /// Sets the maximum size of the second view.
+    pub fn set_max_second(&mut self, max: usize) {
+        self.second.max = Some(max);
+    }
+
+    /// Sets the maximum size of the second view.
+    ///
+    /// Chainable variant.
+    pub fn max_second(self, max: usize) -> Self {
+        self.with(|s| s.set_max_second(max))
+    }
+
+    /// Sets the number of cells the divider moves on each key press.
+    ///
+    /// Defaults to 1.
+    pub fn set_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    /// Sets the number of cells the divider moves on each key press.
+    ///
+    /// Chainable variant.
+    pub fn step(self, step: usize) -> Self {
+        self.with(|s| s.set_step(step))
+    }
+
+    /// Collapses one side of the split.
+    ///
+    /// `Relative::Front` hides the first view, `Relative::Back` hides the
+    /// second one. The remaining view then takes all the space.
+    pub fn collapse(&mut self, side: Relative) {
+        self.collapsed = Some(side);
+        if self.focus == side {
+            self.focus = other_side(side);
+        }
+    }
+
+    /// Restores a collapsed side, if any.
+    pub fn expand(&mut self) {
+        self.collapsed = None;
+    }
+
+    /// Returns the collapsed side, if any.
+    pub fn collapsed(&self) -> Option<Relative> {
+        self.collapsed
+    }
+
+    /// Moves the divider by `delta` cells.
+    ///
+    /// A positive value grows the first view. The new position keeps the
+    /// current kind (ratio or fixed), and respects the size limits.
+    pub fn move_divider(&mut self, delta: isize) {
+        self.collapsed = None;
+
+        let current = self.lengths(self.available).0 as isize;
+        let target = max(0, current + delta) as usize;
+        let target = self.clamp_first(target, self.available);
+
+        self.position = match self.position {
+            SplitPosition::Fixed(_) => SplitPosition::Fixed(target),
+            SplitPosition::Ratio(_) if self.available == 0 => self.position,
+            SplitPosition::Ratio(_) => {
+                SplitPosition::Ratio(target as f32 / self.available as f32)
+            }
+        };
+    }
+
+    fn pane(&self, side: Relative) -> &Pane {
+        match side {
+            Relative::Front => &self.first,
+            Relative::Back => &self.second,
+        }
+    }
+
+    fn pane_mut(&mut self, side: Relative) -> &mut Pane {
+        match side {
+            Relative::Front => &mut self.first,
+            Relative::Back => &mut self.second,
+        }
+    }
+
+    // Applies the size limits of both panes to the first length.
+    fn clamp_first(&self, first: usize, available: usize) -> usize {
+        let mut first = first;
+        if let Some(max_first) = self.first.max {
+            first = min(first, max_first);
+        }
+        first = max(first, self.first.min);
+        if let Some(max_second) = self.second.max {
+            first = max(first, available.saturating_sub(max_second));
+        }
+        first = min(first, available.saturating_sub(self.second.min));
+
+        min(first, available)
+    }
+
+    // Splits `available` cells between the two panes.
+    fn lengths(&self, available: usize) -> (usize, usize) {
+        match self.collapsed {
+            Some(Relative::Front) => return (0, available),
+            Some(Relative::Back) => return (available, 0),
+            None => (),
+        }
+
+        let wanted = match self.position {
+            SplitPosition::Ratio(r) => (available as f32 * r).round() as usize,
+            SplitPosition::Fixed(cells) => cells,
+        };
+
+        let first = self.clamp_first(wanted, available);
+        (first, available - first)
+    }
+
+    // Number of cells along the orientation, once the divider is removed.
+    fn available_for(&self, size: Vec2) -> usize {
+        self.orientation.get(&size).saturating_sub(1)
+    }
+
+    fn is_collapsed(&self, side: Relative) -> bool {
+        self.collapsed == Some(side)
+    }
+
+    fn focus_side(&mut self, side: Relative, source: Direction) -> bool {
+        if self.is_collapsed(side) {
+            return false;
+        }
+        if self.pane_mut(side).view.take_focus(source) {
+            self.focus = side;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn toggle_collapse(&mut self, side: Relative) -> EventResult {
+        match self.collapsed {
+            // Pushing toward the other side restores the collapsed view.
+            Some(collapsed) if collapsed != side => self.expand(),
+            Some(_) => return EventResult::Ignored,
+            None => self.collapse(side),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn move_focus(&mut self, side: Relative, source: Direction)
+                  -> EventResult {
+        if self.focus != side && self.focus_side(side, source) {
+            EventResult::Consumed(None)
+        } else {
+            EventResult::Ignored
+        }
+    }
+}
+
+fn other_side(side: Relative) -> Relative {
+    match side {
+        Relative::Front => Relative::Back,
+        Relative::Back => Relative::Front,
+    }
+}
+
+impl View for SplitView {
+    fn draw(&self, printer: &Printer) {
+        let o = self.orientation;
+
+        if self.first.length > 0 {
+            let focused = self.focus == Relative::Front;
+            child_draw(&*self.first.view,
+                       &printer.sub_printer(Vec2::zero(),
+                                            self.first.size,
+                                            focused));
+        }
+
+        // Draw the divider right after the first view.
+        let offset = self.first.length;
+        match o {
+            Orientation::Horizontal => {
+                printer.print_vline((offset, 0), printer.size.y, "│")
+            }
+            Orientation::Vertical => {
+                printer.print_hline((0, offset), printer.size.x, "─")
+            }
+        }
+
+        if self.second.length > 0 {
+            let offset = o.make_vec(offset + 1, 0);
+            let focused = self.focus == Relative::Back;
+            child_draw(&*self.second.view,
+                       &printer.sub_printer(offset,
+                                            self.second.size,
+                                            focused));
+        }
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        let o = self.orientation;
+        let available = self.available_for(req);
+        let (first, second) = self.lengths(available);
+
+        // We always take the entire space along the orientation,
+        // but only as much as the children need on the other axis.
+        let mut other = 1;
+        if first > 0 {
+            let size = child_required_size(&mut *self.first.view,
+                                           req.with_axis(o, first));
+            other = max(other, o.swap().get(&size));
+        }
+        if second > 0 {
+            let size = child_required_size(&mut *self.second.view,
+                                           req.with_axis(o, second));
+            other = max(other, o.swap().get(&size));
+        }
+
+        o.make_vec(o.get(&req), min(other, o.swap().get(&req)))
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        let o = self.orientation;
+        self.available = self.available_for(size);
+        let (first, second) = self.lengths(self.available);
+
+        for &(side, length) in &[(Relative::Front, first),
+                                 (Relative::Back, second)] {
+            let pane = self.pane_mut(side);
+            pane.length = length;
+            pane.size = size.with_axis(o, length);
+            if length > 0 {
+                child_layout(&mut *pane.view, pane.size);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let focus = self.focus;
+        match self.pane_mut(focus).view.on_event(event.clone()) {
+            EventResult::Ignored => (),
+            res => return res,
+        }
+
+        let step = self.step as isize;
+        match (self.orientation, event) {
+            (Orientation::Horizontal, Event::Ctrl(Key::Left)) |
+            (Orientation::Vertical, Event::Ctrl(Key::Up)) => {
+                self.move_divider(-step);
+                EventResult::Consumed(None)
+            }
+            (Orientation::Horizontal, Event::Ctrl(Key::Right)) |
+            (Orientation::Vertical, Event::Ctrl(Key::Down)) => {
+                self.move_divider(step);
+                EventResult::Consumed(None)
+            }
+            (Orientation::Horizontal, Event::CtrlShift(Key::Left)) |
+            (Orientation::Vertical, Event::CtrlShift(Key::Up)) => {
+                self.toggle_collapse(Relative::Front)
+            }
+            (Orientation::Horizontal, Event::CtrlShift(Key::Right)) |
+            (Orientation::Vertical, Event::CtrlShift(Key::Down)) => {
+                self.toggle_collapse(Relative::Back)
+            }
+            (_, Event::Key(Key::Tab)) => {
+                self.move_focus(Relative::Back, Direction::front())
+            }
+            (_, Event::Shift(Key::Tab)) => {
+                self.move_focus(Relative::Front, Direction::back())
+            }
+            (Orientation::Horizontal, Event::Key(Key::Left)) |
+            (Orientation::Vertical, Event::Key(Key::Up)) => {
+                let source = Direction::Rel(Relative::Back)
+                    .absolute(self.orientation);
+                self.move_focus(Relative::Front, Direction::Abs(source))
+            }
+            (Orientation::Horizontal, Event::Key(Key::Right)) |
+            (Orientation::Vertical, Event::Key(Key::Down)) => {
+                let source = Direction::Rel(Relative::Front)
+                    .absolute(self.orientation);
+                self.move_focus(Relative::Back, Direction::Abs(source))
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, source: Direction) -> bool {
+        // Coming from the back, we start with the second view.
+        let order = match source.relative(self.orientation) {
+            Some(Relative::Back) => [Relative::Back, Relative::Front],
+            _ => [Relative::Front, Relative::Back],
+        };
+
+        order.iter().any(|&side| self.focus_side(side, source))
+    }
+
+    fn call_on_any<'a>(&mut self, selector: &Selector,
+                       mut callback: Box<FnMut(&mut Any) + 'a>) {
+        self.first
+            .view
+            .call_on_any(selector, Box::new(|any| callback(any)));
+        self.second
+            .view
+            .call_on_any(selector, Box::new(|any| callback(any)));
+    }
+
+    fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
+        for &side in &[Relative::Front, Relative::Back] {
+            if self.pane_mut(side).view.focus_view(selector).is_ok() {
+                self.focus = side;
+                if self.is_collapsed(side) {
+                    self.expand();
+                }
+                return Ok(());
+            }
+        }
+
+        Err(())
+    }
+
+    fn needs_relayout(&self) -> bool {
+        self.pane(Relative::Front).view.needs_relayout() ||
+        self.pane(Relative::Back).view.needs_relayout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use direction::Relative;
+    use views::DummyView;
+
+    #[test]
+    fn lengths() {
+        let split = SplitView::horizontal(DummyView, DummyView).ratio(0.25);
+        assert_eq!((10, 30), split.lengths(40));
+
+        let split = split.min_first(15);
+        assert_eq!((15, 25), split.lengths(40));
+
+        let split = split.max_second(10);
+        assert_eq!((30, 10), split.lengths(40));
+
+        let split = SplitView::vertical(DummyView, DummyView).fixed(50);
+        assert_eq!((40, 0), split.lengths(40));
+    }
+
+    #[test]
+    fn collapse() {
+        let mut split = SplitView::horizontal(DummyView, DummyView);
+        split.collapse(Relative::Front);
+        assert_eq!((0, 40), split.lengths(40));
+
+        split.expand();
+        assert_eq!((20, 20), split.lengths(40));
+    }
+
+    #[test]
+    fn move_divider() {
+        let mut split = SplitView::horizontal(DummyView, DummyView).fixed(5);
+        split.layout(Vec2::new(21, 3));
+        split.move_divider(3);
+        assert_eq!(SplitPosition::Fixed(8), split.get_position());
+
+        let mut split = SplitView::horizontal(DummyView, DummyView);
+        split.layout(Vec2::new(21, 3));
+        split.move_divider(-5);
+        assert_eq!((5, 15), split.lengths(20));
+    }
+}
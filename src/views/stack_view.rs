@@ -6,7 +6,8 @@ use event::{Event, EventResult};
 use std::any::Any;
 use theme::ColorStyle;
 use vec::Vec2;
-use view::{Offset, Position, Selector, View};
+use view::{Offset, Position, Selector, View, child_draw, child_layout,
+           child_required_size};
 use views::{Layer, ShadowView};
 
 /// Simple stack of views.
@@ -156,8 +157,9 @@ impl View for StackView {
                     .compute_offset(v.size, printer.size, previous);
 
                 previous = offset;
-                v.view
-                    .draw(&printer.sub_printer(offset, v.size, i + 1 == last));
+                let focused = i + 1 == last;
+                child_draw(&*v.view,
+                           &printer.sub_printer(offset, v.size, focused));
             }
         });
     }
@@ -177,9 +179,9 @@ impl View for StackView {
 
         for layer in &mut self.layers {
             // Give each guy what he asks for, within the budget constraints.
-            let size = Vec2::min(size, layer.view.required_size(size));
-            layer.size = size;
-            layer.view.layout(layer.size);
+            let required = child_required_size(&mut *layer.view, size);
+            layer.size = Vec2::min(size, required);
+            child_layout(&mut *layer.view, layer.size);
 
             // We need to call `layout()` on the view before giving it focus
             // for the first time. Otherwise it will not be properly set up.
@@ -197,7 +199,7 @@ impl View for StackView {
 
         self.layers
             .iter_mut()
-            .map(|layer| layer.view.required_size(size))
+            .map(|layer| child_required_size(&mut *layer.view, size))
             .fold(Vec2::new(1, 1), Vec2::max)
     }
 
@@ -225,3 +227,19 @@ impl View for StackView {
         Err(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use view::Boxable;
+    use views::DummyView;
+
+    #[test]
+    fn layer_ratio() {
+        let mut stack = StackView::new()
+            .fullscreen_layer(DummyView.size_ratio(0.5, 0.25));
+
+        stack.layout(Vec2::new(80, 24));
+        assert_eq!(vec![Vec2::new(40, 6)], stack.layer_sizes());
+    }
+}
@@ -0,0 +1,653 @@
+use Cursive;
+use Printer;
+use With;
+use align::HAlign;
+use direction::Direction;
+use event::{Callback, Event, EventResult, Key};
+use std::cmp::{Ordering, max, min};
+use std::rc::Rc;
+use theme::ColorStyle;
+use unicode_width::UnicodeWidthStr;
+use vec::Vec2;
+use view::{ScrollBase, SizeConstraint, View};
+
+/// A column in a [`TableView`].
+///
+/// [`TableView`]: struct.TableView.html
+pub struct TableColumn<T> {
+    title: String,
+    width: SizeConstraint,
+    align: HAlign,
+    label: Box<Fn(&T) -> String>,
+    compare: Option<Box<Fn(&T, &T) -> Ordering>>,
+}
+
+impl<T> TableColumn<T> {
+    /// Creates a new column with the given title.
+    ///
+    /// `label` computes the content of a cell from a row.
+    pub fn new<S, F>(title: S, label: F) -> Self
+        where S: Into<String>,
+              F: Fn(&T) -> String + 'static
+    {
+        TableColumn {
+            title: title.into(),
+            width: SizeConstraint::Free,
+            align: HAlign::Left,
+            label: Box::new(label),
+            compare: None,
+        }
+    }
+
+    /// Sets the width rule for this column.
+    ///
+    /// * `Free` uses the width of the widest cell (default).
+    /// * `Fixed`, `AtMost` and `AtLeast` set or bound this width.
+    /// * `Ratio` and `BoundedRatio` use a fraction of the table width.
+    /// * `Full` columns share the space left by the others.
+    pub fn width(mut self, width: SizeConstraint) -> Self {
+        self.width = width;
+
+        self
+    }
+
+    /// Sets the horizontal alignment of the cells in this column.
+    pub fn align(mut self, align: HAlign) -> Self {
+        self.align = align;
+
+        self
+    }
+
+    /// Sets the function used to sort rows by this column.
+    ///
+    /// By default, rows are sorted by comparing their labels.
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+        where F: Fn(&T, &T) -> Ordering + 'static
+    {
+        self.compare = Some(Box::new(compare));
+
+        self
+    }
+
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        match self.compare {
+            Some(ref compare) => compare(a, b),
+            None => (self.label)(a).cmp(&(self.label)(b)),
+        }
+    }
+
+    // Width needed to show the title and every cell.
+    fn natural_width(&self, items: &[Rc<T>]) -> usize {
+        items.iter()
+            .map(|item| (self.label)(item).width())
+            .fold(self.title.width() + SORT_MARK_WIDTH, max)
+    }
+}
+
+// Room for the sort direction next to the title.
+const SORT_MARK_WIDTH: usize = 2;
+
+// Blank and line between two columns.
+const SEPARATOR: &'static str = " │ ";
+const SEPARATOR_WIDTH: usize = 3;
+
+// Header line, and the rule below it.
+const HEADER_HEIGHT: usize = 2;
+
+/// View showing rows of values in named columns.
+///
+/// Rows can be sorted by any column: press `<Up>` on the first row to
+/// select the header, `<Left>` and `<Right>` to pick a column, and
+/// `<Enter>` to sort by it. Pressing `<Enter>` again reverses the order.
+///
+/// The header stays visible while scrolling.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::{TableColumn, TableView};
+/// # use cursive::view::SizeConstraint;
+/// # use cursive::align::HAlign;
+/// struct File {
+///     name: String,
+///     size: u64,
+/// }
+///
+/// let table = TableView::new()
+///     .column(TableColumn::new("Name", |f: &File| f.name.clone())
+///         .width(SizeConstraint::Full))
+///     .column(TableColumn::new("Size", |f: &File| f.size.to_string())
+///         .align(HAlign::Right)
+///         .sort_by(|a, b| a.size.cmp(&b.size)))
+///     .item(File { name: "Cargo.toml".to_string(), size: 1024 })
+///     .item(File { name: "README.md".to_string(), size: 4096 })
+///     .on_submit(|_, file| println!("{}", file.name));
+/// ```
+pub struct TableView<T> {
+    columns: Vec<TableColumn<T>>,
+    items: Vec<Rc<T>>,
+    enabled: bool,
+    focus: usize,
+    // `Some(column)` when the header is selected.
+    header_focus: Option<usize>,
+    // Column used to sort, and `true` if the order is reversed.
+    sort: Option<(usize, bool)>,
+    scrollbase: ScrollBase,
+    on_submit: Option<Rc<Fn(&mut Cursive, &T)>>,
+    on_select: Option<Rc<Fn(&mut Cursive, &T)>>,
+
+    // Width of each column, computed during layout.
+    widths: Vec<usize>,
+}
+
+impl<T: 'static> TableView<T> {
+    /// Creates a new, empty `TableView`.
+    pub fn new() -> Self {
+        TableView {
+            columns: Vec::new(),
+            items: Vec::new(),
+            enabled: true,
+            focus: 0,
+            header_focus: None,
+            sort: None,
+            scrollbase: ScrollBase::new(),
+            on_submit: None,
+            on_select: None,
+            widths: Vec::new(),
+        }
+    }
+
+    /// Adds a column to the table.
+    pub fn add_column(&mut self, column: TableColumn<T>) {
+        self.columns.push(column);
+    }
+
+    /// Adds a column to the table.
+    ///
+    /// Chainable variant.
+    pub fn column(self, column: TableColumn<T>) -> Self {
+        self.with(|s| s.add_column(column))
+    }
+
+    /// Disables this view.
+    ///
+    /// A disabled view cannot be selected.
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    /// Disables this view.
+    ///
+    /// Chainable variant.
+    pub fn disabled(self) -> Self {
+        self.with(Self::disable)
+    }
+
+    /// Re-enables this view.
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Enable or disable this view.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns `true` if this view is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets a callback to be used when a row is selected.
+    pub fn set_on_select<F>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.on_select = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when a row is selected.
+    ///
+    /// Chainable variant.
+    pub fn on_select<F>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.with(|s| s.set_on_select(cb))
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed on a row.
+    ///
+    /// The row currently selected will be given to the callback.
+    pub fn set_on_submit<F>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.on_submit = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed on a row.
+    ///
+    /// Chainable variant.
+    pub fn on_submit<F>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.with(|s| s.set_on_submit(cb))
+    }
+
+    /// Adds a row to the table.
+    ///
+    /// If the table is sorted, the row is inserted at its sorted position.
+    pub fn add_item(&mut self, item: T) {
+        self.items.push(Rc::new(item));
+        if let Some((column, reverse)) = self.sort {
+            self.sort_by_column(column, reverse);
+        }
+    }
+
+    /// Adds a row to the table.
+    ///
+    /// Chainable variant.
+    pub fn item(self, item: T) -> Self {
+        self.with(|s| s.add_item(item))
+    }
+
+    /// Adds all rows from an iterator.
+    pub fn add_all<I>(&mut self, iter: I)
+        where I: IntoIterator<Item = T>
+    {
+        self.items.extend(iter.into_iter().map(Rc::new));
+        if let Some((column, reverse)) = self.sort {
+            self.sort_by_column(column, reverse);
+        }
+    }
+
+    /// Adds all rows from an iterator.
+    ///
+    /// Chainable variant.
+    pub fn with_all<I>(self, iter: I) -> Self
+        where I: IntoIterator<Item = T>
+    {
+        self.with(|s| s.add_all(iter))
+    }
+
+    /// Removes a row from the table.
+    pub fn remove_item(&mut self, id: usize) {
+        self.items.remove(id);
+        if self.focus >= id && self.focus > 0 {
+            self.focus -= 1;
+        }
+    }
+
+    /// Removes all rows from this table.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.focus = 0;
+    }
+
+    /// Returns the row at the given position, if any.
+    pub fn get_item(&self, id: usize) -> Option<&T> {
+        self.items.get(id).map(|item| &**item)
+    }
+
+    /// Returns the number of rows in this table.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if this table has no row.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the currently selected row.
+    ///
+    /// Panics if the table is empty.
+    pub fn selection(&self) -> Rc<T> {
+        self.items[self.focus].clone()
+    }
+
+    /// Returns the position of the currently selected row.
+    ///
+    /// Returns `None` if the table is empty.
+    pub fn selected_id(&self) -> Option<usize> {
+        if self.items.is_empty() {
+            None
+        } else {
+            Some(self.focus)
+        }
+    }
+
+    /// Moves the selection to the given row.
+    pub fn set_selection(&mut self, id: usize) {
+        self.focus = min(id, self.items.len().saturating_sub(1));
+        self.scrollbase.scroll_to(self.focus);
+    }
+
+    /// Sorts the rows by the given column.
+    ///
+    /// The selected row stays selected. Rows added later are inserted at
+    /// their sorted position.
+    pub fn sort_by_column(&mut self, column: usize, reverse: bool) {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        {
+            let column = &self.columns[column];
+            let items = &self.items;
+            order.sort_by(|&a, &b| {
+                let ord = column.compare(&items[a], &items[b]);
+                if reverse { ord.reverse() } else { ord }
+            });
+        }
+
+        let focus = self.focus;
+        self.focus = order.iter().position(|&i| i == focus).unwrap_or(0);
+        self.items = order.iter().map(|&i| self.items[i].clone()).collect();
+        self.sort = Some((column, reverse));
+        self.scrollbase.scroll_to(self.focus);
+    }
+
+    /// Returns the column used to sort the rows, if any.
+    ///
+    /// The boolean is `true` if the order is reversed.
+    pub fn sorted_column(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    // Computes the width of each column, for a table `width` wide.
+    fn column_widths(&self, width: usize) -> Vec<usize> {
+        let separators = SEPARATOR_WIDTH *
+                         self.columns.len().saturating_sub(1);
+        let available = width.saturating_sub(separators);
+
+        let mut widths: Vec<usize> = self.columns
+            .iter()
+            .map(|column| match column.width {
+                SizeConstraint::Full => 0,
+                constraint => {
+                    let natural = column.natural_width(&self.items);
+                    let limit = constraint.available(available);
+                    constraint.result((min(natural, limit), limit))
+                }
+            })
+            .collect();
+
+        // Full columns share what's left.
+        let full: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| match self.columns[i].width {
+                SizeConstraint::Full => true,
+                _ => false,
+            })
+            .collect();
+        if !full.is_empty() {
+            let used = widths.iter().fold(0, |a, b| a + b);
+            let left = available.saturating_sub(used);
+            for (n, &i) in full.iter().enumerate() {
+                // The first columns get the remainder.
+                let extra = if n < left % full.len() { 1 } else { 0 };
+                widths[i] = left / full.len() + extra;
+            }
+        }
+
+        widths
+    }
+
+    fn draw_header(&self, printer: &Printer) {
+        let mut x = 0;
+        for (i, (column, &width)) in self.columns
+            .iter()
+            .zip(self.widths.iter())
+            .enumerate() {
+            if i > 0 {
+                printer.print((x, 0), SEPARATOR);
+                printer.print_hline((x, 1), SEPARATOR_WIDTH, "─");
+                printer.print((x + 1, 1), "┼");
+                x += SEPARATOR_WIDTH;
+            }
+
+            let mark = match self.sort {
+                Some((sorted, false)) if sorted == i => " ▲",
+                Some((sorted, true)) if sorted == i => " ▼",
+                _ => "",
+            };
+            let title = format!("{}{}", column.title, mark);
+            let selected = self.header_focus == Some(i);
+            let printer = printer.sub_printer((x, 0), (width, 2), true);
+            printer.with_selection(selected, |printer| {
+                let offset = column.align.get_offset(title.width(), width);
+                printer.print_hline((0, 0), width, " ");
+                printer.print((offset, 0), &title);
+            });
+            printer.print_hline((0, 1), width, "─");
+
+            x += width;
+        }
+    }
+
+    fn draw_row(&self, printer: &Printer, i: usize) {
+        printer.print_hline((0, 0), printer.size.x, " ");
+
+        let mut x = 0;
+        for (c, (column, &width)) in self.columns
+            .iter()
+            .zip(self.widths.iter())
+            .enumerate() {
+            if c > 0 {
+                printer.print((x, 0), SEPARATOR);
+                x += SEPARATOR_WIDTH;
+            }
+
+            let label = (column.label)(&self.items[i]);
+            let offset = column.align.get_offset(label.width(), width);
+            printer.sub_printer((x, 0), (width, 1), true)
+                .print((offset, 0), &label);
+
+            x += width;
+        }
+    }
+
+    // Moves the row selection, returning the `on_select` callback if any.
+    fn select(&mut self, focus: usize) -> EventResult {
+        self.focus = focus;
+        self.scrollbase.scroll_to(focus);
+
+        EventResult::Consumed(self.on_select.clone().map(|cb| {
+            let v = self.selection();
+            Callback::from_fn(move |s| cb(s, &v))
+        }))
+    }
+
+    fn on_header_event(&mut self, column: usize, event: Event)
+                       -> EventResult {
+        match event {
+            Event::Key(Key::Left) if column > 0 => {
+                self.header_focus = Some(column - 1)
+            }
+            Event::Key(Key::Right) if column + 1 < self.columns.len() => {
+                self.header_focus = Some(column + 1)
+            }
+            Event::Key(Key::Down) if !self.items.is_empty() => {
+                self.header_focus = None
+            }
+            Event::Key(Key::Enter) => {
+                let reverse = self.sort == Some((column, false));
+                self.sort_by_column(column, reverse);
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+}
+
+impl<T: 'static> View for TableView<T> {
+    fn draw(&self, printer: &Printer) {
+        self.draw_header(printer);
+
+        let printer = &printer.sub_printer((0, HEADER_HEIGHT),
+                                           printer.size,
+                                           self.header_focus.is_none());
+        self.scrollbase.draw(printer, |printer, i| {
+            printer.with_selection(i == self.focus, |printer| {
+                if i != self.focus && !self.enabled {
+                    printer.with_color(ColorStyle::Secondary,
+                                       |printer| self.draw_row(printer, i));
+                } else {
+                    self.draw_row(printer, i);
+                }
+            });
+        });
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        let h = self.items.len();
+        let scrolling = req.y < h + HEADER_HEIGHT;
+
+        let separators = SEPARATOR_WIDTH *
+                         self.columns.len().saturating_sub(1);
+        let w = self.columns
+            .iter()
+            .map(|column| {
+                let natural = column.natural_width(&self.items);
+                match column.width {
+                    SizeConstraint::Full => natural,
+                    constraint => {
+                        constraint.result((natural,
+                                           constraint.available(req.x)))
+                    }
+                }
+            })
+            .fold(separators, |a, b| a + b);
+
+        // Add 2 spaces for the scrollbar if we need
+        let w = if scrolling { w + 2 } else { w };
+
+        Vec2::new(w, h + HEADER_HEIGHT)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        let height = size.y.saturating_sub(HEADER_HEIGHT);
+        self.scrollbase.set_heights(height, self.items.len());
+
+        // Keep room for the scrollbar.
+        let width = if self.scrollbase.scrollable() {
+            size.x.saturating_sub(2)
+        } else {
+            size.x
+        };
+        self.widths = self.column_widths(width);
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if let Some(column) = self.header_focus {
+            return self.on_header_event(column, event);
+        }
+
+        if self.items.is_empty() {
+            return match event {
+                Event::Key(Key::Up) if !self.columns.is_empty() => {
+                    self.header_focus = Some(0);
+                    EventResult::Consumed(None)
+                }
+                _ => EventResult::Ignored,
+            };
+        }
+
+        let focus = self.focus;
+        let last = self.items.len() - 1;
+        match event {
+            Event::Key(Key::Up) if focus > 0 => self.select(focus - 1),
+            Event::Key(Key::Up) if !self.columns.is_empty() => {
+                self.header_focus =
+                    Some(self.sort.map(|(column, _)| column).unwrap_or(0));
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Down) if focus < last => self.select(focus + 1),
+            Event::Key(Key::PageUp) => {
+                self.select(focus - min(focus, 10))
+            }
+            Event::Key(Key::PageDown) => self.select(min(focus + 10, last)),
+            Event::Key(Key::Home) => self.select(0),
+            Event::Key(Key::End) => self.select(last),
+            Event::Key(Key::Enter) if self.on_submit.is_some() => {
+                let cb = self.on_submit.clone().unwrap();
+                let v = self.selection();
+                EventResult::with_cb(move |s| cb(s, &v))
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        self.enabled && !self.columns.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+    use view::SizeConstraint;
+
+    fn table() -> TableView<(&'static str, u32)> {
+        TableView::new()
+            .column(TableColumn::new("Name", |r: &(&str, u32)| {
+                    r.0.to_string()
+                })
+                .width(SizeConstraint::Full))
+            .column(TableColumn::new("Count", |r: &(&str, u32)| {
+                    r.1.to_string()
+                })
+                .width(SizeConstraint::Fixed(6))
+                .sort_by(|a, b| a.1.cmp(&b.1)))
+            .item(("b", 10))
+            .item(("a", 9))
+            .item(("c", 100))
+    }
+
+    #[test]
+    fn column_widths() {
+        let table = table();
+        assert_eq!(vec![11, 6], table.column_widths(20));
+
+        let table = TableView::new()
+            .column(TableColumn::new("Id", |r: &u32| r.to_string()))
+            .column(TableColumn::new("Name", |_: &u32| String::new())
+                .width(SizeConstraint::Ratio(0.5)))
+            .item(12345);
+        // "Id" + sort mark is narrower than "12345"
+        assert_eq!(vec![5, 11], table.column_widths(25));
+    }
+
+    #[test]
+    fn sort_keeps_selection() {
+        let mut table = table();
+        table.set_selection(2);
+
+        table.sort_by_column(0, false);
+        assert_eq!("a", table.get_item(0).unwrap().0);
+        assert_eq!("c", table.selection().0);
+
+        // Numeric sort, not by label
+        table.sort_by_column(1, true);
+        assert_eq!(vec![100, 10, 9],
+                   table.items.iter().map(|r| r.1).collect::<Vec<_>>());
+        assert_eq!(Some(0), table.selected_id());
+
+        table.add_item(("d", 50));
+        assert_eq!(("d", 50), *table.get_item(1).unwrap());
+        assert_eq!(("c", 100), *table.selection());
+    }
+
+    #[test]
+    fn header_navigation() {
+        let mut table = table();
+        table.on_event(Event::Key(Key::Up));
+        assert_eq!(Some(0), table.header_focus);
+
+        table.on_event(Event::Key(Key::Right));
+        table.on_event(Event::Key(Key::Enter));
+        assert_eq!(Some((1, false)), table.sorted_column());
+        table.on_event(Event::Key(Key::Enter));
+        assert_eq!(Some((1, true)), table.sorted_column());
+
+        table.on_event(Event::Key(Key::Down));
+        assert_eq!(None, table.header_focus);
+    }
+}
@@ -4,10 +4,21 @@ use {Printer, With, XY};
 use direction::Direction;
 use event::{Event, EventResult, Key};
 use odds::vec::VecExt;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::{max, min};
+use std::collections::BTreeMap;
+use std::iter;
+use std::ops::Range;
+use std::rc::Rc;
 use theme::{ColorStyle, Effect};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
-use utils::{LinesIterator, Row, prefix};
+use utils::{LinesIterator, Pattern, PatternError, Rope, Row, StyledString,
+            UndoHistory, prefix, skip_width};
+use utils::clipboard::{self, Clipboard};
+use utils::edit_mode::{EditMode, Editor};
+use utils::highlight::Highlighter;
 use vec::Vec2;
 use view::{ScrollBase, SizeCache, View};
 
@@ -16,87 +27,1189 @@ use view::{ScrollBase, SizeCache, View};
 /// A `TextArea` will attempt to grow vertically and horizontally
 /// dependent on the content.  Wrap it in a `BoxView` to
 /// constrain its size.
+///
+/// `Ctrl-F` opens a search prompt at the bottom of the view, and `Ctrl-R`
+/// a search and replace prompt. In the prompt:
+///
+/// * `Up` and `Down` (or `Ctrl-P` and `Ctrl-N`) jump between matches.
+/// * `Ctrl-T` toggles case sensitivity, and `Ctrl-E` regular expressions.
+/// * `Tab` switches between the search and the replacement.
+/// * `Enter` closes the prompt, or replaces the current match when typing
+///   the replacement, and `Ctrl-A` replaces all matches.
+/// * `Esc` cancels the search.
+///
+/// Once the prompt is closed, `F3` and `Shift-F3` still jump between
+/// matches, and `Esc` clears them.
+///
+/// An [`EditMode`] can change the key bindings, for instance to edit like
+/// in vi. Its indicator is then shown on the last row.
+///
+/// [`EditMode`]: ../utils/edit_mode/trait.EditMode.html
 pub struct TextArea {
-    // TODO: use a smarter data structure (rope?)
-    content: String,
+    content: Rope,
+
     /// Byte offsets within `content` representing text rows
-    rows: Vec<Row>,
+    rows: Shifted<Row>,
+
+    /// Index of the first row of each line.
+    lines: Shifted<usize>,
+
+    /// Number of rows of each width, to find the widest one.
+    row_widths: BTreeMap<usize, usize>,
+
+    /// When `true`, line numbers are shown on the left.
+    line_numbers: bool,
+
+    /// Gives the marker to show next to each line, if any.
+    line_marker: Option<Rc<Fn(usize) -> Option<StyledString>>>,
+
+    /// When `true`, the line containing the cursor is highlighted.
+    highlight_current_line: bool,
+
+    /// When `false`, long lines are not wrapped but scroll horizontally.
+    wrap: bool,
+
+    /// Styles the content, if any.
+    highlighter: Option<Box<Highlighter>>,
+
+    /// Highlighted lines, computed lazily when drawing.
+    ///
+    /// `None` for lines edited since they were last highlighted.
+    highlights: RefCell<Vec<Option<HighlightedLine>>>,
+
+    /// When `false`, we don't take any input.
+    enabled: bool,
+
+    /// Base for scrolling features
+    scrollbase: ScrollBase,
+
+    /// Cache to avoid re-computing layout on no-op events
+    last_size: Option<XY<SizeCache>>,
+
+    /// Byte offset of the currently selected grapheme.
+    cursor: usize,
+
+    /// Changes made to the content, to undo them.
+    history: UndoHistory,
+
+    /// Byte offset of the other end of the selection, if any.
+    ///
+    /// The selection spans from here to the cursor.
+    anchor: Option<usize>,
+
+    /// Used to cut, copy and paste text.
+    clipboard: Rc<RefCell<Clipboard>>,
+
+    /// Current search, highlighted in the text.
+    search: Option<Pattern>,
+
+    /// When `true`, searches use regular expressions.
+    search_regex: bool,
+
+    /// When `true`, searches ignore case.
+    search_case_insensitive: bool,
+
+    /// Search and replace prompt.
+    prompt: Prompt,
+
+    /// Handles events before the default key bindings, if any.
+    edit_mode: Option<Box<EditMode>>,
+}
+
+// A line styled by the highlighter.
+#[derive(Clone)]
+struct HighlightedLine {
+    // State at the end of the previous line.
+    state: usize,
+    text: StyledString,
+    // State at the end of this line.
+    next: usize,
+}
+
+// Search prompt, drawn at the bottom of the view.
+struct Prompt {
+    // `true` while the prompt is shown.
+    open: bool,
+    query: String,
+    replacement: String,
+    // `true` if the prompt has a replacement field.
+    replace: bool,
+    // `true` when typing in the replacement field.
+    replacing: bool,
+    // `true` if the query is not a valid pattern.
+    invalid: bool,
+    // Where the cursor was when the prompt opened.
+    origin: usize,
+}
+
+// Items shifted lazily after an edit.
+//
+// Items from `gap` on are stored `shift` below their actual value, with
+// wrapping arithmetic since the shift may be negative. An edit only moves
+// the gap to where it happens, so typing in one place doesn't update the
+// rest of a large document.
+struct Shifted<T> {
+    items: Vec<T>,
+    gap: usize,
+    shift: usize,
+}
+
+// Values which can be shifted.
+trait Offset: Copy {
+    // Adds `offset`, with wrapping arithmetic.
+    fn offset(self, offset: usize) -> Self;
+}
+
+impl Offset for usize {
+    fn offset(self, offset: usize) -> Self {
+        self.wrapping_add(offset)
+    }
+}
+
+impl Offset for Row {
+    fn offset(self, offset: usize) -> Self {
+        Row {
+            start: self.start.wrapping_add(offset),
+            end: self.end.wrapping_add(offset),
+            width: self.width,
+        }
+    }
+}
+
+impl<T: Offset> Shifted<T> {
+    fn new(items: Vec<T>) -> Self {
+        Shifted {
+            items: items,
+            gap: 0,
+            shift: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn get(&self, i: usize) -> T {
+        if i < self.gap {
+            self.items[i]
+        } else {
+            self.items[i].offset(self.shift)
+        }
+    }
+
+    #[cfg(test)]
+    fn to_vec(&self) -> Vec<T> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+
+    // Same as `binary_search_by_key`, on the actual values.
+    fn search<K, F>(&self, key: &K, f: F) -> Result<usize, usize>
+        where K: Ord,
+              F: Fn(T) -> K
+    {
+        let gap = self.gap;
+        match self.items[..gap].binary_search_by_key(key, |&item| f(item)) {
+            Err(i) if i == gap => {
+                let shift = self.shift;
+                match self.items[gap..]
+                    .binary_search_by_key(key, |&item| f(item.offset(shift))) {
+                    Ok(i) => Ok(gap + i),
+                    Err(i) => Err(gap + i),
+                }
+            }
+            result => result,
+        }
+    }
+
+    // Replaces the items in `range` with `items`, and adds `offset` to
+    // the following ones.
+    fn splice(&mut self, range: Range<usize>, items: Vec<T>, offset: usize) {
+        self.move_gap(range.start);
+        self.gap += items.len();
+        VecExt::splice(&mut self.items, range, items);
+        self.shift = self.shift.wrapping_add(offset);
+    }
+
+    // Moves the gap to `gap`, shifting the items in between.
+    fn move_gap(&mut self, gap: usize) {
+        let shift = self.shift;
+        if shift == 0 {
+            // Nothing to shift.
+        } else if gap > self.gap {
+            for item in &mut self.items[self.gap..gap] {
+                *item = item.offset(shift);
+            }
+        } else {
+            for item in &mut self.items[gap..self.gap] {
+                *item = item.offset(shift.wrapping_neg());
+            }
+        }
+        self.gap = gap;
+    }
+}
+
+// Returns `true` if `key` moves the cursor.
+fn is_movement(key: Key) -> bool {
+    match key {
+        Key::Left | Key::Right | Key::Up | Key::Down | Key::Home |
+        Key::End | Key::PageUp | Key::PageDown => true,
+        _ => false,
+    }
+}
+
+// Prints `text`, starting `x` cells from the beginning of the row, when
+// the first `scroll` cells of the row are hidden.
+fn print_scrolled(printer: &Printer, x: usize, text: &str, scroll: usize) {
+    if x >= scroll {
+        printer.print((x - scroll, 0), text);
+    } else if x + text.width() > scroll {
+        let skipped = skip_width(text, scroll - x);
+        printer.print((skipped.width - (scroll - x), 0),
+                      &text[skipped.length..]);
+    }
+}
+
+fn make_rows(text: &str, width: usize) -> Vec<Row> {
+    LinesIterator::new(text, width)
+        .show_spaces()
+        .collect()
+}
+
+new_default!(TextArea);
+
+impl TextArea {
+    /// Creates a new, empty TextArea.
+    pub fn new() -> Self {
+        TextArea {
+            content: Rope::new(),
+            rows: Shifted::new(Vec::new()),
+            lines: Shifted::new(Vec::new()),
+            row_widths: BTreeMap::new(),
+            line_numbers: false,
+            line_marker: None,
+            highlight_current_line: false,
+            wrap: true,
+            highlighter: None,
+            highlights: RefCell::new(Vec::new()),
+            enabled: true,
+            scrollbase: ScrollBase::new().right_padding(0),
+            last_size: None,
+            cursor: 0,
+            history: UndoHistory::new(),
+            anchor: None,
+            clipboard: clipboard::local(),
+            search: None,
+            search_regex: false,
+            search_case_insensitive: false,
+            prompt: Prompt {
+                open: false,
+                query: String::new(),
+                replacement: String::new(),
+                replace: false,
+                replacing: false,
+                invalid: false,
+                origin: 0,
+            },
+            edit_mode: None,
+        }
+    }
+
+    /// Retrieves the content of the view.
+    ///
+    /// The text is stored in chunks, so this has to copy all of it, unless
+    /// it fits in a single chunk.
+    #[deprecated(note = "copies the content, use `get_rope` instead")]
+    pub fn get_content(&self) -> Cow<str> {
+        self.content.slice(0, self.content.len())
+    }
+
+    /// Retrieves the content of the view, without copying it.
+    pub fn get_rope(&self) -> &Rope {
+        &self.content
+    }
+
+    fn invalidate(&mut self) {
+        self.last_size = None;
+    }
+
+    /// Sets the content of the view.
+    ///
+    /// This clears the undo history.
+    pub fn set_content<S: Into<String>>(&mut self, content: S) {
+        self.content = Rope::from(content.into());
+        self.cursor = 0;
+        self.anchor = None;
+        self.history.clear();
+        self.highlights.borrow_mut().clear();
+        self.rewrap();
+    }
+
+    /// Sets the content of the view.
+    ///
+    /// Chainable variant.
+    pub fn content<S: Into<String>>(self, content: S) -> Self {
+        self.with(|s| s.set_content(content))
+    }
+
+    /// Sets whether line numbers are shown on the left.
+    ///
+    /// When a line is wrapped, only its first row is numbered.
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+        self.invalidate();
+    }
+
+    /// Sets whether line numbers are shown on the left.
+    ///
+    /// Chainable variant.
+    pub fn line_numbers(self, line_numbers: bool) -> Self {
+        self.with(|s| s.set_line_numbers(line_numbers))
+    }
+
+    /// Sets a callback giving the marker to show next to each line.
+    ///
+    /// The callback is given the index of a line (starting at 0), and may
+    /// return a marker one cell wide, like an error sign, a breakpoint or a
+    /// diff mark.
+    pub fn set_line_marker<F>(&mut self, cb: F)
+        where F: Fn(usize) -> Option<StyledString> + 'static
+    {
+        self.line_marker = Some(Rc::new(cb));
+        self.invalidate();
+    }
+
+    /// Sets a callback giving the marker to show next to each line.
+    ///
+    /// Chainable variant.
+    pub fn line_marker<F>(self, cb: F) -> Self
+        where F: Fn(usize) -> Option<StyledString> + 'static
+    {
+        self.with(|s| s.set_line_marker(cb))
+    }
+
+    /// Sets whether the line containing the cursor is highlighted.
+    pub fn set_highlight_current_line(&mut self, highlight: bool) {
+        self.highlight_current_line = highlight;
+    }
+
+    /// Sets whether the line containing the cursor is highlighted.
+    ///
+    /// Chainable variant.
+    pub fn highlight_current_line(self, highlight: bool) -> Self {
+        self.with(|s| s.set_highlight_current_line(highlight))
+    }
+
+    /// Enable or disable line wrapping.
+    ///
+    /// When disabled, long lines extend beyond the view, which scrolls
+    /// horizontally to follow the cursor.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.invalidate();
+    }
+
+    /// Enable or disable line wrapping.
+    ///
+    /// Chainable variant.
+    pub fn wrap(self, wrap: bool) -> Self {
+        self.with(|s| s.set_wrap(wrap))
+    }
+
+    /// Sets the highlighter used to style the content.
+    ///
+    /// Lines are highlighted when they are first drawn, and only edited
+    /// lines are highlighted again.
+    ///
+    /// See [`TomlHighlighter`] for an example.
+    ///
+    /// [`TomlHighlighter`]: ../utils/highlight/struct.TomlHighlighter.html
+    pub fn set_highlighter<H: Highlighter + 'static>(&mut self,
+                                                     highlighter: H) {
+        self.highlighter = Some(Box::new(highlighter));
+        self.highlights.borrow_mut().clear();
+    }
+
+    /// Sets the highlighter used to style the content.
+    ///
+    /// Chainable variant.
+    pub fn highlighter<H: Highlighter + 'static>(self, highlighter: H)
+                                                 -> Self {
+        self.with(|s| s.set_highlighter(highlighter))
+    }
+
+    /// Removes the highlighter, showing the content without style.
+    pub fn clear_highlighter(&mut self) {
+        self.highlighter = None;
+        self.highlights.borrow_mut().clear();
+    }
+
+    /// Sets the editing mode, changing the key bindings.
+    ///
+    /// See [`ViMode`] and [`EmacsMode`].
+    ///
+    /// [`ViMode`]: ../utils/edit_mode/struct.ViMode.html
+    /// [`EmacsMode`]: ../utils/edit_mode/struct.EmacsMode.html
+    pub fn set_edit_mode<M: EditMode + 'static>(&mut self, mode: M) {
+        self.edit_mode = Some(Box::new(mode));
+        self.invalidate();
+    }
+
+    /// Sets the editing mode, changing the key bindings.
+    ///
+    /// Chainable variant.
+    pub fn edit_mode<M: EditMode + 'static>(self, mode: M) -> Self {
+        self.with(|s| s.set_edit_mode(mode))
+    }
+
+    /// Goes back to the default key bindings.
+    pub fn clear_edit_mode(&mut self) {
+        self.edit_mode = None;
+        self.invalidate();
+    }
+
+    /// Returns the indicator of the editing mode, if any.
+    pub fn edit_mode_indicator(&self) -> Option<&str> {
+        self.edit_mode.as_ref().and_then(|mode| mode.indicator())
+    }
+
+    /// Returns the number of lines in the content.
+    pub fn line_count(&self) -> usize {
+        self.content.line_count()
+    }
+
+    /// Returns the number of rows the text is wrapped in.
+    ///
+    /// Rows are only known once the view has been laid out.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the given row, if it exists.
+    ///
+    /// Rows are only known once the view has been laid out.
+    pub fn row(&self, row: usize) -> Option<Row> {
+        if row < self.rows.len() {
+            Some(self.rows.get(row))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the range of rows showing the given line, if it exists.
+    ///
+    /// Rows are only known once the view has been laid out.
+    pub fn line_rows(&self, line: usize) -> Option<Range<usize>> {
+        if line >= self.lines.len() {
+            return None;
+        }
+        let end = if line + 1 < self.lines.len() {
+            self.lines.get(line + 1)
+        } else {
+            self.rows.len()
+        };
+        Some(self.lines.get(line)..end)
+    }
+
+    /// Returns the line shown on the given row, if it exists.
+    ///
+    /// Rows are only known once the view has been laid out.
+    pub fn row_line(&self, row: usize) -> Option<usize> {
+        if row >= self.rows.len() {
+            return None;
+        }
+        Some(match self.lines.search(&row, |line| line) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        })
+    }
+
+    /// Returns the line containing the cursor.
+    pub fn cursor_line(&self) -> usize {
+        if self.rows.is_empty() {
+            return 0;
+        }
+        let row = self.selected_row();
+        self.row_line(row).unwrap_or(0)
+    }
+
+    /// Sets the clipboard used to cut, copy and paste.
+    ///
+    /// Defaults to the in-process clipboard shared by all views.
+    pub fn set_clipboard(&mut self, clipboard: Rc<RefCell<Clipboard>>) {
+        self.clipboard = clipboard;
+    }
+
+    /// Sets the clipboard used to cut, copy and paste.
+    ///
+    /// Chainable variant.
+    pub fn clipboard(self, clipboard: Rc<RefCell<Clipboard>>) -> Self {
+        self.with(|s| s.set_clipboard(clipboard))
+    }
+
+    /// Returns the byte range of the selected text, if any.
+    pub fn selected_range(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((min(anchor, self.cursor), max(anchor, self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the selected text, if any.
+    ///
+    /// Only a selection spanning several chunks of the content is copied.
+    pub fn selected_text(&self) -> Option<Cow<str>> {
+        self.selected_range()
+            .map(|(start, end)| self.content.slice(start, end))
+    }
+
+    /// Selects the entire content.
+    ///
+    /// Bound to `Ctrl-A`.
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.content.len();
+    }
+
+    /// Removes the selection, leaving the text unchanged.
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Copies the selected text to the clipboard.
+    ///
+    /// Bound to `Ctrl-C` when some text is selected.
+    pub fn copy(&mut self) {
+        if let Some(text) = self.selected_text() {
+            self.clipboard.borrow_mut().set(&text);
+        }
+    }
+
+    /// Moves the selected text to the clipboard.
+    ///
+    /// Bound to `Ctrl-X`.
+    pub fn cut(&mut self) {
+        self.copy();
+        self.delete_selection();
+    }
+
+    /// Replaces the selection with the content of the clipboard.
+    ///
+    /// Bound to `Ctrl-V`.
+    pub fn paste(&mut self) {
+        let text = self.clipboard.borrow_mut().get();
+        if let Some(text) = text {
+            self.delete_selection();
+            self.insert_str(&text);
+        }
+    }
+
+    // Removes the selected text, if any.
+    fn delete_selection(&mut self) {
+        let (start, end) = match self.selected_range() {
+            Some(range) => range,
+            None => return,
+        };
+
+        self.history.seal();
+        self.edit(start, end, "", start);
+        self.history.seal();
+        self.anchor = None;
+    }
+
+    // Inserts some text at the cursor.
+    fn insert_str(&mut self, text: &str) {
+        let cursor = self.cursor;
+        self.history.seal();
+        self.edit(cursor, cursor, text, cursor + text.len());
+        self.history.seal();
+    }
+
+    /// Sets whether searches use regular expressions.
+    ///
+    /// See [`Pattern`] for the supported syntax.
+    ///
+    /// [`Pattern`]: ../utils/struct.Pattern.html
+    pub fn set_search_regex(&mut self, regex: bool) {
+        self.search_regex = regex;
+        self.update_search().ok();
+    }
+
+    /// Sets whether searches use regular expressions.
+    ///
+    /// Chainable variant.
+    pub fn search_regex(self, regex: bool) -> Self {
+        self.with(|s| s.set_search_regex(regex))
+    }
+
+    /// Sets whether searches ignore case.
+    pub fn set_search_case_insensitive(&mut self, case_insensitive: bool) {
+        self.search_case_insensitive = case_insensitive;
+        self.update_search().ok();
+    }
+
+    /// Sets whether searches ignore case.
+    ///
+    /// Chainable variant.
+    pub fn search_case_insensitive(self, case_insensitive: bool) -> Self {
+        self.with(|s| s.set_search_case_insensitive(case_insensitive))
+    }
+
+    /// Searches for `query`, and selects the next match from the cursor.
+    ///
+    /// All matches are highlighted until [`clear_search`] is called.
+    ///
+    /// Returns `Ok(false)` if there is no match, or an error if `query` is
+    /// not a valid regular expression.
+    ///
+    /// [`clear_search`]: #method.clear_search
+    pub fn find(&mut self, query: &str) -> Result<bool, PatternError> {
+        self.prompt.query = query.to_string();
+        try!(self.update_search());
+        let from = self.selection_start();
+        Ok(self.select_match_from(from))
+    }
+
+    /// Selects the next match of the current search.
+    ///
+    /// Wraps around at the end of the content.
+    /// Returns `false` if there is no match.
+    ///
+    /// Bound to `F3`.
+    pub fn find_next(&mut self) -> bool {
+        let from = self.cursor;
+        self.select_match_from(from)
+    }
+
+    /// Selects the previous match of the current search.
+    ///
+    /// Wraps around at the beginning of the content.
+    /// Returns `false` if there is no match.
+    ///
+    /// Bound to `Shift-F3`.
+    pub fn find_previous(&mut self) -> bool {
+        let before = self.selection_start();
+        match self.prev_match(before) {
+            Some(found) => {
+                self.select_match(found);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the selected match with `replacement`, and selects the next
+    /// one.
+    ///
+    /// If the selection is not a match, only selects the next one.
+    /// Returns `true` if something was replaced.
+    pub fn replace_next(&mut self, replacement: &str) -> bool {
+        let current = self.selected_range().and_then(|range| {
+            let line_start = self.content.line_start(range.0);
+            if self.line_matches(line_start).contains(&range) {
+                Some(range)
+            } else {
+                None
+            }
+        });
+
+        if let Some((start, end)) = current {
+            self.history.seal();
+            self.edit(start, end, replacement, start + replacement.len());
+            self.history.seal();
+            self.anchor = None;
+        }
+        self.find_next();
+        current.is_some()
+    }
+
+    /// Replaces every match of the current search with `replacement`.
+    ///
+    /// This can be undone in a single step.
+    /// Returns the number of replaced matches.
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let mut matches = Vec::new();
+        let mut start = 0;
+        loop {
+            matches.extend(self.line_matches(start));
+            let end = self.content.line_end(start);
+            if end == self.content.len() {
+                break;
+            }
+            start = end + 1;
+        }
+
+        let (first, last) = match (matches.first(), matches.last()) {
+            (Some(first), Some(last)) => (first.0, last.1),
+            _ => return 0,
+        };
+
+        let mut text = String::new();
+        let mut pos = first;
+        for &(start, end) in &matches {
+            text.push_str(&self.content.slice(pos, start));
+            text.push_str(replacement);
+            pos = end;
+        }
+
+        self.history.seal();
+        self.edit(first, last, &text, first + text.len());
+        self.history.seal();
+        self.anchor = None;
+
+        matches.len()
+    }
+
+    /// Stops highlighting the matches of the current search.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    // Builds the search pattern from the query.
+    fn update_search(&mut self) -> Result<(), PatternError> {
+        self.search = None;
+        self.prompt.invalid = false;
+        if self.prompt.query.is_empty() {
+            return Ok(());
+        }
+
+        let pattern = if self.search_regex {
+            Pattern::new(&self.prompt.query)
+        } else {
+            Ok(Pattern::literal(&self.prompt.query))
+        };
+        match pattern {
+            Ok(pattern) => {
+                let case_insensitive = self.search_case_insensitive;
+                self.search =
+                    Some(pattern.case_insensitive(case_insensitive));
+                Ok(())
+            }
+            Err(err) => {
+                self.prompt.invalid = true;
+                Err(err)
+            }
+        }
+    }
+
+    // Returns the start of the selection, or the cursor.
+    fn selection_start(&self) -> usize {
+        self.selected_range()
+            .map(|(start, _)| start)
+            .unwrap_or(self.cursor)
+    }
+
+    fn select_match(&mut self, (start, end): (usize, usize)) {
+        self.anchor = Some(start);
+        self.cursor = end;
+    }
+
+    // Selects the first match starting at `from` or after.
+    fn select_match_from(&mut self, from: usize) -> bool {
+        match self.next_match(from) {
+            Some(found) => {
+                self.select_match(found);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Returns the matches in the line starting at `start`.
+    fn line_matches(&self, start: usize) -> Vec<(usize, usize)> {
+        match self.search {
+            None => Vec::new(),
+            Some(ref pattern) => {
+                pattern.matches(self.content.line(start))
+                    .into_iter()
+                    .map(|(a, b)| (start + a, start + b))
+                    .collect()
+            }
+        }
+    }
+
+    // Finds the first match starting at `from` or after.
+    fn next_match(&self, from: usize) -> Option<(usize, usize)> {
+        if self.search.is_none() {
+            return None;
+        }
+
+        let mut start = self.content.line_start(from);
+        let mut wrapped = false;
+        loop {
+            let found = self.line_matches(start)
+                .into_iter()
+                .find(|&(s, _)| wrapped || s >= from);
+            if found.is_some() {
+                return found;
+            }
+
+            let end = self.content.line_end(start);
+            if wrapped && end >= from {
+                return None;
+            }
+            if end == self.content.len() {
+                // Start again from the top
+                wrapped = true;
+                start = 0;
+            } else {
+                start = end + 1;
+            }
+        }
+    }
+
+    // Finds the last match starting before `before`.
+    fn prev_match(&self, before: usize) -> Option<(usize, usize)> {
+        if self.search.is_none() {
+            return None;
+        }
+
+        let mut start = self.content.line_start(before);
+        let mut wrapped = false;
+        loop {
+            let found = self.line_matches(start)
+                .into_iter()
+                .filter(|&(s, _)| wrapped || s < before)
+                .last();
+            if found.is_some() {
+                return found;
+            }
+
+            if wrapped && start <= before {
+                return None;
+            }
+            if start == 0 {
+                // Start again from the bottom
+                wrapped = true;
+                start = self.content.line_start(self.content.len());
+            } else {
+                start = self.content.line_start(start - 1);
+            }
+        }
+    }
+
+    fn open_prompt(&mut self, replace: bool) {
+        self.prompt.open = true;
+        self.prompt.replace = replace;
+        self.prompt.replacing = false;
+        self.prompt.origin = self.selection_start();
+        self.update_search().ok();
+        self.relayout();
+    }
+
+    fn close_prompt(&mut self) {
+        self.prompt.open = false;
+        self.relayout();
+    }
+
+    // Number of rows taken by the prompt, or by the mode indicator.
+    fn footer_height(&self) -> usize {
+        match (self.prompt.open, self.prompt.replace) {
+            (false, _) if self.edit_mode_indicator().is_some() => 1,
+            (false, _) => 0,
+            (true, false) => 1,
+            (true, true) => 2,
+        }
+    }
+
+    // Returns the size available for the text in the given view size.
+    fn text_size(&self, size: Vec2) -> Vec2 {
+        Vec2::new(size.x.saturating_sub(self.gutter_width()),
+                  size.y.saturating_sub(self.footer_height()))
+    }
+
+    // Number of columns taken by line numbers and markers.
+    fn gutter_width(&self) -> usize {
+        let mut width = 0;
+        if self.line_marker.is_some() {
+            width += 1;
+        }
+        if self.line_numbers {
+            width += self.line_count().to_string().len();
+        }
+        if width > 0 {
+            // Leave some space before the text.
+            width += 1;
+        }
+        width
+    }
+
+    fn draw_gutter(&self, printer: &Printer) {
+        let current_line = self.cursor_line();
+        let number_width = printer.size.x.saturating_sub(1);
+        let visible = min(self.scrollbase.view_height,
+                          self.rows.len() - self.scrollbase.start_line);
+        for y in 0..visible {
+            let row = self.scrollbase.start_line + y;
+            let line = match self.lines.search(&row, |line| line) {
+                Ok(line) => line,
+                // Continuation rows are left blank.
+                Err(_) => continue,
+            };
+
+            let mut x = 0;
+            if let Some(ref marker) = self.line_marker {
+                if let Some(marker) = marker(line) {
+                    printer.sub_printer((0, y), (1, 1), true)
+                        .print_styled((0, 0), &marker);
+                }
+                x += 1;
+            }
+
+            if self.line_numbers {
+                let color = if line == current_line {
+                    ColorStyle::Primary
+                } else {
+                    ColorStyle::Secondary
+                };
+                let number = format!("{:>1$}", line + 1, number_width - x);
+                printer.with_color(color, |printer| {
+                    printer.print((x, y), &number);
+                });
+            }
+        }
+    }
+
+    // Updates the layout after the prompt opened or closed.
+    fn relayout(&mut self) {
+        if let Some(size) = self.last_size.map(|s| s.map(|s| s.value)) {
+            let text_size = self.text_size(size);
+            if !self.wrap ||
+               (self.rows.len() > text_size.y) ==
+               self.scrollbase.scrollable() {
+                // No need to wrap the text again.
+                self.update_scrollbars(text_size);
+            } else {
+                self.rewrap();
+            }
+        }
+    }
+
+    // Searches again from where the prompt was opened.
+    fn search_incremental(&mut self) {
+        self.update_search().ok();
+        let origin = self.prompt.origin;
+        if !self.select_match_from(origin) {
+            self.anchor = None;
+            self.cursor = origin;
+        }
+    }
+
+    fn on_prompt_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Esc) => {
+                self.clear_search();
+                self.anchor = None;
+                self.cursor = self.prompt.origin;
+                self.close_prompt();
+            }
+            Event::Key(Key::Enter) if self.prompt.replacing => {
+                let replacement = self.prompt.replacement.clone();
+                self.replace_next(&replacement);
+            }
+            Event::Key(Key::Enter) => self.close_prompt(),
+            Event::Key(Key::Tab) if self.prompt.replace => {
+                self.prompt.replacing = !self.prompt.replacing;
+            }
+            Event::CtrlChar('a') if self.prompt.replace => {
+                let replacement = self.prompt.replacement.clone();
+                self.replace_all(&replacement);
+            }
+            Event::Key(Key::Down) |
+            Event::CtrlChar('n') => {
+                self.find_next();
+            }
+            Event::Key(Key::Up) |
+            Event::CtrlChar('p') => {
+                self.find_previous();
+            }
+            Event::CtrlChar('t') => {
+                self.search_case_insensitive = !self.search_case_insensitive;
+                self.search_incremental();
+            }
+            Event::CtrlChar('e') => {
+                self.search_regex = !self.search_regex;
+                self.search_incremental();
+            }
+            Event::Char(c) if self.prompt.replacing => {
+                self.prompt.replacement.push(c)
+            }
+            Event::Key(Key::Backspace) if self.prompt.replacing => {
+                self.prompt.replacement.pop();
+            }
+            Event::Char(c) => {
+                self.prompt.query.push(c);
+                self.search_incremental();
+            }
+            Event::Key(Key::Backspace) => {
+                self.prompt.query.pop();
+                self.search_incremental();
+            }
+            _ => return EventResult::Ignored,
+        }
 
-    /// When `false`, we don't take any input.
-    enabled: bool,
+        self.scroll_to_cursor();
 
-    /// Base for scrolling features
-    scrollbase: ScrollBase,
+        EventResult::Consumed(None)
+    }
 
-    /// Cache to avoid re-computing layout on no-op events
-    last_size: Option<XY<SizeCache>>,
+    // Draws the part of `range` inside `row` with the given color.
+    fn draw_range(&self, printer: &Printer, row: &Row,
+                  (start, end): (usize, usize), color: ColorStyle) {
+        let start = max(start, row.start);
+        let end = min(end, row.end);
+        if start < end {
+            let x = self.content.slice(row.start, start).width();
+            let text = self.content.slice(start, end);
+            let scroll = self.scrollbase.start_column;
+            printer.with_color(color, |printer| {
+                print_scrolled(printer, x, &text, scroll);
+            });
+        }
+    }
 
-    /// Byte offset of the currently selected grapheme.
-    cursor: usize,
-}
+    // Highlights the lines up to `last`, if they changed.
+    fn update_highlights(&self, last: usize) {
+        let highlighter = match self.highlighter {
+            Some(ref highlighter) => highlighter,
+            None => return,
+        };
 
-fn make_rows(text: &str, width: usize) -> Vec<Row> {
-    LinesIterator::new(text, width)
-        .show_spaces()
-        .collect()
-}
+        let mut highlights = self.highlights.borrow_mut();
+        highlights.resize(self.lines.len(), None);
+        let mut state = 0;
+        for line in 0..min(last + 1, self.lines.len()) {
+            let fresh = match highlights[line] {
+                Some(ref highlighted) => highlighted.state == state,
+                None => false,
+            };
+            if !fresh {
+                let start = self.rows.get(self.lines.get(line)).start;
+                let (text, next) =
+                    highlighter.highlight(self.content.line(start), state);
+                highlights[line] = Some(HighlightedLine {
+                    state: state,
+                    text: text,
+                    next: next,
+                });
+            }
+            state = highlights[line].as_ref().unwrap().next;
+        }
+    }
 
-new_default!(TextArea);
+    // Prints the text of the given row, highlighted if possible.
+    fn draw_row_text(&self, printer: &Printer, i: usize, text: &str) {
+        let scroll = self.scrollbase.start_column;
+        let highlights = self.highlights.borrow();
+        let line = self.row_line(i).unwrap();
+        let highlighted = match highlights.get(line) {
+            Some(&Some(ref highlighted)) => highlighted,
+            _ => return print_scrolled(printer, 0, text, scroll),
+        };
 
-impl TextArea {
-    /// Creates a new, empty TextArea.
-    pub fn new() -> Self {
-        TextArea {
-            content: String::new(),
-            rows: Vec::new(),
-            enabled: true,
-            scrollbase: ScrollBase::new().right_padding(0),
-            last_size: None,
-            cursor: 0,
+        // Spans are relative to the start of the line.
+        let mut row = self.rows.get(i);
+        row.rev_shift(self.rows.get(self.lines.get(line)).start);
+        let mut x = 0;
+        for (text, style) in highlighted.text.row_spans(&row) {
+            printer.with_style(style, |printer| {
+                print_scrolled(printer, x, text, scroll);
+            });
+            x += text.width();
         }
     }
 
-    /// Retrieves the content of the view.
-    pub fn get_content(&self) -> &str {
-        &self.content
-    }
+    fn draw_prompt(&self, printer: &Printer) {
+        let mut label = String::from("Find");
+        if self.search_regex {
+            label.push_str(" (regex)");
+        }
+        if self.search_case_insensitive {
+            label.push_str(" (ignore case)");
+        }
+        if self.prompt.invalid {
+            label.push_str(" (invalid)");
+        }
+        label.push_str(": ");
+
+        let mut fields =
+            vec![(label, &self.prompt.query, !self.prompt.replacing)];
+        if self.prompt.replace {
+            fields.push((String::from("Replace with: "),
+                         &self.prompt.replacement,
+                         self.prompt.replacing));
+        }
 
-    fn invalidate(&mut self) {
-        self.last_size = None;
+        for (y, (label, text, active)) in fields.into_iter().enumerate() {
+            printer.with_color(ColorStyle::Tertiary, |printer| {
+                printer.print((0, y), &label);
+            });
+            let x = label.width();
+            printer.print((x, y), text);
+            if printer.focused && active {
+                printer.with_effect(Effect::Reverse, |printer| {
+                    printer.print((x + text.width(), y), " ");
+                });
+            }
+        }
     }
 
-    /// Sets the content of the view.
-    pub fn set_content<S: Into<String>>(&mut self, content: S) {
-        self.content = content.into();
-        if let Some(size) = self.last_size.map(|s| s.map(|s| s.value)) {
-            self.compute_rows(size);
+    /// Reverts the last group of changes.
+    ///
+    /// Bound to `Ctrl-Z`.
+    pub fn undo(&mut self) {
+        self.anchor = None;
+        if let Some(change) = self.history.undo() {
+            self.replace(change.start, change.end, &change.text);
+            self.cursor = change.cursor;
         }
     }
 
-    /// Sets the content of the view.
+    /// Applies again the last undone group of changes.
     ///
-    /// Chainable variant.
-    pub fn content<S: Into<String>>(self, content: S) -> Self {
-        self.with(|s| s.set_content(content))
+    /// Bound to `Ctrl-Y`. On macOS and BSD, this is also the delayed suspend
+    /// character (DSUSP), which only reaches the application because the
+    /// terminal is in raw mode.
+    pub fn redo(&mut self) {
+        self.anchor = None;
+        if let Some(change) = self.history.redo() {
+            self.replace(change.start, change.end, &change.text);
+            self.cursor = change.cursor;
+        }
+    }
+
+    /// Forgets all changes made so far, so they cannot be undone.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    // Computes the rows again after the content changed.
+    fn rewrap(&mut self) {
+        let size = self.last_size.map(|s| s.map(|s| s.value));
+        self.invalidate();
+        if let Some(size) = size {
+            self.compute_rows(size);
+        }
     }
 
     /// Finds the row containing the grapheme at the given offset
     fn row_at(&self, offset: usize) -> usize {
-        // println_stderr!("Offset: {}", offset);
-        self.rows
-            .iter()
-            .enumerate()
-            .take_while(|&(_, row)| row.start <= offset)
-            .map(|(i, _)| i)
-            .last()
-            .unwrap()
+        match self.rows.search(&offset, |row| row.start) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        }
     }
 
     fn col_at(&self, offset: usize) -> usize {
         let row_id = self.row_at(offset);
-        let row = self.rows[row_id];
+        let row = self.rows.get(row_id);
         // Number of cells to the left of the cursor
-        self.content[row.start..offset].width()
+        self.content.slice(row.start, offset).width()
     }
 
     /// Finds the row containing the cursor
@@ -125,9 +1238,11 @@ impl TextArea {
         // Number of cells to the left of the cursor
         let x = self.col_at(self.cursor);
 
-        let prev_row = self.rows[row_id - 1];
-        let prev_text = &self.content[prev_row.start..prev_row.end];
-        let offset = prefix(prev_text.graphemes(true), x, "").length;
+        let prev_row = self.rows.get(row_id - 1);
+        let offset = {
+            let prev_text = self.content.slice(prev_row.start, prev_row.end);
+            prefix(prev_text.graphemes(true), x, "").length
+        };
         self.cursor = prev_row.start + offset;
     }
 
@@ -138,43 +1253,54 @@ impl TextArea {
         }
         let x = self.col_at(self.cursor);
 
-        let next_row = self.rows[row_id + 1];
-        let next_text = &self.content[next_row.start..next_row.end];
-        let offset = prefix(next_text.graphemes(true), x, "").length;
+        let next_row = self.rows.get(row_id + 1);
+        let offset = {
+            let next_text = self.content.slice(next_row.start, next_row.end);
+            prefix(next_text.graphemes(true), x, "").length
+        };
         self.cursor = next_row.start + offset;
     }
 
+    // Returns the length of the grapheme before `offset`.
+    fn prev_grapheme_len(&self, offset: usize) -> usize {
+        // We don't want to utf8-parse the entire content.
+        // So restrict to the row before the offset.
+        let start = self.rows.get(self.row_at(offset - 1)).start;
+        self.content
+            .slice(start, offset)
+            .graphemes(true)
+            .last()
+            .unwrap()
+            .len()
+    }
+
+    // Returns the length of the grapheme at `offset`.
+    fn next_grapheme_len(&self, offset: usize) -> usize {
+        let end = self.content.line_end(offset);
+        if end == offset {
+            // This is the newline.
+            return 1;
+        }
+        self.content
+            .slice(offset, end)
+            .graphemes(true)
+            .next()
+            .unwrap()
+            .len()
+    }
+
     /// Moves the cursor to the left.
     ///
     /// Wraps the previous line if required.
     fn move_left(&mut self) {
-        let len = {
-            // We don't want to utf8-parse the entire content.
-            // So restrict to the last row.
-            let mut row = self.selected_row();
-            if self.rows[row].start == self.cursor {
-                row -= 1;
-            }
-
-            let text = &self.content[self.rows[row].start..self.cursor];
-            text.graphemes(true)
-                .last()
-                .unwrap()
-                .len()
-        };
-        self.cursor -= len;
+        self.cursor -= self.prev_grapheme_len(self.cursor);
     }
 
     /// Moves the cursor to the right.
     ///
     /// Jumps to the next line is required.
     fn move_right(&mut self) {
-        let len = self.content[self.cursor..]
-            .graphemes(true)
-            .next()
-            .unwrap()
-            .len();
-        self.cursor += len;
+        self.cursor += self.next_grapheme_len(self.cursor);
     }
 
     fn is_cache_valid(&self, size: Vec2) -> bool {
@@ -184,15 +1310,40 @@ impl TextArea {
         }
     }
 
-    fn fix_ghost_row(&mut self) {
-        if self.rows.is_empty() ||
-           self.rows.last().unwrap().end != self.content.len() {
-            // Add a fake, empty row at the end.
-            self.rows.push(Row {
-                start: self.content.len(),
-                end: self.content.len(),
-                width: 0,
-            });
+    // Wraps the paragraphs between `start` and `end` in rows.
+    //
+    // `start` must be the beginning of a paragraph, and `end` the end of
+    // one. Every paragraph gets at least one row, even if it is empty.
+    //
+    // Also returns the index of the first row of each paragraph.
+    fn wrap_paragraphs(&self, start: usize, end: usize, width: usize)
+                       -> (Vec<Row>, Vec<usize>) {
+        // We need room for at least one cell.
+        let width = max(width, 1);
+        let mut rows = Vec::new();
+        let mut lines = Vec::new();
+        let mut pos = start;
+        loop {
+            let line = self.content.line(pos);
+            let count = rows.len();
+            lines.push(count);
+            rows.extend(make_rows(line, width)
+                .into_iter()
+                .map(|row| row.shifted(pos)));
+            if rows.len() == count {
+                rows.push(Row {
+                    start: pos,
+                    end: pos,
+                    width: 0,
+                });
+            }
+
+            pos += line.len();
+            if pos >= end {
+                return (rows, lines);
+            }
+            // Skip the newline
+            pos += 1;
         }
     }
 
@@ -200,165 +1351,282 @@ impl TextArea {
         if self.is_cache_valid(size) {
             return;
         }
-        // println_stderr!("Computing! Oh yeah!");
 
-        let mut available = size.x;
-        self.rows = make_rows(&self.content, available);
-        self.fix_ghost_row();
-        if self.rows.len() > size.y {
-            available -= 1;
+        self.last_size = Some(SizeCache::build(size, size));
+        let size = self.text_size(size);
+
+        let end = self.content.len();
+        let mut available = if self.wrap {
+            size.x
+        } else {
+            usize::max_value()
+        };
+        let (mut rows, mut lines) =
+            self.wrap_paragraphs(0, end, available);
+        if self.wrap && rows.len() > size.y {
+            available = available.saturating_sub(1);
             // Doh :(
-            self.rows = make_rows(&self.content, available);
-            self.fix_ghost_row();
+            let (new_rows, new_lines) =
+                self.wrap_paragraphs(0, end, available);
+            rows = new_rows;
+            lines = new_lines;
         }
-
-        if !self.rows.is_empty() {
-            self.last_size = Some(SizeCache::build(size, size));
+        self.row_widths.clear();
+        for row in &rows {
+            *self.row_widths.entry(row.width).or_insert(0) += 1;
         }
-        self.scrollbase.set_heights(size.y, self.rows.len());
-    }
+        self.rows = Shifted::new(rows);
+        self.lines = Shifted::new(lines);
 
-    fn backspace(&mut self) {
-        if self.cursor != 0 {
-            self.move_left();
-            self.delete();
-        }
+        self.update_scrollbars(size);
     }
 
-    fn delete(&mut self) {
-        if self.cursor == self.content.len() {
+    // Sets the scrollbars up for the current rows, in the given text size.
+    fn update_scrollbars(&mut self, size: Vec2) {
+        if self.wrap {
+            self.scrollbase.set_heights(size.y, self.rows.len());
+            self.scrollbase.set_widths(size.x, 0);
             return;
         }
-        // println_stderr!("Rows: {:?}", self.rows);
-        let len = self.content[self.cursor..]
-            .graphemes(true)
-            .next()
-            .unwrap()
-            .len();
-        let start = self.cursor;
-        let end = self.cursor + len;
-        // println_stderr!("Start/end: {}/{}", start, end);
-        // println_stderr!("Content: `{}`", self.content);
-        for _ in self.content.drain(start..end) {}
-        // println_stderr!("Content: `{}`", self.content);
-
-        let selected_row = self.selected_row();
-        // println_stderr!("Selected row: {}", selected_row);
-        if self.cursor == self.rows[selected_row].end {
-            // We're removing an (implicit) newline.
-            // This means merging two rows.
-            let new_end = self.rows[selected_row + 1].end;
-            self.rows[selected_row].end = new_end;
-            self.rows.remove(selected_row + 1);
-        }
-        self.rows[selected_row].end -= len;
-
-        // update all the rows downstream
-        for row in &mut self.rows.iter_mut().skip(1 + selected_row) {
-            row.rev_shift(len);
-        }
-        // println_stderr!("Rows: {:?}", self.rows);
-
-        self.fix_damages();
-        // println_stderr!("Rows: {:?}", self.rows);
-    }
 
-    fn insert(&mut self, ch: char) {
+        // Leave room for the cursor at the end of a line.
+        let content_width = 1 + self.max_row_width().unwrap_or(0);
 
-        // First, we inject the data, but keep the cursor unmoved
-        // (So the cursor is to the left of the injected char)
-        self.content.insert(self.cursor, ch);
+        // Each scrollbar takes some room, and may require the other one.
+        let mut h_scrollbar = false;
+        let mut v_scrollbar = false;
+        for _ in 0..2 {
+            v_scrollbar = self.rows.len() + h_scrollbar as usize > size.y;
+            h_scrollbar = content_width + v_scrollbar as usize > size.x;
+        }
 
-        // Then, we shift the indexes of every row after this one.
-        let shift = ch.len_utf8();
+        self.scrollbase
+            .set_heights(size.y.saturating_sub(h_scrollbar as usize),
+                         self.rows.len());
+        self.scrollbase
+            .set_widths(size.x.saturating_sub(v_scrollbar as usize),
+                        content_width);
+    }
 
-        // The current row grows, every other is just shifted.
-        let selected_row = self.selected_row();
-        self.rows[selected_row].end += shift;
+    // Returns the width of the widest row, if any.
+    fn max_row_width(&self) -> Option<usize> {
+        self.row_widths.keys().next_back().cloned()
+    }
 
-        for row in &mut self.rows.iter_mut().skip(1 + selected_row) {
-            row.shift(shift);
+    // Scrolls to keep the cursor visible.
+    fn scroll_to_cursor(&mut self) {
+        let focus = self.selected_row();
+        self.scrollbase.scroll_to(focus);
+        if !self.wrap {
+            let x = self.col_at(self.cursor);
+            self.scrollbase.scroll_to_column(x);
         }
-        self.cursor += shift;
+    }
 
-        // Finally, rows may not have the correct width anymore, so fix them.
-        self.fix_damages();
+    // Replaces some text, and records the change in the history.
+    //
+    // The cursor then moves to `cursor_after`.
+    fn edit(&mut self, start: usize, end: usize, text: &str,
+            cursor_after: usize) {
+        let removed = self.replace(start, end, text);
+        let cursor = self.cursor;
+        self.history.record(start, &removed, text, cursor, cursor_after);
+        self.cursor = cursor_after;
     }
 
-    /// Fix a damage located at the cursor.
-    ///
-    /// The only damages are assumed to have occured around the cursor.
-    fn fix_damages(&mut self) {
-        if self.last_size.is_none() {
-            // If we don't know our size, it means we'll get a layout command soon.
-            // So no need to do that here.
+    // Drops the highlighting of the lines about to change when replacing
+    // the text between `start` and `end` with `text`.
+    fn forget_highlights(&mut self, start: usize, end: usize, text: &str) {
+        if self.highlighter.is_none() {
             return;
         }
-
-        let size = self.last_size.unwrap().map(|s| s.value);
-
-        // Find affected text.
-        // We know the damage started at this row, so it'll need to go.
-        let mut first_row = self.selected_row();
-        // Actually, if possible, also re-compute the previous row.
-        // Indeed, the previous row may have been cut short, and if we now
-        // break apart a big word, maybe the first half can go up one level.
-        if first_row > 0 {
-            first_row -= 1;
+        if self.last_size.is_none() || self.rows.is_empty() {
+            // Rows may not match the content: we can't find the lines.
+            self.highlights.get_mut().clear();
+            return;
         }
-        // The
-        let first_byte = self.rows[first_row].start;
 
-        // We don't need to go beyond a newline.
-        // If we don't find one, end of the text it is.
-        // println_stderr!("Cursor: {}", self.cursor);
-        let last_byte = self.content[self.cursor..]
-            .find('\n')
-            .map(|i| 1 + i + self.cursor);
-        let last_row = last_byte.map_or(self.rows.len(), |last_byte| self.row_at(last_byte));
-        let last_byte = last_byte.unwrap_or_else(|| self.content.len());
+        let first = self.row_line(self.row_at(start)).unwrap();
+        let last = self.row_line(self.row_at(end)).unwrap();
+        let added = text.matches('\n').count();
+        let highlights = self.highlights.get_mut();
+        if last < highlights.len() {
+            VecExt::splice(highlights,
+                           first..last + 1,
+                           iter::repeat(None).take(added + 1));
+        } else {
+            highlights.truncate(first);
+        }
+    }
 
-        // println_stderr!("Content: `{}` (len={})",
-        //                 self.content,
-        //                 self.content.len());
-        // println_stderr!("start/end: {}/{}", first_byte, last_byte);
-        // println_stderr!("start/end rows: {}/{}", first_row, last_row);
+    // Replaces the text between `start` and `end`, and returns it.
+    //
+    // Only the rows of the affected paragraphs are wrapped again. The
+    // following rows and lines are shifted lazily, so the cost depends on
+    // the distance from the previous edit rather than the size of the text.
+    fn replace(&mut self, start: usize, end: usize, text: &str) -> String {
+        self.forget_highlights(start, end, text);
+        let gutter_width = self.gutter_width();
+        let removed = self.content.replace(start, end, text);
+
+        let size = match self.last_size {
+            // If we don't know our size, it means we'll get a layout
+            // command soon. So no need to do that here.
+            None => return removed,
+            Some(size) => size.map(|s| s.value),
+        };
+        if self.gutter_width() != gutter_width {
+            // The gutter grew or shrank, so every row changes.
+            self.invalidate();
+            self.compute_rows(size);
+            return removed;
+        }
+        let text_size = self.text_size(size);
+
+        // Paragraphs touched by the edit, in the new content...
+        let first_byte = self.content.line_start(start);
+        let last_byte = self.content.line_end(start + text.len());
+        // ... and the end of the last one in the old content.
+        let old_last_byte = last_byte + removed.len() - text.len();
+
+        // Rows of these paragraphs, in the old content.
+        let first_row = self.row_at(first_byte);
+        let last_row = match self.rows
+            .search(&(old_last_byte + 1), |row| row.start) {
+            Ok(i) | Err(i) => i,
+        };
 
         // Do we have access to the entire width?...
-        let mut available = size.x;
-
-        let scrollable = self.rows.len() > size.y;
-        if scrollable {
+        let scrollable = self.rows.len() > text_size.y;
+        let available = if !self.wrap {
+            usize::max_value()
+        } else if scrollable {
             // ... not if a scrollbar is there
-            available -= 1;
-        }
+            text_size.x.saturating_sub(1)
+        } else {
+            text_size.x
+        };
 
-        // First attempt, if scrollbase status didn't change.
-        // println_stderr!("Rows: {:?}", self.rows);
-        let new_rows = make_rows(&self.content[first_byte..last_byte],
-                                 available);
-        // How much did this add?
-        // println_stderr!("New rows: {:?}", new_rows);
-        // println_stderr!("{}-{}", first_row, last_row);
+        let (new_rows, new_lines) =
+            self.wrap_paragraphs(first_byte, last_byte, available);
         let new_row_count = self.rows.len() + new_rows.len() + first_row -
                             last_row;
-        if !scrollable && new_row_count > size.y {
+        if self.wrap && scrollable != (new_row_count > text_size.y) {
             // We just changed scrollable status.
             // This changes everything.
-            // TODO: compute_rows() currently makes a scroll-less attempt.
-            // Here, we know it's just no gonna happen.
             self.invalidate();
             self.compute_rows(size);
-            return;
+            return removed;
+        }
+
+        // Otherwise, replace the affected rows, and shift the next ones.
+        for i in first_row..last_row {
+            let width = self.rows.get(i).width;
+            let count = self.row_widths.get_mut(&width).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.row_widths.remove(&width);
+            }
+        }
+        for row in &new_rows {
+            *self.row_widths.entry(row.width).or_insert(0) += 1;
+        }
+        let next_row = first_row + new_rows.len();
+        self.rows.splice(first_row..last_row,
+                         new_rows,
+                         text.len().wrapping_sub(removed.len()));
+
+        // Same for the lines.
+        let first_line = self.row_line(first_row).unwrap();
+        let last_line = match self.lines.search(&last_row, |line| line) {
+            Ok(i) | Err(i) => i,
+        };
+        self.lines.splice(first_line..last_line,
+                          new_lines.into_iter()
+                              .map(|i| first_row + i)
+                              .collect(),
+                          next_row.wrapping_sub(last_row));
+        self.update_scrollbars(text_size);
+
+        removed
+    }
+
+    fn backspace(&mut self) {
+        let len = self.prev_grapheme_len(self.cursor);
+        let cursor = self.cursor;
+        self.edit(cursor - len, cursor, "", cursor - len);
+    }
+
+    fn delete(&mut self) {
+        let len = self.next_grapheme_len(self.cursor);
+        let cursor = self.cursor;
+        self.edit(cursor, cursor + len, "", cursor);
+    }
+
+    fn insert(&mut self, ch: char) {
+        let cursor = self.cursor;
+        let len = ch.len_utf8();
+        self.edit(cursor, cursor, &ch.to_string(), cursor + len);
+    }
+}
+
+impl Editor for TextArea {
+    fn content_len(&self) -> usize {
+        self.content.len()
+    }
+
+    fn text(&self, start: usize, end: usize) -> Cow<str> {
+        self.content.slice(start, end)
+    }
+
+    fn line_at(&self, pos: usize) -> (usize, &str) {
+        (self.content.line_start(pos), self.content.line(pos))
+    }
+
+    fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    fn anchor(&self) -> Option<usize> {
+        self.anchor
+    }
+
+    fn set_anchor(&mut self, anchor: Option<usize>) {
+        self.anchor = anchor;
+    }
+
+    fn move_up(&mut self) {
+        if !self.rows.is_empty() {
+            TextArea::move_up(self);
+        }
+    }
+
+    fn move_down(&mut self) {
+        if !self.rows.is_empty() {
+            TextArea::move_down(self);
         }
+    }
+
+    fn replace_range(&mut self, start: usize, end: usize, text: &str) {
+        self.history.seal();
+        self.edit(start, end, text, start + text.len());
+        self.history.seal();
+    }
+
+    fn undo(&mut self) {
+        TextArea::undo(self);
+    }
+
+    fn redo(&mut self) {
+        TextArea::redo(self);
+    }
 
-        // Otherwise, replace stuff.
-        let affected_rows = first_row..last_row;
-        let replacement_rows = new_rows.into_iter()
-            .map(|row| row.shifted(first_byte));
-        VecExt::splice(&mut self.rows, affected_rows, replacement_rows);
-        self.fix_ghost_row();
-        self.scrollbase.set_heights(size.y, self.rows.len());
+    fn get_clipboard(&self) -> Rc<RefCell<Clipboard>> {
+        self.clipboard.clone()
     }
 }
 
@@ -366,13 +1634,23 @@ impl View for TextArea {
     fn required_size(&mut self, constraint: Vec2) -> Vec2 {
         self.compute_rows(constraint);
         Vec2::new(
-            1 + self.rows.iter().map(|r| r.width).max().unwrap_or(1),
-            self.rows.len()
+            self.gutter_width() + 1 + self.max_row_width().unwrap_or(1),
+            self.rows.len() + self.footer_height()
         )
     }
 
     fn draw(&self, printer: &Printer) {
-        printer.with_color(ColorStyle::Secondary, |printer| {
+        let text_size = self.text_size(printer.size);
+        let gutter_width = self.gutter_width();
+        if gutter_width > 0 {
+            self.draw_gutter(&printer.sub_printer((0, 0),
+                                                  (gutter_width, text_size.y),
+                                                  true));
+        }
+
+        let text_printer =
+            printer.sub_printer((gutter_width, 0), text_size, true);
+        text_printer.with_color(ColorStyle::Secondary, |printer| {
             let effect = if self.enabled {
                 Effect::Reverse
             } else {
@@ -389,16 +1667,61 @@ impl View for TextArea {
                                     printer.print_hline((0, y), w, " ");
                                 });
 
+            let current_rows = if self.highlight_current_line &&
+                                  self.enabled {
+                self.line_rows(self.cursor_line())
+            } else {
+                None
+            };
+
+            let last_row = min(self.rows.len(),
+                               self.scrollbase.start_line +
+                               self.scrollbase.view_height);
+            if let Some(last_line) = last_row.checked_sub(1)
+                .and_then(|row| self.row_line(row)) {
+                self.update_highlights(last_line);
+            }
+
             // println_stderr!("Content: `{}`", &self.content);
             self.scrollbase.draw(printer, |printer, i| {
                 // println_stderr!("Drawing row {}", i);
-                let row = &self.rows[i];
+                let row = self.rows.get(i);
                 // println_stderr!("row: {:?}", row);
-                let text = &self.content[row.start..row.end];
+                let text = self.content.slice(row.start, row.end);
                 // println_stderr!("row text: `{}`", text);
-                printer.with_effect(effect, |printer| {
-                    printer.print((0, 0), text);
-                });
+                let current = current_rows.as_ref()
+                    .map(|rows| rows.start <= i && i < rows.end)
+                    .unwrap_or(false);
+                let scroll = self.scrollbase.start_column;
+                if current {
+                    printer.with_color(ColorStyle::Primary, |printer| {
+                        printer.with_effect(effect, |printer| {
+                            printer.print_hline((0, 0), printer.size.x, " ");
+                            self.draw_row_text(printer, i, &text);
+                        });
+                    });
+                } else {
+                    printer.with_effect(effect, |printer| {
+                        self.draw_row_text(printer, i, &text);
+                    });
+                }
+
+                if self.search.is_some() {
+                    let line_start = self.content.line_start(row.start);
+                    for range in self.line_matches(line_start) {
+                        self.draw_range(printer,
+                                       &row,
+                                        range,
+                                        ColorStyle::HighlightInactive);
+                    }
+                }
+
+                if let Some(range) = self.selected_range() {
+                    self.draw_range(printer,
+                                   &row,
+                                    range,
+                                    ColorStyle::Highlight);
+                }
 
                 if printer.focused && i == self.selected_row() {
                     let cursor_offset = self.cursor - row.start;
@@ -411,17 +1734,99 @@ impl View for TextArea {
                             .expect("Found no char!")
                     };
                     let offset = text[..cursor_offset].width();
-                    printer.print((offset, 0), c);
+                    print_scrolled(printer, offset, c, scroll);
                 }
 
             });
         });
+
+        if self.prompt.open {
+            let size = (printer.size.x, self.footer_height());
+            self.draw_prompt(&printer.sub_printer((0, text_size.y),
+                                                  size,
+                                                  true));
+        } else if let Some(indicator) = self.edit_mode_indicator() {
+            printer.with_color(ColorStyle::Tertiary, |printer| {
+                printer.print((0, text_size.y),
+                              &format!("-- {} --", indicator));
+            });
+        }
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        if self.prompt.open {
+            return self.on_prompt_event(event);
+        }
+
+        let footer_height = self.footer_height();
+        let actions = self.edit_mode
+            .as_mut()
+            .and_then(|mode| mode.on_event(&event));
+        if let Some(actions) = actions {
+            self.history.seal();
+            for action in actions {
+                self.apply(action);
+            }
+            if self.footer_height() != footer_height {
+                // The indicator appeared or disappeared.
+                self.relayout();
+            }
+            self.scroll_to_cursor();
+            return EventResult::Consumed(None);
+        }
+
+        // Moving the cursor around ends the current group of changes.
+        match event {
+            Event::Char(_) |
+            Event::Key(Key::Enter) |
+            Event::Key(Key::Backspace) |
+            Event::Key(Key::Del) => (),
+            _ => self.history.seal(),
+        }
+
+        // Shift + movement extends the selection.
+        let (event, extend) = match event {
+            Event::Shift(key) if is_movement(key) => (Event::Key(key), true),
+            Event::CtrlShift(key) if is_movement(key) => {
+                (Event::Ctrl(key), true)
+            }
+            event => (event, false),
+        };
+        let cursor = self.cursor;
+
         match event {
-            Event::Char(ch) => self.insert(ch),
-            Event::Key(Key::Enter) => self.insert('\n'),
+            Event::CtrlChar('z') => self.undo(),
+            Event::CtrlChar('y') => self.redo(),
+            Event::CtrlChar('a') => self.select_all(),
+            // Without a selection, let Ctrl-C quit.
+            Event::CtrlChar('c') if self.selected_range().is_some() => {
+                self.copy()
+            }
+            Event::CtrlChar('x') => self.cut(),
+            Event::CtrlChar('v') => self.paste(),
+            Event::CtrlChar('f') => self.open_prompt(false),
+            Event::CtrlChar('r') => self.open_prompt(true),
+            Event::Key(Key::F3) => {
+                self.find_next();
+            }
+            Event::Shift(Key::F3) => {
+                self.find_previous();
+            }
+            Event::Key(Key::Esc) if self.search.is_some() => {
+                self.clear_search()
+            }
+            Event::Char(ch) => {
+                self.delete_selection();
+                self.insert(ch);
+            }
+            Event::Key(Key::Enter) => {
+                self.delete_selection();
+                self.insert('\n');
+            }
+            Event::Key(Key::Backspace) |
+            Event::Key(Key::Del) if self.selected_range().is_some() => {
+                self.delete_selection()
+            }
             Event::Key(Key::Backspace) if self.cursor > 0 => self.backspace(),
             Event::Key(Key::Del) if self.cursor < self.content.len() => {
                 self.delete()
@@ -429,16 +1834,16 @@ impl View for TextArea {
 
             Event::Key(Key::End) => {
                 let row = self.selected_row();
-                self.cursor = self.rows[row].end;
+                self.cursor = self.rows.get(row).end;
                 if row + 1 < self.rows.len() &&
-                   self.cursor == self.rows[row + 1].start {
+                   self.cursor == self.rows.get(row + 1).start {
                     self.move_left();
                 }
             }
             Event::Ctrl(Key::Home) => self.cursor = 0,
             Event::Ctrl(Key::End) => self.cursor = self.content.len(),
             Event::Key(Key::Home) => {
-                self.cursor = self.rows[self.selected_row()].start
+                self.cursor = self.rows.get(self.selected_row()).start
             }
             Event::Key(Key::Up) if self.selected_row() > 0 => self.move_up(),
             Event::Key(Key::Down) if self.selected_row() + 1 <
@@ -452,9 +1857,20 @@ impl View for TextArea {
             _ => return EventResult::Ignored,
         }
 
+        match event {
+            Event::Key(key) |
+            Event::Ctrl(key) if is_movement(key) => {
+                if !extend {
+                    self.anchor = None;
+                } else if self.anchor.is_none() {
+                    self.anchor = Some(cursor);
+                }
+            }
+            _ => (),
+        }
+
         // println_stderr!("Rows: {:?}", self.rows);
-        let focus = self.selected_row();
-        self.scrollbase.scroll_to(focus);
+        self.scroll_to_cursor();
 
         EventResult::Consumed(None)
     }
@@ -467,3 +1883,283 @@ impl View for TextArea {
         self.compute_rows(size);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use utils::StyledString;
+    use utils::clipboard::LocalClipboard;
+    use vec::Vec2;
+    use view::View;
+
+    #[test]
+    fn select_cut_paste() {
+        let mut area = TextArea::new()
+            .content("hello world")
+            .clipboard(Rc::new(RefCell::new(LocalClipboard::new())));
+        area.layout(Vec2::new(20, 5));
+
+        area.on_event(Event::Shift(Key::Right));
+        area.on_event(Event::Shift(Key::Right));
+        assert_eq!(Some("he".into()), area.selected_text());
+
+        area.on_event(Event::CtrlChar('x'));
+        assert_eq!("llo world", area.get_rope().to_string());
+        assert_eq!(None, area.selected_text());
+
+        area.on_event(Event::Ctrl(Key::End));
+        area.on_event(Event::CtrlChar('v'));
+        assert_eq!("llo worldhe", area.get_rope().to_string());
+
+        // Typing replaces the selection.
+        area.on_event(Event::CtrlChar('a'));
+        area.on_event(Event::Char('x'));
+        assert_eq!("x", area.get_rope().to_string());
+
+        area.undo();
+        area.undo();
+        assert_eq!("llo worldhe", area.get_rope().to_string());
+    }
+
+    #[test]
+    fn find_replace() {
+        let mut area = TextArea::new().content("one two\nTwo three two");
+        area.layout(Vec2::new(20, 5));
+
+        assert_eq!(Ok(true), area.find("two"));
+        assert_eq!(Some((4, 7)), area.selected_range());
+        area.find_next();
+        assert_eq!(Some((18, 21)), area.selected_range());
+        area.find_next();
+        assert_eq!(Some((4, 7)), area.selected_range());
+
+        area.set_search_case_insensitive(true);
+        area.find_next();
+        assert_eq!(Some((8, 11)), area.selected_range());
+        area.find_previous();
+        assert_eq!(Some((4, 7)), area.selected_range());
+
+        assert_eq!(3, area.replace_all("2"));
+        assert_eq!("one 2\n2 three 2", area.get_rope().to_string());
+        area.undo();
+        assert_eq!("one two\nTwo three two", area.get_rope().to_string());
+
+        area.set_search_regex(true);
+        assert_eq!(Ok(true), area.find("t[a-z]+e"));
+        assert_eq!(Some("three".into()), area.selected_text());
+        assert!(area.replace_next("3"));
+        assert_eq!("one two\nTwo 3 two", area.get_rope().to_string());
+        assert!(area.find("(").is_err());
+
+        // Incremental search from the prompt
+        let mut area = TextArea::new().content("abc\nabd");
+        area.layout(Vec2::new(20, 5));
+        area.on_event(Event::CtrlChar('f'));
+        area.on_event(Event::Char('a'));
+        area.on_event(Event::Char('b'));
+        assert_eq!(Some((0, 2)), area.selected_range());
+        area.on_event(Event::Char('d'));
+        assert_eq!(Some((4, 7)), area.selected_range());
+        area.on_event(Event::Key(Key::Enter));
+        assert!(!area.prompt.open);
+    }
+
+    #[test]
+    fn line_rows() {
+        let mut area = TextArea::new()
+            .content("short\na longer line\n\nend")
+            .line_numbers(true);
+        area.layout(Vec2::new(12, 20));
+
+        // 4 lines take 1 digit, plus a space: 10 columns are left.
+        assert_eq!(2, area.gutter_width());
+        assert_eq!(4, area.line_count());
+        assert_eq!(Some(0..1), area.line_rows(0));
+        assert_eq!(Some(1..3), area.line_rows(1));
+        assert_eq!(Some(3..4), area.line_rows(2));
+        assert_eq!(None, area.line_rows(4));
+        assert_eq!(Some(1), area.row_line(2));
+        assert_eq!(Some(3), area.row_line(4));
+
+        for _ in 0..6 {
+            area.on_event(Event::Key(Key::Enter));
+        }
+        assert_eq!(10, area.line_count());
+        assert_eq!(3, area.gutter_width());
+        assert_eq!(6, area.cursor_line());
+        assert_eq!(Some(7..9), area.line_rows(7));
+        area.on_event(Event::Key(Key::Backspace));
+        assert_eq!(2, area.gutter_width());
+        assert_eq!(5, area.cursor_line());
+        assert_eq!(Some(6..8), area.line_rows(6));
+    }
+
+    #[test]
+    fn required_size_gutter() {
+        let mut area = TextArea::new()
+            .content("short\na longer line")
+            .line_numbers(true);
+
+        // The gutter takes 2 columns, and the cursor 1 after the text.
+        let size = area.required_size(Vec2::new(80, 20));
+        assert_eq!(Vec2::new(16, 2), size);
+
+        // Nothing wraps at that size.
+        area.layout(size);
+        assert_eq!(Some(1..2), area.line_rows(1));
+        assert_eq!(2, area.row_count());
+    }
+
+    #[test]
+    fn narrow_gutter() {
+        let content = vec!["line"; 200].join("\n");
+        for width in 1..6 {
+            let mut area = TextArea::new()
+                .content(content.clone())
+                .line_numbers(true);
+            area.layout(Vec2::new(width, 10));
+
+            // The gutter leaves little or no room for the text.
+            area.on_event(Event::Char('a'));
+            area.on_event(Event::Key(Key::Enter));
+            area.on_event(Event::Key(Key::Backspace));
+            assert!(area.get_rope().to_string().starts_with("aline"));
+        }
+    }
+
+    #[test]
+    fn no_wrap() {
+        let mut area = TextArea::new()
+            .content("a rather long line\nshort")
+            .wrap(false);
+        area.layout(Vec2::new(10, 5));
+
+        // One row per line, and a row for the horizontal scrollbar.
+        assert_eq!(2, area.row_count());
+        assert_eq!(4, area.scrollbase.view_height);
+        assert!(area.scrollbase.h_scrollable());
+
+        area.on_event(Event::Key(Key::End));
+        assert_eq!(9, area.scrollbase.start_column);
+        area.on_event(Event::Key(Key::Down));
+        assert_eq!(5, area.scrollbase.start_column);
+        area.on_event(Event::Key(Key::Home));
+        assert_eq!(0, area.scrollbase.start_column);
+
+        for _ in 0..15 {
+            area.on_event(Event::Char('x'));
+        }
+        assert_eq!(2, area.row_count());
+        assert_eq!(21, area.scrollbase.content_width);
+        assert_eq!(6, area.scrollbase.start_column);
+    }
+
+    #[test]
+    fn highlight_cache() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        // Counts the opened parentheses.
+        let highlighter = move |line: &str, state: usize| {
+            counter.set(counter.get() + 1);
+            let opened = line.matches('(').count();
+            let closed = line.matches(')').count();
+            (StyledString::plain(line), state + opened - closed)
+        };
+        let mut area = TextArea::new()
+            .content("a\nb\nc\nd")
+            .highlighter(highlighter);
+        area.layout(Vec2::new(10, 10));
+
+        area.update_highlights(3);
+        assert_eq!(4, calls.get());
+        area.update_highlights(3);
+        assert_eq!(4, calls.get());
+
+        // Only the edited line changes.
+        area.on_event(Event::Key(Key::Down));
+        area.on_event(Event::Char('x'));
+        area.update_highlights(3);
+        assert_eq!(5, calls.get());
+
+        // Splitting a line highlights both parts.
+        area.on_event(Event::Key(Key::Enter));
+        area.update_highlights(4);
+        assert_eq!(7, calls.get());
+
+        // A new state changes the next lines too.
+        area.on_event(Event::Char('('));
+        area.update_highlights(4);
+        assert_eq!(10, calls.get());
+        let highlights = area.highlights.borrow();
+        assert_eq!(5, highlights.len());
+        assert_eq!(1, highlights[4].as_ref().unwrap().state);
+    }
+
+    #[test]
+    fn incremental_rows() {
+        let line = "a few words to wrap
+
+";
+        let text: String = ::std::iter::repeat(line).take(500).collect();
+        let size = Vec2::new(10, 5);
+        let mut area = TextArea::new().content(text);
+        area.layout(size);
+
+        for _ in 0..40 {
+            area.on_event(Event::Key(Key::Down));
+        }
+        for c in "some more text\n".chars() {
+            area.on_event(Event::Char(c));
+        }
+        for _ in 0..8 {
+            area.on_event(Event::Key(Key::Backspace));
+        }
+        area.on_event(Event::Key(Key::Del));
+        area.undo();
+
+        let content = area.get_rope().to_string();
+        let mut expected = TextArea::new().content(content.as_str());
+        expected.layout(size);
+        assert_eq!(expected.rows.to_vec(), area.rows.to_vec());
+        assert_eq!(expected.lines.to_vec(), area.lines.to_vec());
+        assert_eq!(expected.row_widths, area.row_widths);
+
+        // The selection spans several chunks.
+        area.select_all();
+        assert_eq!(Some(content.as_str().into()), area.selected_text());
+        #[allow(deprecated)]
+        let flat = area.get_content();
+        assert_eq!(content, flat);
+    }
+
+    #[test]
+    fn scattered_edits() {
+        let line = "a few words to wrap\n";
+        let text: String = ::std::iter::repeat(line).take(300).collect();
+        let size = Vec2::new(10, 5);
+        for &wrap in &[true, false] {
+            let mut area = TextArea::new().content(text.as_str()).wrap(wrap);
+            area.layout(size);
+
+            // Each edit moves the gap backward or forward.
+            let edits = [(2000, 2000, "xx\nyy"), (10, 14, ""),
+                         (5000, 5003, "a much longer text"), (3, 3, "\n"),
+                         (4000, 4001, "")];
+            for &(start, end, text) in &edits {
+                area.replace_range(start, end, text);
+
+                let content = area.get_rope().to_string();
+                let mut expected = TextArea::new()
+                    .content(content.as_str())
+                    .wrap(wrap);
+                expected.layout(size);
+                assert_eq!(expected.rows.to_vec(), area.rows.to_vec());
+                assert_eq!(expected.lines.to_vec(), area.lines.to_vec());
+                assert_eq!(expected.row_widths, area.row_widths);
+            }
+        }
+    }
+}
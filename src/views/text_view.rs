@@ -7,13 +7,14 @@ use event::*;
 
 use unicode_width::UnicodeWidthStr;
 
-use utils::{LinesIterator, Row};
+use theme::Style;
+use utils::{LinesIterator, Row, StyledString, skip_width};
 use vec::Vec2;
 use view::{SizeCache, View, ScrollBase, ScrollStrategy};
 
 /// A simple view showing a fixed text
 pub struct TextView {
-    content: String,
+    content: StyledString,
     rows: Vec<Row>,
 
     align: Align,
@@ -21,6 +22,9 @@ pub struct TextView {
     // If `false`, disable scrolling.
     scrollable: bool,
 
+    // If `false`, long lines are not wrapped but scroll horizontally.
+    wrap: bool,
+
     // ScrollBase make many scrolling-related things easier
     scrollbase: ScrollBase,
     scroll_strategy: ScrollStrategy,
@@ -40,11 +44,21 @@ fn strip_last_newline(content: &str) -> &str {
 impl TextView {
     /// Creates a new TextView with the given content.
     pub fn new<S: Into<String>>(content: S) -> Self {
+        TextView::new_styled(content.into())
+    }
+
+    /// Creates a new TextView with the given styled content.
+    ///
+    /// See [`StyledString`].
+    ///
+    /// [`StyledString`]: ../utils/markup/struct.StyledString.html
+    pub fn new_styled<S: Into<StyledString>>(content: S) -> Self {
         let content = content.into();
         TextView {
             content: content,
             rows: Vec::new(),
             scrollable: true,
+            wrap: true,
             scrollbase: ScrollBase::new(),
             scroll_strategy: ScrollStrategy::KeepRow,
             align: Align::top_left(),
@@ -76,6 +90,22 @@ impl TextView {
         self.with(|s| s.set_scrollable(scrollable))
     }
 
+    /// Enable or disable line wrapping.
+    ///
+    /// When disabled, long lines extend beyond the view, which then
+    /// scrolls horizontally with the `Left` and `Right` keys.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.invalidate();
+    }
+
+    /// Enable or disable line wrapping.
+    ///
+    /// Chainable variant.
+    pub fn wrap(self, wrap: bool) -> Self {
+        self.with(|s| s.set_wrap(wrap))
+    }
+
     /// Sets the horizontal alignment for this view.
     pub fn h_align(mut self, h: HAlign) -> Self {
         self.align.h = h;
@@ -112,19 +142,43 @@ impl TextView {
 
     /// Replace the text in this view.
     pub fn set_content<S: Into<String>>(&mut self, content: S) {
-        let content = content.into();
-        self.content = content;
+        self.set_content_styled(content.into());
+    }
+
+    /// Replace the text in this view with some styled text.
+    ///
+    /// Chainable variant.
+    pub fn content_styled<S: Into<StyledString>>(self, content: S) -> Self {
+        self.with(|s| s.set_content_styled(content))
+    }
+
+    /// Replace the text in this view with some styled text.
+    pub fn set_content_styled<S: Into<StyledString>>(&mut self, content: S) {
+        self.content = content.into();
         self.invalidate();
     }
 
     /// Append content to the end of a TextView.
-    pub fn append_content(&mut self, content: &str) {
-        self.content.push_str(content);
+    pub fn append_content<S: Into<String>>(&mut self, content: S) {
+        self.append_content_styled(content.into());
+    }
+
+    /// Append styled content to the end of a TextView.
+    pub fn append_content_styled<S: Into<StyledString>>(&mut self,
+                                                        content: S) {
+        self.content.append(content.into());
         self.invalidate();
     }
 
     /// Returns the current text in this view.
+    ///
+    /// This is the text without any style.
     pub fn get_content(&self) -> &str {
+        self.content.source()
+    }
+
+    /// Returns the current styled text in this view.
+    pub fn get_styled_content(&self) -> &StyledString {
         &self.content
     }
 
@@ -179,11 +233,40 @@ impl TextView {
             return;
         }
 
+        let content = strip_last_newline(self.content.source());
+        if !self.wrap {
+            // One row per line, however long.
+            self.rows = LinesIterator::new(content, usize::max_value())
+                .collect();
+            self.update_scrollbars(size);
+
+            let scrollbar_width = if self.scrollbase.scrollable() {
+                2
+            } else {
+                0
+            };
+            let scrollbar_height = if self.scrollbase.h_scrollable() {
+                1
+            } else {
+                0
+            };
+            self.width = Some(self.scrollbase.content_width +
+                              scrollbar_width);
+
+            let mut my_size = Vec2::new(self.width.unwrap_or(0),
+                                        self.rows.len() + scrollbar_height);
+            if self.scrollable {
+                my_size = Vec2::min(my_size, size);
+            }
+
+            self.last_size = Some(SizeCache::build(my_size, size));
+            self.adjust_scroll();
+            return;
+        }
+
         // First attempt: naively hope that we won't need a scrollbar_width
         // (This means we try to use the entire available width for text).
-        self.rows = LinesIterator::new(strip_last_newline(&self.content),
-                                       size.x)
-            .collect();
+        self.rows = LinesIterator::new(content, size.x).collect();
 
         // Width taken by the scrollbar. Without a scrollbar, it's 0.
         let mut scrollbar_width = 0;
@@ -199,7 +282,8 @@ impl TextView {
 
             // If we're too high, include a scrollbar_width
             let available = size.x - scrollbar_width;
-            self.rows = LinesIterator::new(&self.content, available).collect();
+            self.rows = LinesIterator::new(self.content.source(), available)
+                .collect();
 
             if self.rows.is_empty() && !self.content.is_empty() {
                 // We have some content, we we didn't find any row for it?
@@ -233,6 +317,34 @@ impl TextView {
         self.adjust_scroll();
     }
 
+    // Sets the scrollbars up for the current rows and the given size.
+    fn update_scrollbars(&mut self, size: Vec2) {
+        let content_width = if self.wrap {
+            0
+        } else {
+            self.rows.iter().map(|row| row.width).max().unwrap_or(0)
+        };
+
+        // Each scrollbar takes some room, and may require the other one.
+        let mut h_scrollbar = false;
+        let mut v_scrollbar = false;
+        if self.scrollable {
+            for _ in 0..2 {
+                v_scrollbar = self.rows.len() + h_scrollbar as usize >
+                              size.y;
+                h_scrollbar = content_width + 2 * v_scrollbar as usize >
+                              size.x;
+            }
+        }
+
+        self.scrollbase
+            .set_heights(size.y.saturating_sub(h_scrollbar as usize),
+                         self.rows.len());
+        self.scrollbase
+            .set_widths(size.x.saturating_sub(2 * v_scrollbar as usize),
+                        content_width);
+    }
+
     // Invalidates the cache, so next call will recompute everything.
     fn invalidate(&mut self) {
         self.last_size = None;
@@ -240,6 +352,33 @@ impl TextView {
 }
 
 
+// Skips the first `width` cells of the given spans.
+//
+// Also returns the number of blank cells left when a wide character is cut.
+fn skip_spans(spans: Vec<(&str, Style)>, width: usize)
+              -> (usize, Vec<(&str, Style)>) {
+    let mut remaining = width;
+    let mut blank = 0;
+    let mut result = Vec::new();
+    for (text, style) in spans {
+        if remaining == 0 {
+            result.push((text, style));
+            continue;
+        }
+
+        let skipped = skip_width(text, remaining);
+        if skipped.width < remaining {
+            // The entire span is hidden.
+            remaining -= skipped.width;
+        } else {
+            blank = skipped.width - remaining;
+            remaining = 0;
+            result.push((&text[skipped.length..], style));
+        }
+    }
+    (blank, result)
+}
+
 impl View for TextView {
     fn draw(&self, printer: &Printer) {
 
@@ -250,19 +389,27 @@ impl View for TextView {
 
         self.scrollbase.draw(printer, |printer, i| {
             let row = &self.rows[i];
-            let text = &self.content[row.start..row.end];
-            let l = text.width();
+            let spans = self.content.row_spans(row);
+            let l = spans.iter().map(|&(text, _)| text.width()).sum();
             let x = self.align.h.get_offset(l, printer.size.x);
-            printer.print((x, 0), text);
+            let (blank, spans) = skip_spans(spans,
+                                            self.scrollbase.start_column);
+            printer.print_spans((x + blank, 0), &spans);
         });
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
-        if !self.scrollbase.scrollable() {
+        if !self.scrollbase.scrollable() && !self.scrollbase.h_scrollable() {
             return EventResult::Ignored;
         }
 
         match event {
+            Event::Key(Key::Left) if self.scrollbase.can_scroll_left() => {
+                self.scrollbase.scroll_left(1)
+            }
+            Event::Key(Key::Right) if self.scrollbase.can_scroll_right() => {
+                self.scrollbase.scroll_right(1)
+            }
             Event::Key(Key::Home) => self.scrollbase.scroll_top(),
             Event::Key(Key::End) => self.scrollbase.scroll_bottom(),
             Event::Key(Key::Up) if self.scrollbase.can_scroll_up() => {
@@ -290,20 +437,28 @@ impl View for TextView {
         // This is what we'd like
         let mut ideal = Vec2::new(self.width.unwrap_or(0), self.rows.len());
 
+        if self.scrollbase.h_scrollable() {
+            // Room for the horizontal scrollbar
+            ideal.y += 1;
+        }
+
         if self.scrollable && ideal.y > size.y {
             ideal.y = size.y;
         }
+        if self.scrollable && ideal.x > size.x {
+            ideal.x = size.x;
+        }
 
         ideal
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
-        self.scrollbase.scrollable()
+        self.scrollbase.scrollable() || self.scrollbase.h_scrollable()
     }
 
     fn layout(&mut self, size: Vec2) {
         // Compute the text rows.
         self.compute_rows(size);
-        self.scrollbase.set_heights(size.y, self.rows.len());
+        self.update_scrollbars(size);
     }
 }
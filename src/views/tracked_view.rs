@@ -2,7 +2,7 @@ use Printer;
 use std::cell::Cell;
 use vec::Vec2;
 
-use view::{View, ViewWrapper};
+use view::{View, ViewWrapper, child_draw};
 use views::IdView;
 
 /// Wrapper around a view that remembers its position.
@@ -40,6 +40,6 @@ impl<T: View> ViewWrapper for TrackedView<T> {
 
     fn wrap_draw(&self, printer: &Printer) {
         self.offset.set(printer.offset);
-        self.view.draw(printer);
+        child_draw(&self.view, printer);
     }
 }
@@ -0,0 +1,688 @@
+use Cursive;
+use Printer;
+use With;
+use direction::Direction;
+use event::{Callback, Event, EventResult, Key};
+use std::borrow::Borrow;
+use std::cmp::min;
+use std::rc::Rc;
+use theme::ColorStyle;
+use unicode_width::UnicodeWidthStr;
+use vec::Vec2;
+use view::{ScrollBase, View};
+
+/// View showing a tree of items, where each branch can be collapsed.
+///
+/// Items are identified by the id returned when they are added.
+///
+/// Use `<Right>` to expand a branch (or go to its first child), `<Left>` to
+/// collapse it (or go to its parent), and `<Enter>` to toggle it. `<Enter>`
+/// on a leaf calls the `on_submit` callback.
+///
+/// Children can be loaded lazily: mark an item as expandable, and add its
+/// children from the `on_load` callback, called the first time it is
+/// expanded.
+///
+/// # Examples
+///
+/// ```
+/// # use cursive::views::TreeView;
+/// let mut tree = TreeView::new();
+/// let src = tree.add_item("src", "src/");
+/// tree.add_child(src, "lib.rs", "src/lib.rs");
+/// let views = tree.add_child(src, "views", "src/views/");
+/// tree.set_expandable(views, true);
+///
+/// tree.set_on_load(|tree, id| {
+///     let path = *tree.get_item(id).unwrap();
+///     if path == "src/views/" {
+///         tree.add_child(id, "mod.rs", "src/views/mod.rs");
+///     }
+/// });
+/// ```
+pub struct TreeView<T> {
+    // Removed items are `None`, so ids stay valid.
+    nodes: Vec<Option<Node<T>>>,
+    roots: Vec<usize>,
+    // Visible rows, in order.
+    rows: Vec<Row>,
+    // `true` if items were added since `rows` was computed.
+    dirty: bool,
+    enabled: bool,
+    // Index in `rows`.
+    focus: usize,
+    scrollbase: ScrollBase,
+    on_submit: Option<Rc<Fn(&mut Cursive, &T)>>,
+    on_select: Option<Rc<Fn(&mut Cursive, &T)>>,
+    on_load: Option<Rc<Fn(&mut TreeView<T>, usize)>>,
+}
+
+struct Node<T> {
+    label: String,
+    value: Rc<T>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    // `true` if the node may have children not loaded yet.
+    expandable: bool,
+    expanded: bool,
+    loaded: bool,
+}
+
+impl<T> Node<T> {
+    fn is_branch(&self) -> bool {
+        self.expandable || !self.children.is_empty()
+    }
+}
+
+struct Row {
+    id: usize,
+    // Indentation guides, branch lines and expansion marker.
+    prefix: String,
+}
+
+impl<T: 'static> TreeView<T> {
+    /// Creates a new, empty `TreeView`.
+    pub fn new() -> Self {
+        TreeView {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            rows: Vec::new(),
+            dirty: false,
+            enabled: true,
+            focus: 0,
+            scrollbase: ScrollBase::new(),
+            on_submit: None,
+            on_select: None,
+            on_load: None,
+        }
+    }
+
+    /// Disables this view.
+    ///
+    /// A disabled view cannot be selected.
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    /// Disables this view.
+    ///
+    /// Chainable variant.
+    pub fn disabled(self) -> Self {
+        self.with(Self::disable)
+    }
+
+    /// Re-enables this view.
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Enable or disable this view.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns `true` if this view is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets a callback to be used when an item is selected.
+    pub fn set_on_select<F>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.on_select = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when an item is selected.
+    ///
+    /// Chainable variant.
+    pub fn on_select<F>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, &T) + 'static
+    {
+        self.with(|s| s.set_on_select(cb))
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed on a leaf.
+    ///
+    /// The item currently selected will be given to the callback.
+    ///
+    /// Here, `V` can be `T` itself, or a type that can be borrowed from `T`.
+    pub fn set_on_submit<F, V: ?Sized>(&mut self, cb: F)
+        where F: Fn(&mut Cursive, &V) + 'static,
+              T: Borrow<V>
+    {
+        self.on_submit = Some(Rc::new(move |s, t| cb(s, t.borrow())));
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed on a leaf.
+    ///
+    /// Chainable variant.
+    pub fn on_submit<F, V: ?Sized>(self, cb: F) -> Self
+        where F: Fn(&mut Cursive, &V) + 'static,
+              T: Borrow<V>
+    {
+        self.with(|s| s.set_on_submit(cb))
+    }
+
+    /// Sets a callback to load the children of an item.
+    ///
+    /// It is called the first time an item is expanded, with the id of this
+    /// item. It should add its children with `add_child`.
+    pub fn set_on_load<F>(&mut self, cb: F)
+        where F: Fn(&mut TreeView<T>, usize) + 'static
+    {
+        self.on_load = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to load the children of an item.
+    ///
+    /// Chainable variant.
+    pub fn on_load<F>(self, cb: F) -> Self
+        where F: Fn(&mut TreeView<T>, usize) + 'static
+    {
+        self.with(|s| s.set_on_load(cb))
+    }
+
+    /// Adds a top-level item, and returns its id.
+    pub fn add_item<S: Into<String>>(&mut self, label: S, value: T)
+                                     -> usize {
+        let id = self.new_node(label.into(), value, None);
+        self.roots.push(id);
+        self.dirty = true;
+        id
+    }
+
+    /// Adds a top-level item.
+    ///
+    /// Chainable variant.
+    pub fn item<S: Into<String>>(self, label: S, value: T) -> Self {
+        self.with(|s| {
+            s.add_item(label, value);
+        })
+    }
+
+    /// Adds a child to the item `parent`, and returns its id.
+    ///
+    /// New children are collapsed.
+    pub fn add_child<S: Into<String>>(&mut self, parent: usize, label: S,
+                                      value: T)
+                                      -> usize {
+        let id = self.new_node(label.into(), value, Some(parent));
+        self.node_mut(parent).children.push(id);
+        self.dirty = true;
+        id
+    }
+
+    /// Marks an item as having children, even if none was added yet.
+    ///
+    /// Children of such items can be loaded later by the `on_load`
+    /// callback.
+    pub fn set_expandable(&mut self, id: usize, expandable: bool) {
+        self.node_mut(id).expandable = expandable;
+        self.dirty = true;
+    }
+
+    /// Removes an item and all its children.
+    ///
+    /// `get_item` then returns `None` for them; their ids are not reused.
+    pub fn remove_item(&mut self, id: usize) {
+        match self.node(id).parent {
+            Some(parent) => {
+                self.node_mut(parent).children.retain(|&c| c != id)
+            }
+            None => self.roots.retain(|&r| r != id),
+        }
+        // Rebuild first, so a removed selection can still find its parent.
+        self.rebuild();
+
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes[id].take() {
+                stack.extend(node.children);
+            }
+        }
+    }
+
+    /// Removes all items from this view.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.rows.clear();
+        self.dirty = false;
+        self.focus = 0;
+    }
+
+    /// Returns the value of the given item, if it exists.
+    pub fn get_item(&self, id: usize) -> Option<&T> {
+        self.nodes
+            .get(id)
+            .and_then(|node| node.as_ref())
+            .map(|node| &*node.value)
+    }
+
+    /// Returns the parent of the given item, if any.
+    pub fn parent(&self, id: usize) -> Option<usize> {
+        self.node(id).parent
+    }
+
+    /// Returns the children of the given item.
+    pub fn children(&self, id: usize) -> &[usize] {
+        &self.node(id).children
+    }
+
+    /// Expands the given item, showing its children.
+    ///
+    /// Calls the `on_load` callback the first time.
+    pub fn expand(&mut self, id: usize) {
+        if !self.node(id).loaded {
+            self.node_mut(id).loaded = true;
+            if let Some(cb) = self.on_load.clone() {
+                cb(self, id);
+            }
+            // Nothing was loaded: this is just a leaf.
+            if self.node(id).children.is_empty() {
+                self.node_mut(id).expandable = false;
+            }
+        }
+
+        self.node_mut(id).expanded = self.node(id).is_branch();
+        self.rebuild();
+    }
+
+    /// Collapses the given item, hiding its children.
+    pub fn collapse(&mut self, id: usize) {
+        self.node_mut(id).expanded = false;
+        self.rebuild();
+    }
+
+    /// Expands or collapses the given item.
+    pub fn toggle(&mut self, id: usize) {
+        if self.node(id).expanded {
+            self.collapse(id);
+        } else {
+            self.expand(id);
+        }
+    }
+
+    /// Returns `true` if the given item is expanded.
+    pub fn is_expanded(&self, id: usize) -> bool {
+        self.node(id).expanded
+    }
+
+    /// Returns the value of the currently selected item.
+    ///
+    /// Panics if the tree is empty.
+    pub fn selection(&self) -> Rc<T> {
+        self.node(self.selected_id().unwrap()).value.clone()
+    }
+
+    /// Returns the id of the item currently selected.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn selected_id(&self) -> Option<usize> {
+        // Adding items doesn't move the selection, unless there was none.
+        self.rows
+            .get(self.focus)
+            .map(|row| row.id)
+            .or_else(|| self.roots.first().cloned())
+    }
+
+    /// Selects the given item, expanding its parents if needed.
+    pub fn set_selection(&mut self, id: usize) {
+        let mut parent = self.node(id).parent;
+        while let Some(p) = parent {
+            self.node_mut(p).expanded = true;
+            parent = self.node(p).parent;
+        }
+        self.rebuild();
+
+        if let Some(row) = self.rows.iter().position(|row| row.id == id) {
+            self.focus = row;
+            self.scrollbase.scroll_to(row);
+        }
+    }
+
+    /// Returns the number of visible items.
+    pub fn len(&self) -> usize {
+        if !self.dirty {
+            return self.rows.len();
+        }
+
+        let mut len = 0;
+        let mut stack = self.roots.clone();
+        while let Some(id) = stack.pop() {
+            len += 1;
+            let node = self.node(id);
+            if node.expanded {
+                stack.extend_from_slice(&node.children);
+            }
+        }
+        len
+    }
+
+    /// Returns `true` if this tree has no item.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    fn new_node(&mut self, label: String, value: T, parent: Option<usize>)
+                -> usize {
+        self.nodes.push(Some(Node {
+            label: label,
+            value: Rc::new(value),
+            parent: parent,
+            children: Vec::new(),
+            expandable: false,
+            expanded: false,
+            loaded: false,
+        }));
+        self.nodes.len() - 1
+    }
+
+    // Panics if the item was removed.
+    fn node(&self, id: usize) -> &Node<T> {
+        self.nodes[id].as_ref().expect("item was removed")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node<T> {
+        self.nodes[id].as_mut().expect("item was removed")
+    }
+
+    // Computes the visible rows again if items were added.
+    fn refresh(&mut self) {
+        if self.dirty {
+            self.rebuild();
+        }
+    }
+
+    // Computes the list of visible rows, keeping the selected item.
+    fn rebuild(&mut self) {
+        let selected = self.selected_id();
+
+        let mut rows = Vec::new();
+        for (i, &root) in self.roots.iter().enumerate() {
+            let last = i + 1 == self.roots.len();
+            self.push_rows(&mut rows, root, None, last);
+        }
+        self.rows = rows;
+        self.dirty = false;
+
+        // The selected item may be hidden now: select its visible parent.
+        let mut id = selected;
+        self.focus = 0;
+        while let Some(i) = id {
+            if let Some(row) = self.rows.iter().position(|row| row.id == i) {
+                self.focus = row;
+                break;
+            }
+            id = self.node(i).parent;
+        }
+
+        self.scrollbase.set_heights(self.scrollbase.view_height,
+                                    self.rows.len());
+    }
+
+    // `guides` is `None` for top-level items.
+    fn push_rows(&self, rows: &mut Vec<Row>, id: usize, guides: Option<&str>,
+                 last: bool) {
+        let node = self.node(id);
+        let marker = if !node.is_branch() {
+            if guides.is_some() { "─ " } else { "  " }
+        } else if node.expanded {
+            "▾ "
+        } else {
+            "▸ "
+        };
+
+        let (prefix, child_guides) = match guides {
+            None => (marker.to_string(), String::new()),
+            Some(guides) => {
+                let (branch, guide) = if last {
+                    ("└─", "  ")
+                } else {
+                    ("├─", "│ ")
+                };
+                (format!("{}{}{}", guides, branch, marker),
+                 format!("{}{}", guides, guide))
+            }
+        };
+
+        rows.push(Row {
+            id: id,
+            prefix: prefix,
+        });
+
+        if node.expanded {
+            for (i, &child) in node.children.iter().enumerate() {
+                let last = i + 1 == node.children.len();
+                self.push_rows(rows, child, Some(&child_guides), last);
+            }
+        }
+    }
+
+    fn draw_row(&self, printer: &Printer, i: usize) {
+        let row = &self.rows[i];
+        let label = &self.node(row.id).label;
+        let x = row.prefix.width();
+
+        printer.print_hline((0, 0), printer.size.x, " ");
+        printer.with_color(ColorStyle::Secondary,
+                           |printer| printer.print((0, 0), &row.prefix));
+        printer.with_selection(i == self.focus, |printer| {
+            if i != self.focus && !self.enabled {
+                printer.with_color(ColorStyle::Secondary,
+                                   |printer| printer.print((x, 0), label));
+            } else {
+                printer.print((x, 0), label);
+            }
+        });
+    }
+
+    // Moves the selection, returning the `on_select` callback if any.
+    fn select(&mut self, focus: usize) -> EventResult {
+        self.focus = focus;
+        self.scrollbase.scroll_to(focus);
+
+        EventResult::Consumed(self.on_select.clone().map(|cb| {
+            let v = self.selection();
+            Callback::from_fn(move |s| cb(s, &v))
+        }))
+    }
+
+    fn select_id(&mut self, id: usize) -> EventResult {
+        match self.rows.iter().position(|row| row.id == id) {
+            Some(row) => self.select(row),
+            None => EventResult::Ignored,
+        }
+    }
+}
+
+impl<T: 'static> View for TreeView<T> {
+    fn draw(&self, printer: &Printer) {
+        self.scrollbase.draw(printer, |printer, i| self.draw_row(printer, i));
+    }
+
+    fn required_size(&mut self, req: Vec2) -> Vec2 {
+        self.refresh();
+        let w = self.rows
+            .iter()
+            .map(|row| row.prefix.width() + self.node(row.id).label.width())
+            .max()
+            .unwrap_or(1);
+        let h = self.rows.len();
+
+        // Add 2 spaces for the scrollbar if we need
+        let w = if req.y < h { w + 2 } else { w };
+
+        Vec2::new(w, h)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.refresh();
+        self.scrollbase.set_heights(size.y, self.rows.len());
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        self.refresh();
+        let id = match self.selected_id() {
+            Some(id) => id,
+            None => return EventResult::Ignored,
+        };
+        let focus = self.focus;
+        let last = self.rows.len() - 1;
+        let (branch, expanded) = {
+            let node = self.node(id);
+            (node.is_branch(), node.expanded)
+        };
+
+        match event {
+            Event::Key(Key::Up) if focus > 0 => self.select(focus - 1),
+            Event::Key(Key::Down) if focus < last => self.select(focus + 1),
+            Event::Key(Key::PageUp) => {
+                self.select(focus - min(focus, 10))
+            }
+            Event::Key(Key::PageDown) => self.select(min(focus + 10, last)),
+            Event::Key(Key::Home) => self.select(0),
+            Event::Key(Key::End) => self.select(last),
+            Event::Key(Key::Right) if branch && !expanded => {
+                self.expand(id);
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Right) if branch => {
+                match self.node(id).children.first().cloned() {
+                    Some(child) => self.select_id(child),
+                    None => EventResult::Ignored,
+                }
+            }
+            Event::Key(Key::Left) if expanded => {
+                self.collapse(id);
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Left) => {
+                match self.node(id).parent {
+                    Some(parent) => self.select_id(parent),
+                    None => EventResult::Ignored,
+                }
+            }
+            Event::Key(Key::Enter) if branch => {
+                self.toggle(id);
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Enter) if self.on_submit.is_some() => {
+                let cb = self.on_submit.clone().unwrap();
+                let v = self.selection();
+                EventResult::with_cb(move |s| cb(s, &v))
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        self.enabled && !self.roots.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+
+    fn prefixes(tree: &mut TreeView<&'static str>) -> Vec<String> {
+        tree.refresh();
+        tree.rows.iter().map(|row| row.prefix.clone()).collect()
+    }
+
+    #[test]
+    fn guides() {
+        let mut tree = TreeView::new();
+        let a = tree.add_item("a", "a");
+        let b = tree.add_child(a, "b", "b");
+        tree.add_child(b, "c", "c");
+        tree.add_child(a, "d", "d");
+        tree.add_item("e", "e");
+
+        assert_eq!(vec!["▸ ", "  "], prefixes(&mut tree));
+
+        tree.expand(a);
+        tree.expand(b);
+        assert_eq!(vec!["▾ ", "├─▾ ", "│ └── ", "└── ", "  "],
+                   prefixes(&mut tree));
+    }
+
+    #[test]
+    fn lazy_loading() {
+        let mut tree = TreeView::new().on_load(|tree, id| {
+            if id == 0 {
+                tree.add_child(id, "child", "child");
+            }
+        });
+        let root = tree.add_item("root", "root");
+        let empty = tree.add_item("empty", "empty");
+        tree.set_expandable(root, true);
+        tree.set_expandable(empty, true);
+
+        tree.expand(root);
+        tree.expand(empty);
+        assert_eq!(3, tree.len());
+        assert!(tree.is_expanded(root));
+        assert!(!tree.is_expanded(empty));
+
+        // Only loaded once
+        tree.collapse(root);
+        tree.expand(root);
+        assert_eq!(1, tree.children(root).len());
+    }
+
+    #[test]
+    fn navigation() {
+        let mut tree = TreeView::new();
+        let a = tree.add_item("a", "a");
+        let b = tree.add_child(a, "b", "b");
+        tree.add_item("c", "c");
+
+        tree.on_event(Event::Key(Key::Right));
+        tree.on_event(Event::Key(Key::Right));
+        assert_eq!(Some(b), tree.selected_id());
+
+        tree.on_event(Event::Key(Key::Left));
+        assert_eq!(Some(a), tree.selected_id());
+        tree.on_event(Event::Key(Key::Left));
+        assert_eq!(2, tree.len());
+
+        // Collapsing a parent selects it.
+        tree.set_selection(b);
+        tree.collapse(a);
+        assert_eq!(Some(a), tree.selected_id());
+    }
+
+    #[test]
+    fn remove_subtree() {
+        let mut tree = TreeView::new();
+        let a = tree.add_item("a", "a");
+        let b = tree.add_child(a, "b", "b");
+        let c = tree.add_child(b, "c", "c");
+        let d = tree.add_item("d", "d");
+        tree.set_selection(c);
+        assert_eq!(4, tree.len());
+
+        tree.remove_item(b);
+        assert_eq!(None, tree.get_item(b));
+        assert_eq!(None, tree.get_item(c));
+        assert_eq!(Some(&"d"), tree.get_item(d));
+        assert!(tree.children(a).is_empty());
+        assert_eq!(Some(a), tree.selected_id());
+        assert_eq!(vec!["  ", "  "], prefixes(&mut tree));
+    }
+
+    #[test]
+    fn add_many() {
+        let mut tree = TreeView::new();
+        let root = tree.add_item("root", 0);
+        for i in 0..100_000 {
+            tree.add_child(root, "child", i);
+        }
+        assert_eq!(1, tree.len());
+        tree.expand(root);
+        assert_eq!(100_001, tree.len());
+    }
+}